@@ -4,7 +4,17 @@
 extern crate ranger;
 
 use ranger::{
-    nodes::{node_functions::NodeFunctions, scenes::scene_boot::SceneBoot},
+    assets::loader::Loader,
+    nodes::{
+        custom_nodes::cross_node::CrossNode,
+        node_functions::NodeFunctions,
+        scenes::{
+            scene_boot::SceneBoot,
+            scene_loading::SceneLoading,
+            scene_transition::{Effect, SceneTransition},
+        },
+    },
+    rendering::color::Palette,
     world::World,
 };
 
@@ -57,6 +67,11 @@ fn main() {
         }
     }
 
+    // Script the running game with: nc 127.0.0.1 7878
+    if let Err(err) = world.enable_debug_console(7878) {
+        println!("Debug console disabled: {}", err);
+    }
+
     match world.launch(build) {
         Ok(msg) => {
             println!("World: {}", msg);
@@ -69,13 +84,32 @@ fn main() {
 
 fn build(world: &mut World) -> bool {
     println!("Building...");
+    // Timing targets are registered automatically when a scene enters the stage.
     let game_scene = GameScene::new("GameScene", world);
 
-    NodeFunctions::register_timing_targets(&game_scene, world.node_manager_mut());
-
     let game_id = NodeFunctions::node_id(&game_scene);
 
-    let splash_scene = SplashScene::with_replacement("SplashScene", game_id, world);
+    // The scene description is applied while the loading scene runs.
+    // Edit and save the json while the game runs to rebuild the scene in place.
+    let mut loader = Loader::new();
+    loader.add_step(move |world, _files| {
+        world.register_node_type("CrossNode", CrossNode::new);
+        if let Err(err) = world.load_scene("examples/template_0/game_scene.json", &game_scene) {
+            println!("Scene description not loaded: {}", err);
+        }
+    });
+
+    // Fade through black from the splash scene into the game.
+    let fade = SceneTransition::new(
+        "FadeToGame",
+        Effect::Fade(Palette::BLACK()),
+        1.0,
+        game_id,
+        world,
+    );
+    let fade_id = NodeFunctions::node_id(&fade);
+
+    let splash_scene = SplashScene::with_replacement("SplashScene", fade_id, world);
     {
         let mut splash = splash_scene.borrow_mut();
 
@@ -85,13 +119,16 @@ fn build(world: &mut World) -> bool {
     }
     let splash_id = NodeFunctions::node_id(&splash_scene);
 
-    NodeFunctions::register_timing_targets(&splash_scene, world.node_manager_mut());
     {
         let splash = splash_scene.borrow();
         println!("{} ({})", splash.name(), splash_id);
     }
 
-    let boot_scene = SceneBoot::with_replacement("BootScene", splash_id, world);
+    let loading_scene = SceneLoading::new("LoadingScene", splash_id, loader.progress(), world);
+    let loading_id = NodeFunctions::node_id(&loading_scene);
+    world.start_loading(loader);
+
+    let boot_scene = SceneBoot::with_replacement("BootScene", loading_id, world);
     let boot_id = NodeFunctions::node_id(&boot_scene);
     {
         let boot = boot_scene.borrow();
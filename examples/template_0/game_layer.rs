@@ -3,9 +3,12 @@ use std::cell::{Cell, RefCell};
 use std::rc::Rc;
 
 use ranger::{
-    events::io_events::{IOEvent, IOEventData},
-    geometry::{aabb::AABBox, point::Point},
+    geometry::{
+        aabb::AABBox,
+        point::{Point, RVertices},
+    },
     nodes::{
+        components::rotator::Rotator,
         custom_nodes::transform_filter::TransformFilter,
         node_functions::NodeFunctions,
         node_group::NodeGroup,
@@ -100,6 +103,9 @@ impl GameLayer {
         if let Some(n) = brect.as_any_mut().downcast_mut::<RectangleNode>() {
             n.set_color(Palette::YELLOW());
         }
+
+        // Compose a continuous rotation rather than coding it into RectangleNode.
+        brect.add_component(Box::new(Rotator::new(3.0)), world.data());
     }
 
     fn build(layer: &mut GameLayer, world: &mut World) {
@@ -159,6 +165,10 @@ impl NodeTrait for GameLayer {
         // context.render_rectangle(&self.bucket);
     }
 
+    fn device_vertices(&self) -> Option<&RVertices> {
+        Some(&self.bucket)
+    }
+
     // --------------------------------------------------------
     // Transformations
     // --------------------------------------------------------
@@ -170,19 +180,6 @@ impl NodeTrait for GameLayer {
         self.parent.replace(parent);
     }
 
-    // --------------------------------------------------------
-    // IO Events
-    // --------------------------------------------------------
-    fn io_event(&self, io_event: &IOEventData) {
-        match io_event.event {
-            IOEvent::Mouse => {
-                if let Some(_children) = self.get_children() {}
-                // io_event.coord.0, io_event.coord.1
-            }
-            _ => (),
-        }
-    }
-
     // --------------------------------------------------------
     // Grouping
     // --------------------------------------------------------
@@ -8,13 +8,24 @@ use std::rc::Rc;
 extern crate ranger;
 
 use ranger::{
+    geometry::{path::Path, point::Point},
     nodes::{
-        custom_nodes::{cross_node::CrossNode, vector_text_node::VectorTextNode},
+        custom_nodes::{
+            cross_node::CrossNode, path_node::PathNode, polygon_node::PolygonNode,
+            sprite_node::SpriteNode, text_field_node::TextFieldNode, text_node::TextNode,
+            vector_text_node::VectorTextNode,
+        },
         node_functions::NodeFunctions,
         node_properties::{NodeData, RNodeData},
         node_trait::{NodeTrait, NodeType, OChildren, RNode},
     },
-    rendering::color::Palette,
+    rendering::{
+        color::Palette,
+        font::TextAlignment,
+        render_context::RenderStyle,
+        stroke::{LineCap, LineJoin, StrokeStyle},
+        texture::Texture,
+    },
     world::{GlobalData, World},
 };
 
@@ -72,6 +83,11 @@ impl GameScene {
         //         TriangleNode      <-- leaf
         //     YellowRectangle       <-- leaf
         //   WhiteText               <-- leaf
+        //   NameField               <-- text field (click or Tab to edit)
+        //   Checker                 <-- sprite
+        //   Rock                    <-- concave polygon with a hole
+        //   Heart                   <-- Bezier path
+        //   Caption                 <-- TrueType text, if the font is found
         //   CrossNode               <-- leaf
 
         let node_id = NodeFunctions::node_id(node);
@@ -84,14 +100,133 @@ impl GameScene {
         bword.set_scale(50.0);
         bword.set_position(0.0, 0.0);
         bword.set_rotation_degrees(45.0);
+        bword.set_stroke(Some(StrokeStyle {
+            width: 3.0,
+            anti_aliased: true,
+            cap: LineCap::Round,
+            join: LineJoin::Round,
+            ..StrokeStyle::default()
+        }));
 
         match bword.as_any().downcast_ref::<VectorTextNode>() {
             Some(vtn) => {
-                vtn.set_text(&String::from("RANGER IS A GO!"));
+                vtn.set_alignment(TextAlignment::Center);
+                vtn.set_text("RANGER IS A GO!\nVector text, now in lowercase.");
             }
             None => panic!("Downcast failed for VectorTextNode"),
         };
 
+        let field = TextFieldNode::new("NameField", node_id, world);
+        {
+            let bfield = field.borrow();
+            bfield.set_scale(25.0);
+            bfield.set_position(-300.0, -400.0);
+            if let Some(tf) = bfield.as_any().downcast_ref::<TextFieldNode>() {
+                tf.set_max_length(16);
+                tf.set_text("PLAYER 1");
+            }
+        }
+
+        // A texture made in code; files load with textures().load(path).
+        let size = 16;
+        let mut pixels = Vec::new();
+        for y in 0..size {
+            for x in 0..size {
+                if (x / 4 + y / 4) % 2 == 0 {
+                    pixels.extend_from_slice(&[255, 127, 0, 255]);
+                } else {
+                    pixels.extend_from_slice(&[0, 0, 0, 0]);
+                }
+            }
+        }
+        let checker = match Texture::from_rgba(size, size, pixels) {
+            Ok(texture) => world
+                .data()
+                .textures()
+                .borrow_mut()
+                .insert("checker", texture),
+            Err(err) => panic!("{}", err),
+        };
+
+        let sprite = SpriteNode::new("Checker", node_id, world);
+        {
+            let bsprite = sprite.borrow();
+            bsprite.set_scale(8.0);
+            bsprite.set_position(300.0, -300.0);
+            bsprite.set_rotation_degrees(30.0);
+            if let Some(sn) = bsprite.as_any().downcast_ref::<SpriteNode>() {
+                sn.set_texture(Some(checker));
+            }
+        }
+
+        let rock = PolygonNode::new("Rock", node_id, world);
+        {
+            let brock = rock.borrow();
+            brock.set_scale(20.0);
+            brock.set_position(-300.0, 300.0);
+            if let Some(pn) = brock.as_any().downcast_ref::<PolygonNode>() {
+                let points = |xy: &[(f64, f64)]| -> Vec<Point> {
+                    xy.iter().map(|&p| Point::from_tup(p)).collect()
+                };
+                let outline = points(&[
+                    (-5.0, -2.0),
+                    (-2.0, -5.0),
+                    (1.0, -3.0),
+                    (4.0, -4.0),
+                    (5.0, 1.0),
+                    (2.0, 5.0),
+                    (0.0, 2.0),
+                    (-4.0, 4.0),
+                ]);
+                let crater = points(&[(-2.0, -2.0), (1.0, -1.0), (-1.0, 1.0)]);
+                if let Err(err) = pn.set_polygon(&outline, &[crater]) {
+                    panic!("{}", err);
+                }
+                pn.set_style(RenderStyle::Both);
+                pn.set_fill_color(Palette::RGBA(96, 80, 64, 255));
+                pn.set_outline_color(Palette::RGBA(200, 180, 160, 255));
+            }
+        }
+
+        let heart = PathNode::new("Heart", node_id, world);
+        {
+            let bheart = heart.borrow();
+            bheart.set_scale(25.0);
+            bheart.set_position(300.0, 300.0);
+            if let Some(pn) = bheart.as_any().downcast_ref::<PathNode>() {
+                let mut path = Path::new();
+                path.move_to(0.0, 3.0);
+                path.cubic_to(-6.0, -1.0, -3.0, -6.0, 0.0, -3.0);
+                path.cubic_to(3.0, -6.0, 6.0, -1.0, 0.0, 3.0);
+                path.close();
+                pn.set_path(path);
+                pn.set_style(RenderStyle::Both);
+                pn.set_fill_color(Palette::RGBA(200, 40, 60, 255));
+            }
+        }
+
+        // Any TrueType or OpenType font will do.
+        let font = world
+            .data()
+            .fonts()
+            .borrow_mut()
+            .load("assets/fonts/DejaVuSans.ttf");
+        match font {
+            Ok(font) => {
+                let caption = TextNode::new("Caption", node_id, world);
+                let bcaption = caption.borrow();
+                bcaption.set_position(0.0, -450.0);
+                if let Some(tn) = bcaption.as_any().downcast_ref::<TextNode>() {
+                    tn.set_font(Some(font));
+                    tn.set_size(32.0);
+                    tn.set_alignment(TextAlignment::Center);
+                    tn.set_color(Palette::RGBA(255, 220, 120, 255));
+                    tn.set_text("Ranger\nTrueType text");
+                }
+            }
+            Err(err) => println!("No caption: {}", err),
+        }
+
         let cross = CrossNode::new("WhiteCross", node_id, world);
 
         let data = world.data();
@@ -4,7 +4,7 @@ use std::rc::Rc;
 
 use ranger::{
     animation::motion::AngularMotion,
-    geometry::point::Point,
+    geometry::point::{Point, RVertices},
     nodes::{
         custom_nodes::{orbit_anchor_node::OrbitAnchorNode, transform_filter::TransformFilter},
         node_functions::NodeFunctions,
@@ -170,6 +170,10 @@ impl NodeTrait for OrbitSystemNode {
         NodeFunctions::draw_aabb(&self.bucket.borrow(), context);
     }
 
+    fn device_vertices(&self) -> Option<&RVertices> {
+        Some(&self.bucket)
+    }
+
     fn interpolate(&self, interpolation: f64) {
         let value = self.angle_motion.borrow_mut().interpolate(interpolation);
         self.set_rotation_degrees(value);
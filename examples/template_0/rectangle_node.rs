@@ -3,9 +3,10 @@ use std::cell::{Cell, RefCell};
 use std::rc::Rc;
 
 use ranger::{
-    events::io_events::{IOEvent, IOEventData},
-    geometry::point::Point,
+    events::io_events::{EventPhase, EventResult, IOEvent, IOEventData, MouseAction},
+    geometry::point::{Point, RVertices},
     nodes::{
+        hit_test::HitShape,
         node_functions::NodeFunctions,
         node_group::NodeGroup,
         node_properties::{NodeData, RNodeData},
@@ -62,6 +63,9 @@ impl RectangleNode {
         let rc: RNode = Rc::new(RefCell::new(tn));
 
         world.data_mut().add_node(rc.clone());
+        world
+            .data_mut()
+            .register_io_event_target(rc.clone(), &[IOEvent::Mouse]);
 
         NodeGroup::attach_parent(&rc, world.data());
 
@@ -127,6 +131,10 @@ impl NodeTrait for RectangleNode {
         NodeFunctions::draw_aabb(&self.bucket.borrow(), context);
     }
 
+    fn device_vertices(&self) -> Option<&RVertices> {
+        Some(&self.bucket)
+    }
+
     // --------------------------------------------------------
     // Transformations
     // --------------------------------------------------------
@@ -141,19 +149,23 @@ impl NodeTrait for RectangleNode {
     // --------------------------------------------------------
     // IO Events
     // --------------------------------------------------------
-    fn io_event(&self, io_event: &IOEventData) {
-        match io_event.event {
-            IOEvent::Mouse => {
-                if let Some(_children) = self.get_children() {}
-                println!(
-                    "{}: {}, {}",
-                    self.name(),
-                    io_event.coord.0,
-                    io_event.coord.1
-                );
-            }
-            _ => (),
+    fn io_event(&self, phase: EventPhase, io_event: &IOEventData) -> EventResult {
+        // Button presses on the rectangle stop here.
+        if phase == EventPhase::Target && io_event.mouse_action() == Some(MouseAction::ButtonDown) {
+            println!(
+                "{}: {}, {}",
+                self.name(),
+                io_event.coord.0,
+                io_event.coord.1
+            );
+            return EventResult::Handled;
         }
+
+        EventResult::Unhandled
+    }
+
+    fn hit_shape(&self) -> HitShape {
+        HitShape::Polygon
     }
 
     // --------------------------------------------------------
@@ -4,7 +4,7 @@ use std::rc::Rc;
 
 use ranger::{
     animation::motion::AngularMotion,
-    geometry::point::Point,
+    geometry::point::{Point, RVertices},
     nodes::{
         node_functions::NodeFunctions,
         node_group::NodeGroup,
@@ -112,6 +112,10 @@ impl NodeTrait for TriangleNode {
         NodeFunctions::draw_aabb(&self.bucket.borrow(), context);
     }
 
+    fn device_vertices(&self) -> Option<&RVertices> {
+        Some(&self.bucket)
+    }
+
     fn interpolate(&self, interpolation: f64) {
         let value = self.angle_motion.borrow_mut().interpolate(interpolation);
 
@@ -0,0 +1,212 @@
+// Frame-by-frame animation: a Clip lists atlas frames and how long each is
+// shown, a FrameAnimation plays one clip at a time.
+//
+//   let mut walk = Clip::new(LoopMode::Loop);
+//   walk.add_frames(&[4, 5, 6, 7], 0.1);
+//
+// Times are in seconds.
+
+/// Shortest time a frame is shown.
+const MIN_FRAME_DURATION: f64 = 0.001;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum LoopMode {
+    // Plays to the last frame and stops there.
+    Once,
+    // Starts over after the last frame.
+    Loop,
+    // Plays forward then backward, repeating.
+    PingPong,
+}
+
+impl LoopMode {
+    pub fn from_name(name: &str) -> Option<LoopMode> {
+        match name {
+            "once" => Some(LoopMode::Once),
+            "loop" => Some(LoopMode::Loop),
+            "ping_pong" => Some(LoopMode::PingPong),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ClipEvent {
+    // A Loop or PingPong clip came back around to its first frame.
+    Looped,
+    // A Once clip showed its last frame for its full duration.
+    Completed,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Clip {
+    // (atlas frame index, seconds)
+    frames: Vec<(usize, f64)>,
+    mode: LoopMode,
+}
+
+impl Clip {
+    pub fn new(mode: LoopMode) -> Self {
+        Self {
+            frames: Vec::new(),
+            mode,
+        }
+    }
+
+    pub fn add_frame(&mut self, frame: usize, seconds: f64) {
+        self.frames.push((frame, seconds.max(MIN_FRAME_DURATION)));
+    }
+
+    /// Adds `frames` in order, each shown for `seconds`.
+    pub fn add_frames(&mut self, frames: &[usize], seconds: f64) {
+        for frame in frames.iter() {
+            self.add_frame(*frame, seconds);
+        }
+    }
+
+    pub fn frames(&self) -> &[(usize, f64)] {
+        &self.frames
+    }
+
+    pub fn mode(&self) -> LoopMode {
+        self.mode
+    }
+
+    pub fn set_mode(&mut self, mode: LoopMode) {
+        self.mode = mode;
+    }
+
+    /// Seconds to play the frames once.
+    pub fn duration(&self) -> f64 {
+        self.frames.iter().map(|(_, seconds)| seconds).sum()
+    }
+}
+
+/// Where playback is within a clip.
+#[derive(Debug, Clone)]
+pub struct FrameAnimation {
+    // Position in the clip's frame list
+    position: usize,
+    // Seconds the current frame has been shown
+    elapsed: f64,
+    // PingPong direction
+    forward: bool,
+    playing: bool,
+    // Playback rate, 1.0 = as authored
+    speed: f64,
+}
+
+impl Default for FrameAnimation {
+    fn default() -> Self {
+        Self {
+            position: 0,
+            elapsed: 0.0,
+            forward: true,
+            playing: false,
+            speed: 1.0,
+        }
+    }
+}
+
+impl FrameAnimation {
+    /// Starts from the clip's first frame.
+    pub fn restart(&mut self) {
+        self.position = 0;
+        self.elapsed = 0.0;
+        self.forward = true;
+        self.playing = true;
+    }
+
+    pub fn stop(&mut self) {
+        self.playing = false;
+    }
+
+    pub fn resume(&mut self) {
+        self.playing = true;
+    }
+
+    pub fn is_playing(&self) -> bool {
+        self.playing
+    }
+
+    pub fn speed(&self) -> f64 {
+        self.speed
+    }
+
+    /// Negative speeds are treated as 0.0.
+    pub fn set_speed(&mut self, speed: f64) {
+        self.speed = speed.max(0.0);
+    }
+
+    /// Position in the clip's frame list.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Shows the frame at `position` in the clip's frame list.
+    pub fn seek(&mut self, clip: &Clip, position: usize) {
+        self.position = position.min(clip.frames.len().saturating_sub(1));
+        self.elapsed = 0.0;
+    }
+
+    /// The atlas frame index being shown.
+    pub fn frame(&self, clip: &Clip) -> Option<usize> {
+        clip.frames.get(self.position).map(|(frame, _)| *frame)
+    }
+
+    /// Moves playback forward by `seconds`, returning the events that
+    /// happened along the way.
+    pub fn advance(&mut self, clip: &Clip, seconds: f64) -> Vec<ClipEvent> {
+        let mut events = Vec::new();
+        let count = clip.frames.len();
+
+        if !self.playing || count == 0 {
+            return events;
+        }
+
+        self.elapsed += seconds * self.speed;
+
+        while self.playing && self.elapsed >= clip.frames[self.position].1 {
+            self.elapsed -= clip.frames[self.position].1;
+
+            match clip.mode {
+                LoopMode::Once => {
+                    if self.position + 1 < count {
+                        self.position += 1;
+                    } else {
+                        self.elapsed = 0.0;
+                        self.playing = false;
+                        events.push(ClipEvent::Completed);
+                    }
+                }
+                LoopMode::Loop => {
+                    self.position += 1;
+                    if self.position == count {
+                        self.position = 0;
+                        events.push(ClipEvent::Looped);
+                    }
+                }
+                LoopMode::PingPong => {
+                    if count == 1 {
+                        events.push(ClipEvent::Looped);
+                    } else if self.forward {
+                        if self.position + 1 < count {
+                            self.position += 1;
+                        } else {
+                            self.forward = false;
+                            self.position -= 1;
+                        }
+                    } else if self.position > 0 {
+                        self.position -= 1;
+                        if self.position == 0 {
+                            self.forward = true;
+                            events.push(ClipEvent::Looped);
+                        }
+                    }
+                }
+            }
+        }
+
+        events
+    }
+}
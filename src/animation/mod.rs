@@ -1 +1,2 @@
-pub mod motion;
\ No newline at end of file
+pub mod frame_animation;
+pub mod motion;
@@ -0,0 +1,289 @@
+extern crate serde_json;
+
+use self::serde_json::Value;
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::rc::Rc;
+
+use animation::frame_animation::{Clip, LoopMode};
+use assets::texture_manager::TextureManager;
+use rendering::texture::{RTexture, TextureRegion};
+
+// A sprite sheet: one texture cut into frames, plus named clips that play
+// those frames in order.
+//
+// Atlases are built in code or read from a description file. Frames come
+// from a grid, named rectangles, or both. Grid frames are numbered left to
+// right, top to bottom and are also named by their number. A pivot is the
+// point of the frame, as a fraction of its size, placed at the node's
+// position.
+//
+//   {
+//     "image": "hero.png",            <-- relative to the description file
+//     "grid": { "width": 32, "height": 32, "margin": 0, "spacing": 0,
+//               "pivot": [0.5, 1.0] },
+//     "frames": {
+//       "jump": { "rect": [0, 96, 32, 48], "pivot": [0.5, 1.0] }
+//     },
+//     "clips": {
+//       "walk": { "frames": [0, 1, 2, 3], "duration": 0.1, "mode": "loop" },
+//       "land": { "frames": ["jump", 4], "durations": [0.2, 0.05], "mode": "once" }
+//     }
+//   }
+//
+// "mode" is "once", "loop" or "ping_pong" and defaults to "loop".
+
+pub type RAtlas = Rc<Atlas>;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct AtlasFrame {
+    pub region: TextureRegion,
+    pub pivot: (f64, f64),
+}
+
+pub struct Atlas {
+    texture: RTexture,
+
+    frames: Vec<AtlasFrame>,
+    names: HashMap<String, usize>,
+
+    clips: HashMap<String, Clip>,
+}
+
+impl Atlas {
+    pub fn new(texture: RTexture) -> Self {
+        Self {
+            texture,
+            frames: Vec::new(),
+            names: HashMap::new(),
+            clips: HashMap::new(),
+        }
+    }
+
+    /// Reads the description at `path`, loading its image through
+    /// `textures`.
+    pub fn from_file(path: &str, textures: &mut TextureManager) -> Result<Self, String> {
+        let text = match fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(err) => return Err(format!("{}: {}", path, err)),
+        };
+
+        let json = match serde_json::from_str::<Value>(&text) {
+            Ok(json) => json,
+            Err(err) => return Err(format!("{}: {}", path, err)),
+        };
+
+        let dir = Path::new(path).parent().unwrap_or_else(|| Path::new(""));
+
+        match Atlas::from_json(&json, dir, textures) {
+            Ok(atlas) => Ok(atlas),
+            Err(err) => Err(format!("{}: {}", path, err)),
+        }
+    }
+
+    /// Builds an atlas from a description whose image path is relative to
+    /// `dir`.
+    pub fn from_json(
+        json: &Value,
+        dir: &Path,
+        textures: &mut TextureManager,
+    ) -> Result<Self, String> {
+        let image = match json["image"].as_str() {
+            Some(image) => image,
+            None => return Err(String::from("atlas without an \"image\"")),
+        };
+
+        let texture = textures.load(&dir.join(image).to_string_lossy())?;
+        let mut atlas = Atlas::new(texture);
+
+        let grid = &json["grid"];
+        if !grid.is_null() {
+            let size = |name: &str| match grid[name].as_u64() {
+                Some(value) => Ok(value as u32),
+                None => Err(format!("\"grid\" needs a \"{}\"", name)),
+            };
+            let (width, height) = (size("width")?, size("height")?);
+            let margin = grid["margin"].as_u64().unwrap_or(0) as u32;
+            let spacing = grid["spacing"].as_u64().unwrap_or(0) as u32;
+            let pivot = pivot(&grid["pivot"], "grid")?;
+
+            atlas.add_grid(width, height, margin, spacing, pivot)?;
+        }
+
+        if let Some(frames) = json["frames"].as_object() {
+            for (name, frame) in frames.iter() {
+                let rect = &frame["rect"];
+                let region = match (
+                    rect[0].as_u64(),
+                    rect[1].as_u64(),
+                    rect[2].as_u64(),
+                    rect[3].as_u64(),
+                ) {
+                    (Some(x), Some(y), Some(w), Some(h)) => {
+                        TextureRegion::new(x as u32, y as u32, w as u32, h as u32)
+                    }
+                    _ => {
+                        return Err(format!(
+                            "frame '{}': \"rect\" should be [x, y, width, height]",
+                            name
+                        ))
+                    }
+                };
+
+                atlas.add_frame(name, region, pivot(&frame["pivot"], name)?)?;
+            }
+        }
+
+        if let Some(clips) = json["clips"].as_object() {
+            for (name, clip) in clips.iter() {
+                let clip = atlas
+                    .clip_from_json(clip)
+                    .map_err(|err| format!("clip '{}': {}", name, err))?;
+                atlas.add_clip(name, clip);
+            }
+        }
+
+        Ok(atlas)
+    }
+
+    fn clip_from_json(&self, json: &Value) -> Result<Clip, String> {
+        let mode = match json["mode"].as_str() {
+            Some(name) => match LoopMode::from_name(name) {
+                Some(mode) => mode,
+                None => return Err(format!("unknown mode '{}'", name)),
+            },
+            None => LoopMode::Loop,
+        };
+
+        let frames = match json["frames"].as_array() {
+            Some(frames) => frames,
+            None => return Err(String::from("\"frames\" should be a list")),
+        };
+
+        let durations = json["durations"].as_array();
+        let duration = json["duration"].as_f64();
+
+        let mut clip = Clip::new(mode);
+        for (i, frame) in frames.iter().enumerate() {
+            let index = match frame {
+                Value::Number(n) => n.as_u64().map(|n| n as usize),
+                Value::String(name) => self.frame_index(name),
+                _ => None,
+            };
+            let index = match index {
+                Some(index) if index < self.frames.len() => index,
+                _ => return Err(format!("unknown frame {}", frame)),
+            };
+
+            let seconds = match (durations.and_then(|d| d.get(i)), duration) {
+                (Some(seconds), _) => seconds.as_f64(),
+                (None, duration) => duration,
+            };
+            match seconds {
+                Some(seconds) => clip.add_frame(index, seconds),
+                None => return Err(format!("no duration for frame {}", frame)),
+            }
+        }
+
+        Ok(clip)
+    }
+
+    // --------------------------------------------------------
+    // Frames
+    // --------------------------------------------------------
+    /// Cuts the texture into `width` x `height` frames. `margin` is the
+    /// border around the sheet and `spacing` the gap between frames, in
+    /// texels. Returns the index of the first frame added.
+    pub fn add_grid(
+        &mut self,
+        width: u32,
+        height: u32,
+        margin: u32,
+        spacing: u32,
+        pivot: (f64, f64),
+    ) -> Result<usize, String> {
+        if width == 0 || height == 0 {
+            return Err(String::from("grid frames need a width and height"));
+        }
+
+        let first = self.frames.len();
+        let (sheet_width, sheet_height) = (self.texture.width(), self.texture.height());
+
+        let mut y = margin;
+        while y + height <= sheet_height.saturating_sub(margin) {
+            let mut x = margin;
+            while x + width <= sheet_width.saturating_sub(margin) {
+                let index = self.frames.len();
+                let region = TextureRegion::new(x, y, width, height);
+                self.add_frame(&index.to_string(), region, pivot)?;
+                x += width + spacing;
+            }
+            y += height + spacing;
+        }
+
+        Ok(first)
+    }
+
+    /// Adds a named frame and returns its index.
+    pub fn add_frame(
+        &mut self,
+        name: &str,
+        region: TextureRegion,
+        pivot: (f64, f64),
+    ) -> Result<usize, String> {
+        if region.x + region.width > self.texture.width()
+            || region.y + region.height > self.texture.height()
+        {
+            return Err(format!("frame '{}' is outside the texture", name));
+        }
+
+        let index = self.frames.len();
+        self.frames.push(AtlasFrame { region, pivot });
+        self.names.insert(name.to_string(), index);
+
+        Ok(index)
+    }
+
+    pub fn texture(&self) -> &RTexture {
+        &self.texture
+    }
+
+    pub fn frame(&self, index: usize) -> Option<&AtlasFrame> {
+        self.frames.get(index)
+    }
+
+    pub fn frame_index(&self, name: &str) -> Option<usize> {
+        self.names.get(name).cloned()
+    }
+
+    pub fn frame_count(&self) -> usize {
+        self.frames.len()
+    }
+
+    // --------------------------------------------------------
+    // Clips
+    // --------------------------------------------------------
+    pub fn add_clip(&mut self, name: &str, clip: Clip) {
+        self.clips.insert(name.to_string(), clip);
+    }
+
+    pub fn clip(&self, name: &str) -> Option<&Clip> {
+        self.clips.get(name)
+    }
+
+    pub fn clips(&self) -> impl Iterator<Item = &String> {
+        self.clips.keys()
+    }
+}
+
+fn pivot(json: &Value, owner: &str) -> Result<(f64, f64), String> {
+    match json {
+        Value::Null => Ok((0.5, 0.5)),
+        pair => match (pair[0].as_f64(), pair[1].as_f64()) {
+            (Some(x), Some(y)) => Ok((x, y)),
+            _ => Err(format!("'{}': \"pivot\" should be [x, y]", owner)),
+        },
+    }
+}
@@ -0,0 +1,86 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+
+// Detects file changes by polling modification times. There are no
+// background threads or OS services involved; the World calls poll()
+// once per frame and the watcher only touches the file system every
+// `interval`.
+
+const DEFAULT_INTERVAL_MS: u64 = 500;
+
+struct WatchedFile {
+    path: PathBuf,
+    modified: Option<SystemTime>,
+}
+
+pub struct FileWatcher {
+    files: Vec<WatchedFile>,
+    interval: Duration,
+    last_poll: Instant,
+}
+
+impl FileWatcher {
+    pub fn new() -> Self {
+        Self {
+            files: Vec::new(),
+            interval: Duration::from_millis(DEFAULT_INTERVAL_MS),
+            last_poll: Instant::now(),
+        }
+    }
+
+    pub fn set_interval(&mut self, interval: Duration) {
+        self.interval = interval;
+    }
+
+    /// Watching a file that is already watched is a no-op.
+    pub fn watch(&mut self, path: &Path) {
+        if self.is_watching(path) {
+            return;
+        }
+
+        self.files.push(WatchedFile {
+            path: path.to_path_buf(),
+            modified: FileWatcher::modified(path),
+        });
+    }
+
+    pub fn unwatch(&mut self, path: &Path) {
+        self.files.retain(|file| file.path != path);
+    }
+
+    pub fn is_watching(&self, path: &Path) -> bool {
+        self.files.iter().any(|file| file.path == path)
+    }
+
+    /// Returns the files modified since the last poll. A file that
+    /// disappears isn't reported until it reappears.
+    pub fn poll(&mut self) -> Vec<PathBuf> {
+        let mut changed = Vec::new();
+
+        if self.files.is_empty() || self.last_poll.elapsed() < self.interval {
+            return changed;
+        }
+        self.last_poll = Instant::now();
+
+        for file in self.files.iter_mut() {
+            let modified = FileWatcher::modified(&file.path);
+            if modified.is_some() && modified != file.modified {
+                changed.push(file.path.clone());
+            }
+            file.modified = modified;
+        }
+
+        changed
+    }
+
+    fn modified(path: &Path) -> Option<SystemTime> {
+        fs::metadata(path).and_then(|meta| meta.modified()).ok()
+    }
+}
+
+impl Default for FileWatcher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
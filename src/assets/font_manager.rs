@@ -0,0 +1,75 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::rc::Rc;
+
+use rendering::font::{Font, RFont};
+
+// Loads TrueType and OpenType fonts and caches them by path, so text nodes
+// using the same font share it and its glyph cache.
+//
+//   let font = world.data().fonts().borrow_mut().load("assets/DejaVuSans.ttf")?;
+//   text.set_font(Some(font));
+
+pub type RFontManager = Rc<RefCell<FontManager>>;
+
+pub struct FontManager {
+    fonts: HashMap<String, RFont>,
+}
+
+impl FontManager {
+    pub fn new() -> RFontManager {
+        Rc::new(RefCell::new(Self {
+            fonts: HashMap::new(),
+        }))
+    }
+
+    /// The cached font for `path`, reading the file the first time.
+    pub fn load(&mut self, path: &str) -> Result<RFont, String> {
+        if let Some(font) = self.fonts.get(path) {
+            return Ok(font.clone());
+        }
+
+        let bytes = match fs::read(Path::new(path)) {
+            Ok(bytes) => bytes,
+            Err(err) => return Err(format!("'{}': {}", path, err)),
+        };
+
+        self.load_bytes(path, bytes)
+    }
+
+    /// The cached font for `path`, parsing `bytes` the first time.
+    pub fn load_bytes(&mut self, path: &str, bytes: Vec<u8>) -> Result<RFont, String> {
+        if let Some(font) = self.fonts.get(path) {
+            return Ok(font.clone());
+        }
+
+        let font = match Font::from_bytes(bytes) {
+            Ok(font) => Rc::new(font),
+            Err(err) => return Err(format!("'{}': {}", path, err)),
+        };
+
+        self.fonts.insert(path.to_string(), font.clone());
+
+        Ok(font)
+    }
+
+    pub fn get(&self, path: &str) -> Option<RFont> {
+        self.fonts.get(path).cloned()
+    }
+
+    /// Drops the cache's reference. Nodes still using the font keep it
+    /// alive.
+    pub fn unload(&mut self, path: &str) {
+        self.fonts.remove(path);
+    }
+
+    pub fn clear(&mut self) {
+        self.fonts.clear();
+    }
+
+    pub fn count(&self) -> usize {
+        self.fonts.len()
+    }
+}
@@ -0,0 +1,210 @@
+use std::cell::RefCell;
+use std::collections::{HashMap, VecDeque};
+use std::fs;
+use std::rc::Rc;
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use world::World;
+
+// Spreads scene construction and asset loading over several frames.
+//
+// Steps run on the main thread, in order, within a per-frame time budget.
+// Files are read, in the order requested, on one worker thread that the
+// loader starts with its first read and that ends when the loader is
+// dropped. A step only runs once every file requested before it has been
+// read, and it receives all the files read so far.
+//
+//   let mut loader = Loader::new();
+//   loader.read_file("assets/level1.json");
+//   loader.add_step(|world, files| { /* build nodes from files */ });
+//   world.start_loading(loader);
+//
+// The World runs the loader each frame; a SceneLoading node displays its
+// progress and hands off to the target scene once it completes.
+
+const DEFAULT_BUDGET_MS: u64 = 8;
+
+pub type LoadedFiles = HashMap<String, Result<Vec<u8>, String>>;
+pub type LoadStep = Box<dyn FnOnce(&mut World, &LoadedFiles)>;
+
+pub type RLoadProgress = Rc<RefCell<LoadProgress>>;
+
+pub struct LoadProgress {
+    total: usize,
+    completed: usize,
+}
+
+impl LoadProgress {
+    pub fn total(&self) -> usize {
+        self.total
+    }
+
+    pub fn completed(&self) -> usize {
+        self.completed
+    }
+
+    /// 0.0 -> 1.0
+    pub fn fraction(&self) -> f64 {
+        if self.total == 0 {
+            return 1.0;
+        }
+        self.completed as f64 / self.total as f64
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.completed >= self.total
+    }
+}
+
+pub struct Loader {
+    // Each step with the number of file reads requested before it
+    steps: VecDeque<(usize, LoadStep)>,
+
+    pending: usize,
+    files: LoadedFiles,
+    sender: Sender<(String, Result<Vec<u8>, String>)>,
+    receiver: Receiver<(String, Result<Vec<u8>, String>)>,
+    // Paths to the worker thread, None until the first read
+    paths: Option<Sender<String>>,
+    // Requested paths in request order
+    read_order: Vec<String>,
+
+    budget: Duration,
+
+    progress: RLoadProgress,
+}
+
+impl Loader {
+    pub fn new() -> Self {
+        let (sender, receiver) = channel();
+
+        Self {
+            steps: VecDeque::new(),
+            pending: 0,
+            files: HashMap::new(),
+            sender,
+            receiver,
+            paths: None,
+            read_order: Vec::new(),
+            budget: Duration::from_millis(DEFAULT_BUDGET_MS),
+            progress: Rc::new(RefCell::new(LoadProgress {
+                total: 0,
+                completed: 0,
+            })),
+        }
+    }
+
+    /// How long steps may run each frame. At least one step runs per frame.
+    pub fn set_budget(&mut self, budget: Duration) {
+        self.budget = budget;
+    }
+
+    pub fn progress(&self) -> RLoadProgress {
+        self.progress.clone()
+    }
+
+    pub fn add_step<F>(&mut self, step: F)
+    where
+        F: FnOnce(&mut World, &LoadedFiles) + 'static,
+    {
+        self.steps
+            .push_back((self.read_order.len(), Box::new(step)));
+        self.progress.borrow_mut().total += 1;
+    }
+
+    /// Reads `path` on the worker thread. Steps added after this call can
+    /// find the contents, or the error, in their LoadedFiles under `path`.
+    pub fn read_file(&mut self, path: &str) {
+        let path = path.to_string();
+
+        self.read_order.push(path.clone());
+        self.pending += 1;
+        self.progress.borrow_mut().total += 1;
+
+        if self.paths.is_none() {
+            self.paths = Some(Loader::start_worker(self.sender.clone()));
+        }
+
+        if let Some(paths) = &self.paths {
+            if paths.send(path.clone()).is_err() {
+                // The worker is gone, so the file is reported unread.
+                let error = format!("{}: the loader's worker thread stopped", path);
+                let _ = self.sender.send((path, Err(error)));
+            }
+        }
+    }
+
+    // Reads the paths sent to it until the sender is dropped.
+    fn start_worker(files: Sender<(String, Result<Vec<u8>, String>)>) -> Sender<String> {
+        let (sender, paths) = channel::<String>();
+
+        thread::spawn(move || {
+            for path in paths.iter() {
+                let result = fs::read(&path).map_err(|err| format!("{}: {}", path, err));
+                // The loader may have been dropped, nobody is waiting then.
+                if files.send((path, result)).is_err() {
+                    break;
+                }
+            }
+        });
+
+        sender
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.progress.borrow().is_complete()
+    }
+
+    /// Runs steps until the budget is spent. Called once per frame by the World.
+    pub fn run(&mut self, world: &mut World) {
+        self.receive_files();
+
+        let start = Instant::now();
+
+        while let Some(&(files_needed, _)) = self.steps.front() {
+            if !self.files_ready(files_needed) {
+                break;
+            }
+
+            if let Some((_, step)) = self.steps.pop_front() {
+                step(world, &self.files);
+                self.progress.borrow_mut().completed += 1;
+            }
+
+            if start.elapsed() >= self.budget {
+                break;
+            }
+        }
+    }
+
+    fn receive_files(&mut self) {
+        while let Ok((path, result)) = self.receiver.try_recv() {
+            if let Err(err) = &result {
+                println!("Loader: {}", err);
+            }
+            self.files.insert(path, result);
+            self.pending -= 1;
+            self.progress.borrow_mut().completed += 1;
+        }
+    }
+
+    // True if the first `count` requested files have all been read.
+    fn files_ready(&self, count: usize) -> bool {
+        if self.pending == 0 {
+            return true;
+        }
+
+        self.read_order
+            .iter()
+            .take(count)
+            .all(|path| self.files.contains_key(path))
+    }
+}
+
+impl Default for Loader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,6 @@
+pub mod atlas;
+pub mod file_watcher;
+pub mod font_manager;
+pub mod loader;
+pub mod scene_loader;
+pub mod texture_manager;
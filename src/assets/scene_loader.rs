@@ -0,0 +1,297 @@
+extern crate serde_json;
+
+use self::serde_json::Value;
+
+use std::collections::{HashMap, HashSet};
+use std::fs;
+
+use nodes::node_trait::RNode;
+use world::World;
+
+// Builds and rebuilds node subtrees from JSON scene descriptions.
+//
+// A description names a node that already exists (the subtree root) and
+// lists its properties and children:
+//
+//   {
+//     "name": "GameScene",
+//     "children": [
+//       { "name": "WhiteText", "position": [0, 0], "rotation": 45, "scale": 50 },
+//       { "name": "BlueCross", "type": "CrossNode", "scale": [100, 100] }
+//     ]
+//   }
+//
+// All properties are optional. "scale" is either uniform or [sx, sy],
+// "rotation" is in degrees. Children are matched to existing children by
+// name, so a matched node keeps its id and state. Unmatched children are
+// created with the builder registered for their "type". Children that a
+// previous load described but that are no longer described are destroyed;
+// children created in code and never described are left alone.
+
+/// Same shape as the node constructors, for example, CrossNode::new.
+pub type NodeBuilder = fn(&str, usize, &mut World) -> RNode;
+
+#[derive(Default)]
+pub struct NodeFactory {
+    builders: HashMap<String, NodeBuilder>,
+}
+
+impl NodeFactory {
+    pub fn register(&mut self, type_name: &str, builder: NodeBuilder) {
+        self.builders.insert(type_name.to_string(), builder);
+    }
+
+    pub fn builder(&self, type_name: &str) -> Option<NodeBuilder> {
+        self.builders.get(type_name).cloned()
+    }
+}
+
+pub struct NodeDescription {
+    pub name: String,
+    pub type_name: Option<String>,
+    pub position: Option<(f64, f64)>,
+    pub rotation: Option<f64>,
+    pub scale: Option<(f64, f64)>,
+    pub visible: Option<bool>,
+    pub children: Vec<NodeDescription>,
+}
+
+impl NodeDescription {
+    pub fn from_file(path: &str) -> Result<Self, String> {
+        let text = match fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(err) => return Err(format!("{}: {}", path, err)),
+        };
+
+        match serde_json::from_str::<Value>(&text) {
+            Ok(json) => NodeDescription::from_json(&json),
+            Err(err) => Err(format!("{}: {}", path, err)),
+        }
+    }
+
+    pub fn from_json(json: &Value) -> Result<Self, String> {
+        let name = match json["name"].as_str() {
+            Some(name) => name.to_string(),
+            None => return Err(String::from("node description without a \"name\"")),
+        };
+
+        let mut children = Vec::new();
+        if let Some(list) = json["children"].as_array() {
+            for child in list {
+                children.push(NodeDescription::from_json(child)?);
+            }
+        }
+
+        let scale = match &json["scale"] {
+            Value::Null => None,
+            Value::Number(s) => s.as_f64().map(|s| (s, s)),
+            pair => Some(NodeDescription::pair(pair, &name, "scale")?),
+        };
+
+        let position = match &json["position"] {
+            Value::Null => None,
+            pair => Some(NodeDescription::pair(pair, &name, "position")?),
+        };
+
+        Ok(Self {
+            type_name: json["type"].as_str().map(String::from),
+            position,
+            rotation: json["rotation"].as_f64(),
+            scale,
+            visible: json["visible"].as_bool(),
+            children,
+            name,
+        })
+    }
+
+    fn pair(json: &Value, name: &str, property: &str) -> Result<(f64, f64), String> {
+        match (json[0].as_f64(), json[1].as_f64()) {
+            (Some(x), Some(y)) => Ok((x, y)),
+            _ => Err(format!("'{}': \"{}\" should be [x, y]", name, property)),
+        }
+    }
+
+    fn apply(&self, node: &RNode) {
+        let no = node.borrow();
+
+        if let Some((x, y)) = self.position {
+            no.set_position(x, y);
+        }
+        if let Some(degrees) = self.rotation {
+            no.set_rotation_degrees(degrees);
+        }
+        if let Some((sx, sy)) = self.scale {
+            no.set_nonuniform_scale(sx, sy);
+        }
+        if let Some(visible) = self.visible {
+            no.data().borrow_mut().node.set_visible(visible);
+        }
+    }
+}
+
+pub struct SceneLoader;
+
+impl SceneLoader {
+    /// Applies `desc` to the subtree at `root`. `previous` holds the ids
+    /// described by the last load of the same file. Returns the ids that
+    /// this description now covers.
+    pub fn reconcile(
+        root: &RNode,
+        desc: &NodeDescription,
+        previous: &HashSet<usize>,
+        world: &mut World,
+    ) -> Result<HashSet<usize>, String> {
+        let root_name = root.borrow().name();
+        if root_name != desc.name {
+            return Err(format!(
+                "description is for '{}' but the root is '{}'",
+                desc.name, root_name
+            ));
+        }
+
+        SceneLoader::check_types(desc, world)?;
+
+        // Build the missing nodes before changing anything else, checking
+        // the description against the live tree on the way. If that fails,
+        // the nodes just built are destroyed and the tree is as it was.
+        let mut built = Vec::new();
+        if let Err(err) = SceneLoader::build_missing(root, desc, &mut built, world) {
+            // Children were built after their parents.
+            for node in built.iter().rev() {
+                world.destroy_node(node);
+            }
+            return Err(err);
+        }
+
+        let mut described = HashSet::new();
+        SceneLoader::sub_reconcile(root, desc, previous, &mut described, world);
+
+        Ok(described)
+    }
+
+    fn check_types(desc: &NodeDescription, world: &World) -> Result<(), String> {
+        if let Some(type_name) = &desc.type_name {
+            if world.node_factory().builder(type_name).is_none() {
+                return Err(format!(
+                    "'{}': no builder registered for type '{}'",
+                    desc.name, type_name
+                ));
+            }
+        }
+
+        for child in desc.children.iter() {
+            SceneLoader::check_types(child, world)?;
+        }
+
+        Ok(())
+    }
+
+    // Builds the described children that don't exist yet, all the way down.
+    fn build_missing(
+        node: &RNode,
+        desc: &NodeDescription,
+        built: &mut Vec<RNode>,
+        world: &mut World,
+    ) -> Result<(), String> {
+        let children: Vec<RNode> = match node.borrow().get_children() {
+            Some(children) => children.borrow().clone(),
+            None => {
+                if desc.children.is_empty() {
+                    return Ok(());
+                }
+                return Err(format!("'{}' can't have children", desc.name));
+            }
+        };
+
+        let id = node.borrow().id();
+        let mut matched = HashSet::new();
+
+        for child_desc in desc.children.iter() {
+            let child = match SceneLoader::find_child(&children, child_desc, &matched) {
+                Some(child) => child,
+                None => {
+                    let child = SceneLoader::build(child_desc, id, world)?;
+                    built.push(child.clone());
+                    child
+                }
+            };
+
+            matched.insert(child.borrow().id());
+
+            SceneLoader::build_missing(&child, child_desc, built, world)?;
+        }
+
+        Ok(())
+    }
+
+    // Applies the description to the tree build_missing() completed and
+    // destroys the children it no longer describes.
+    fn sub_reconcile(
+        node: &RNode,
+        desc: &NodeDescription,
+        previous: &HashSet<usize>,
+        described: &mut HashSet<usize>,
+        world: &mut World,
+    ) {
+        desc.apply(node);
+
+        described.insert(node.borrow().id());
+
+        let children: Vec<RNode> = match node.borrow().get_children() {
+            Some(children) => children.borrow().clone(),
+            None => return,
+        };
+
+        let mut matched = HashSet::new();
+
+        for child_desc in desc.children.iter() {
+            // Built children were added after the existing ones, so they
+            // are matched the same way build_missing() matched them.
+            let child = match SceneLoader::find_child(&children, child_desc, &matched) {
+                Some(child) => child,
+                None => continue,
+            };
+
+            matched.insert(child.borrow().id());
+
+            SceneLoader::sub_reconcile(&child, child_desc, previous, described, world);
+        }
+
+        for child in children.iter() {
+            let child_id = child.borrow().id();
+            if previous.contains(&child_id) && !matched.contains(&child_id) {
+                world.destroy_node(child);
+            }
+        }
+    }
+
+    // The first child named as `desc` that isn't matched yet
+    fn find_child(
+        children: &[RNode],
+        desc: &NodeDescription,
+        matched: &HashSet<usize>,
+    ) -> Option<RNode> {
+        children
+            .iter()
+            .find(|child| {
+                let ch = child.borrow();
+                ch.name() == desc.name && !matched.contains(&ch.id())
+            })
+            .cloned()
+    }
+
+    fn build(desc: &NodeDescription, parent: usize, world: &mut World) -> Result<RNode, String> {
+        let builder = match &desc.type_name {
+            Some(type_name) => world.node_factory().builder(type_name),
+            None => None,
+        };
+
+        match builder {
+            Some(builder) => Ok(builder(&desc.name, parent, world)),
+            None => Err(format!(
+                "'{}' doesn't exist and has no \"type\" to build it from",
+                desc.name
+            )),
+        }
+    }
+}
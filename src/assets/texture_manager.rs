@@ -0,0 +1,180 @@
+extern crate png;
+extern crate sdl2;
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::rc::Rc;
+
+use self::png::{ColorType, Decoder, Transformations};
+use self::sdl2::{pixels::PixelFormatEnum, render::BlendMode, rwops::RWops, surface::Surface};
+
+use rendering::texture::{RTexture, Texture};
+
+// Loads images into Textures and caches them by path, so nodes showing the
+// same image share one copy.
+//
+//   let texture = world.data().textures().borrow_mut().load("assets/ship.png")?;
+//   sprite.set_texture(texture);
+//
+// BMP files are read by SDL, PNG files by the png crate. The format is
+// chosen by the file's signature, not its extension. Images fetched by a
+// Loader can be decoded from memory with load_bytes().
+
+pub type RTextureManager = Rc<RefCell<TextureManager>>;
+
+pub struct TextureManager {
+    textures: HashMap<String, RTexture>,
+}
+
+impl TextureManager {
+    pub fn new() -> RTextureManager {
+        Rc::new(RefCell::new(Self {
+            textures: HashMap::new(),
+        }))
+    }
+
+    /// The cached texture for `path`, reading the file the first time.
+    pub fn load(&mut self, path: &str) -> Result<RTexture, String> {
+        if let Some(texture) = self.textures.get(path) {
+            return Ok(texture.clone());
+        }
+
+        let bytes = match fs::read(Path::new(path)) {
+            Ok(bytes) => bytes,
+            Err(err) => return Err(format!("'{}': {}", path, err)),
+        };
+
+        self.load_bytes(path, &bytes)
+    }
+
+    /// The cached texture for `path`, decoding `bytes` the first time.
+    pub fn load_bytes(&mut self, path: &str, bytes: &[u8]) -> Result<RTexture, String> {
+        if let Some(texture) = self.textures.get(path) {
+            return Ok(texture.clone());
+        }
+
+        let texture = match decode(bytes) {
+            Ok(texture) => Rc::new(texture),
+            Err(err) => return Err(format!("'{}': {}", path, err)),
+        };
+
+        self.textures.insert(path.to_string(), texture.clone());
+
+        Ok(texture)
+    }
+
+    /// Replaces the cached texture for `path` with the file's current
+    /// contents, for example, from a World::watch_asset callback. Nodes
+    /// holding the old texture keep it until they're given the new one.
+    pub fn reload(&mut self, path: &str) -> Result<RTexture, String> {
+        let old = self.textures.remove(path);
+
+        match self.load(path) {
+            Ok(texture) => Ok(texture),
+            Err(err) => {
+                if let Some(old) = old {
+                    self.textures.insert(path.to_string(), old);
+                }
+                Err(err)
+            }
+        }
+    }
+
+    /// Caches a texture made in code under `name`.
+    pub fn insert(&mut self, name: &str, texture: Texture) -> RTexture {
+        let texture = Rc::new(texture);
+        self.textures.insert(name.to_string(), texture.clone());
+        texture
+    }
+
+    pub fn get(&self, path: &str) -> Option<RTexture> {
+        self.textures.get(path).cloned()
+    }
+
+    /// Drops the cache's reference. Nodes still using the texture keep it
+    /// alive.
+    pub fn unload(&mut self, path: &str) {
+        self.textures.remove(path);
+    }
+
+    pub fn clear(&mut self) {
+        self.textures.clear();
+    }
+
+    pub fn count(&self) -> usize {
+        self.textures.len()
+    }
+}
+
+const PNG_SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A];
+
+fn decode(bytes: &[u8]) -> Result<Texture, String> {
+    if bytes.starts_with(&PNG_SIGNATURE) {
+        decode_png(bytes)
+    } else if bytes.starts_with(b"BM") {
+        decode_bmp(bytes)
+    } else {
+        Err(String::from("not a BMP or PNG image"))
+    }
+}
+
+fn decode_png(bytes: &[u8]) -> Result<Texture, String> {
+    let mut decoder = Decoder::new(bytes);
+    // Palette and low bit depth images expand to 8 bits per channel.
+    decoder.set_transformations(Transformations::normalize_to_color8());
+
+    let mut reader = decoder.read_info().map_err(|err| err.to_string())?;
+    let mut buffer = vec![0; reader.output_buffer_size()];
+    let info = reader
+        .next_frame(&mut buffer)
+        .map_err(|err| err.to_string())?;
+    let data = &buffer[..info.buffer_size()];
+
+    let mut pixels = Vec::with_capacity(info.width as usize * info.height as usize * 4);
+    match info.color_type {
+        ColorType::Rgba => pixels.extend_from_slice(data),
+        ColorType::Rgb => {
+            for p in data.chunks(3) {
+                pixels.extend_from_slice(&[p[0], p[1], p[2], 255]);
+            }
+        }
+        ColorType::GrayscaleAlpha => {
+            for p in data.chunks(2) {
+                pixels.extend_from_slice(&[p[0], p[0], p[0], p[1]]);
+            }
+        }
+        ColorType::Grayscale => {
+            for p in data.iter() {
+                pixels.extend_from_slice(&[*p, *p, *p, 255]);
+            }
+        }
+        ColorType::Indexed => return Err(String::from("palette was not expanded")),
+    }
+
+    Texture::from_rgba(info.width, info.height, pixels)
+}
+
+fn decode_bmp(bytes: &[u8]) -> Result<Texture, String> {
+    let mut rwops = RWops::from_bytes(bytes)?;
+    let mut bmp = Surface::load_bmp_rw(&mut rwops)?;
+
+    // Blit into an RGBA surface to convert from whatever the file holds.
+    let (width, height) = (bmp.width(), bmp.height());
+    let mut rgba = Surface::new(width, height, PixelFormatEnum::RGBA32)?;
+    bmp.set_blend_mode(BlendMode::None)?;
+    bmp.blit(None, &mut rgba, None)?;
+
+    let pitch = rgba.pitch() as usize;
+    let row = width as usize * 4;
+    let pixels = rgba.with_lock(|data| {
+        let mut pixels = Vec::with_capacity(row * height as usize);
+        for y in 0..height as usize {
+            pixels.extend_from_slice(&data[y * pitch..y * pitch + row]);
+        }
+        pixels
+    });
+
+    Texture::from_rgba(width, height, pixels)
+}
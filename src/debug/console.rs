@@ -0,0 +1,432 @@
+use std::io::{self, ErrorKind, Read, Write};
+use std::net::{Ipv4Addr, SocketAddrV4, TcpListener, TcpStream};
+
+use nodes::{
+    node_manager::NodeManager,
+    node_trait::{NodeType, RNode},
+};
+use world::GlobalData;
+
+// A remote debug console. It listens on localhost only and accepts newline
+// terminated text commands, for example, from netcat:
+//
+//   $ nc 127.0.0.1 7878
+//   list
+//   set /GameScene/GameLayer/YellowRect pos 100 50
+//
+// The sockets are non-blocking and polled once per frame from the core loop,
+// after the updates and before rendering, so a command never runs while the
+// scene graph is being visited. Replies the socket can't take yet wait in the
+// client's outgoing buffer, and a client's next commands wait until it has
+// read them.
+//
+// Nodes are addressed either by id or by a path of names starting at the
+// running node. Each reply ends with a line of either "ok" or "error: ...".
+
+const HELP: &str = "\
+commands:
+  help                           this text
+  list                           running node's tree: name (id) type
+  pool                           every node in the pool
+  get <node>                     transform, visibility and timing
+  set <node> pos <x> <y>
+  set <node> rot <degrees>
+  set <node> scale <s> [<sy>]
+  set <node> visible <true|false>
+  pause <node>                   pause node and its children
+  resume <node>                  resume node and its children
+  replace <node>                 replace the running scene
+  quit                           close this connection
+<node> is an id, e.g. 5, or a path, e.g. /GameScene/GameLayer";
+
+// Longest command line. A client sending more without a newline is dropped.
+const MAX_LINE: usize = 4096;
+
+struct Client {
+    stream: TcpStream,
+    // Bytes received that don't yet form a complete line
+    pending: Vec<u8>,
+    // Reply bytes not yet written to the socket
+    outgoing: Vec<u8>,
+    // The client has stopped sending. It is closed once its replies are sent.
+    finished: bool,
+    closed: bool,
+}
+
+impl Client {
+    fn is_done(&self) -> bool {
+        self.closed || (self.finished && self.outgoing.is_empty())
+    }
+}
+
+pub struct Console {
+    listener: TcpListener,
+    clients: Vec<Client>,
+}
+
+impl Console {
+    pub fn new(port: u16) -> io::Result<Self> {
+        let listener = TcpListener::bind(SocketAddrV4::new(Ipv4Addr::LOCALHOST, port))?;
+        listener.set_nonblocking(true)?;
+
+        println!("Debug console listening on: {}", listener.local_addr()?);
+
+        Ok(Self {
+            listener,
+            clients: Vec::new(),
+        })
+    }
+
+    pub fn poll(&mut self, manager: &NodeManager, data: &mut GlobalData) {
+        self.accept();
+
+        for client in self.clients.iter_mut() {
+            if !Console::flush(client) || client.finished {
+                // Still sending earlier replies
+                continue;
+            }
+
+            for line in Console::read_lines(client) {
+                let line = line.trim();
+                if line.is_empty() {
+                    continue;
+                }
+
+                if line == "quit" {
+                    client.closed = true;
+                    break;
+                }
+
+                let reply = match Console::execute(line, manager, data) {
+                    Ok(mut lines) => {
+                        lines.push(String::from("ok"));
+                        lines
+                    }
+                    Err(err) => vec![format!("error: {}", err)],
+                };
+
+                for line in reply.iter() {
+                    client.outgoing.extend_from_slice(line.as_bytes());
+                    client.outgoing.push(b'\n');
+                }
+            }
+
+            Console::flush(client);
+        }
+
+        self.clients.retain(|client| !client.is_done());
+    }
+
+    fn accept(&mut self) {
+        loop {
+            match self.listener.accept() {
+                Ok((stream, addr)) => {
+                    if stream.set_nonblocking(true).is_err() {
+                        continue;
+                    }
+                    println!("Debug console: connection from {}", addr);
+                    self.clients.push(Client {
+                        stream,
+                        pending: Vec::new(),
+                        outgoing: Vec::new(),
+                        finished: false,
+                        closed: false,
+                    });
+                }
+                Err(ref err) if err.kind() == ErrorKind::WouldBlock => break,
+                Err(err) => {
+                    println!("Debug console: accept failed: {}", err);
+                    break;
+                }
+            }
+        }
+    }
+
+    fn read_lines(client: &mut Client) -> Vec<String> {
+        let mut buf = [0u8; 512];
+
+        loop {
+            match client.stream.read(&mut buf) {
+                Ok(0) => {
+                    client.finished = true;
+                    break;
+                }
+                Ok(n) => {
+                    client.pending.extend_from_slice(&buf[..n]);
+                    // The rest waits in the socket for the next poll.
+                    if client.pending.len() > MAX_LINE {
+                        break;
+                    }
+                }
+                Err(ref err) if err.kind() == ErrorKind::WouldBlock => break,
+                Err(ref err) if err.kind() == ErrorKind::Interrupted => (),
+                Err(_) => {
+                    client.closed = true;
+                    break;
+                }
+            }
+        }
+
+        let mut lines = Vec::new();
+        while let Some(end) = client.pending.iter().position(|&b| b == b'\n') {
+            let line: Vec<u8> = client.pending.drain(..=end).collect();
+            lines.push(String::from_utf8_lossy(&line).into_owned());
+        }
+
+        if client.pending.len() > MAX_LINE {
+            println!(
+                "Debug console: dropping a client, line over {} bytes",
+                MAX_LINE
+            );
+            client.closed = true;
+        }
+
+        lines
+    }
+
+    // Writes as much of the outgoing buffer as the socket takes. Returns
+    // true once it is all written.
+    fn flush(client: &mut Client) -> bool {
+        while !client.outgoing.is_empty() {
+            match client.stream.write(&client.outgoing) {
+                Ok(0) => {
+                    client.closed = true;
+                    return false;
+                }
+                Ok(n) => {
+                    client.outgoing.drain(..n);
+                }
+                Err(ref err) if err.kind() == ErrorKind::WouldBlock => return false,
+                Err(ref err) if err.kind() == ErrorKind::Interrupted => (),
+                Err(_) => {
+                    client.closed = true;
+                    return false;
+                }
+            }
+        }
+
+        true
+    }
+
+    // --------------------------------------------------------------------------
+    // Commands
+    // --------------------------------------------------------------------------
+    fn execute(
+        line: &str,
+        manager: &NodeManager,
+        data: &mut GlobalData,
+    ) -> Result<Vec<String>, String> {
+        let args: Vec<&str> = line.split_whitespace().collect();
+
+        match args[0] {
+            "help" => Ok(HELP.lines().map(String::from).collect()),
+            "list" => Ok(Console::list(manager)),
+            "pool" => Ok(Console::pool(data)),
+            "get" => {
+                let node = Console::resolve(&args, 1, manager, data)?;
+                Ok(Console::get(&node))
+            }
+            "set" => {
+                let node = Console::resolve(&args, 1, manager, data)?;
+                Console::set(&node, &args[2..])?;
+                Ok(Vec::new())
+            }
+            "pause" | "resume" => {
+                let node = Console::resolve(&args, 1, manager, data)?;
+                let paused = args[0] == "pause";
+                let no = node.borrow();
+                no.pause(paused);
+                no.ripple_pause(paused);
+                Ok(Vec::new())
+            }
+            "replace" => {
+                let node = Console::resolve(&args, 1, manager, data)?;
+                Console::replace(&node, manager, data)?;
+                Ok(Vec::new())
+            }
+            cmd => Err(format!("unknown command '{}', try 'help'", cmd)),
+        }
+    }
+
+    fn list(manager: &NodeManager) -> Vec<String> {
+        let mut lines = Vec::new();
+        let root = manager.running_node();
+        if !root.borrow().is_nil() {
+            Console::sub_list(&root, 0, &mut lines);
+        }
+        lines
+    }
+
+    fn sub_list(node: &RNode, level: usize, lines: &mut Vec<String>) {
+        {
+            let no = node.borrow();
+            lines.push(format!(
+                "{}{} ({}) {:?}",
+                "  ".repeat(level),
+                no.name(),
+                no.id(),
+                no.get_node_type()
+            ));
+        }
+
+        if let Some(children) = node.borrow().get_children() {
+            for child in children.borrow().iter() {
+                Console::sub_list(child, level + 1, lines);
+            }
+        }
+    }
+
+    fn pool(data: &GlobalData) -> Vec<String> {
+        let mut nodes: Vec<(usize, String)> = data
+            .nodes()
+            .map(|node| {
+                let no = node.borrow();
+                (no.id(), no.name())
+            })
+            .collect();
+        nodes.sort();
+
+        nodes
+            .iter()
+            .map(|(id, name)| format!("{} ({})", name, id))
+            .collect()
+    }
+
+    fn get(node: &RNode) -> Vec<String> {
+        let no = node.borrow();
+        let nd = no.data().borrow();
+        let (x, y) = nd.transform.get_position();
+        let (sx, sy) = nd.transform.scale();
+
+        vec![
+            format!("id: {}", nd.node.id()),
+            format!("name: {}", nd.node.name()),
+            format!("type: {:?}", nd.node.node_type()),
+            format!("parent: {}", no.parent()),
+            format!("pos: {} {}", x, y),
+            format!("rot: {}", nd.transform.rotation_in_degrees()),
+            format!("scale: {} {}", sx, sy),
+            format!("visible: {}", nd.node.visible()),
+            format!("paused: {}", nd.timing.paused()),
+            format!("stage: {}", nd.node.is_on_stage()),
+        ]
+    }
+
+    fn set(node: &RNode, args: &[&str]) -> Result<(), String> {
+        if args.is_empty() {
+            return Err(String::from("set needs a property"));
+        }
+
+        let values = &args[1..];
+        let no = node.borrow();
+
+        match args[0] {
+            "pos" => {
+                let x = Console::number(values, 0)?;
+                let y = Console::number(values, 1)?;
+                no.set_position(x, y);
+            }
+            "rot" => {
+                no.set_rotation_degrees(Console::number(values, 0)?);
+            }
+            "scale" => {
+                let sx = Console::number(values, 0)?;
+                if values.len() > 1 {
+                    no.set_nonuniform_scale(sx, Console::number(values, 1)?);
+                } else {
+                    no.set_scale(sx);
+                }
+            }
+            "visible" => {
+                let visible = match values.first() {
+                    Some(&"true") | Some(&"1") => true,
+                    Some(&"false") | Some(&"0") => false,
+                    _ => return Err(String::from("visible expects true or false")),
+                };
+                no.data().borrow_mut().node.set_visible(visible);
+            }
+            prop => return Err(format!("unknown property '{}'", prop)),
+        }
+
+        Ok(())
+    }
+
+    fn replace(node: &RNode, manager: &NodeManager, data: &mut GlobalData) -> Result<(), String> {
+        let (id, is_scene) = {
+            let no = node.borrow();
+            (no.id(), no.is_node_type(NodeType::Scene))
+        };
+
+        if !is_scene {
+            return Err(format!("({}) is not a scene", id));
+        }
+
+        if manager.running_node().borrow().id() == id {
+            return Err(format!("({}) is already running", id));
+        }
+
+        manager.replace_node_by_id(id, data);
+
+        Ok(())
+    }
+
+    fn number(values: &[&str], index: usize) -> Result<f64, String> {
+        match values.get(index) {
+            Some(value) => value
+                .parse::<f64>()
+                .map_err(|_| format!("'{}' is not a number", value)),
+            None => Err(String::from("missing value")),
+        }
+    }
+
+    // A node is addressed by id or by a path of names from the running node.
+    fn resolve(
+        args: &[&str],
+        index: usize,
+        manager: &NodeManager,
+        data: &GlobalData,
+    ) -> Result<RNode, String> {
+        let target = match args.get(index) {
+            Some(target) => *target,
+            None => return Err(String::from("missing node id or path")),
+        };
+
+        if let Ok(id) = target.parse::<usize>() {
+            return match data.find_node(&id) {
+                Some(node) => Ok(node.clone()),
+                None => Err(format!("no node with id ({})", id)),
+            };
+        }
+
+        let mut names = target.split('/').filter(|name| !name.is_empty());
+
+        let mut node = manager.running_node();
+        match names.next() {
+            Some(name) if !node.borrow().is_nil() && node.borrow().name() == name => (),
+            _ => {
+                return Err(format!(
+                    "path '{}' doesn't start at the running node",
+                    target
+                ))
+            }
+        }
+
+        for name in names {
+            let child = match node.borrow().get_children() {
+                Some(children) => children
+                    .borrow()
+                    .iter()
+                    .find(|child| child.borrow().name() == name)
+                    .cloned(),
+                None => None,
+            };
+
+            node = match child {
+                Some(child) => child,
+                None => return Err(format!("no node '{}' in path '{}'", name, target)),
+            };
+        }
+
+        Ok(node)
+    }
+}
@@ -0,0 +1,188 @@
+use geometry::point::Point;
+use math::affine_transform::AffineTransform;
+use nodes::{node_functions::NodeFunctions, node_trait::RNode};
+use rendering::{color::Palette, render_context::Context};
+use world::GlobalData;
+
+// An in-window scene graph inspector. It lists the running node's tree and
+// shows the selected node's properties. The selected node is highlighted with
+// its AABB (when the node has device vertices) and its local axes.
+//
+// Everything is drawn in device space after the scene has been visited.
+
+const LINE_HEIGHT: i32 = 18;
+const AXIS_LENGTH: f64 = 40.0;
+
+#[derive(Default)]
+pub struct Inspector {
+    visible: bool,
+
+    // Index into the flattened tree
+    selected: usize,
+}
+
+impl Inspector {
+    pub fn new() -> Self {
+        Self {
+            visible: false,
+            selected: 0,
+        }
+    }
+
+    pub fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    pub fn toggle(&mut self) {
+        self.visible = !self.visible;
+    }
+
+    pub fn select_next(&mut self) {
+        self.selected += 1;
+    }
+
+    pub fn select_previous(&mut self) {
+        if self.selected > 0 {
+            self.selected -= 1;
+        }
+    }
+
+    pub fn draw(&mut self, root: &RNode, context: &mut Context, data: &GlobalData) {
+        if !self.visible || root.borrow().is_nil() {
+            return;
+        }
+
+        let tree = Inspector::flatten(root);
+
+        // The tree may have shrunk since the last selection.
+        if self.selected >= tree.len() {
+            self.selected = tree.len() - 1;
+        }
+
+        let mut y = 60;
+        for (i, &(level, ref node)) in tree.iter().enumerate() {
+            let no = node.borrow();
+            let marker = if i == self.selected { ">" } else { " " };
+
+            if i == self.selected {
+                context.set_draw_color(&Palette::YELLOW());
+            } else if no.is_visible() {
+                context.set_draw_color(&Palette::WHITE(200));
+            } else {
+                context.set_draw_color(&Palette::GRAY());
+            }
+
+            context.text(
+                10,
+                y,
+                &format!(
+                    "{}{}{} ({})",
+                    marker,
+                    "  ".repeat(level),
+                    no.name(),
+                    no.id()
+                ),
+                2,
+                1,
+            );
+            y += LINE_HEIGHT;
+        }
+
+        let selected = &tree[self.selected].1;
+        Inspector::highlight(selected, context);
+        Inspector::draw_properties(selected, context, data);
+    }
+
+    fn flatten(root: &RNode) -> Vec<(usize, RNode)> {
+        let mut tree = Vec::new();
+        Inspector::sub_flatten(root, 0, &mut tree);
+        tree
+    }
+
+    fn sub_flatten(node: &RNode, level: usize, tree: &mut Vec<(usize, RNode)>) {
+        tree.push((level, node.clone()));
+
+        if let Some(children) = node.borrow().get_children() {
+            for child in children.borrow().iter() {
+                Inspector::sub_flatten(child, level + 1, tree);
+            }
+        }
+    }
+
+    fn highlight(node: &RNode, context: &mut Context) {
+        let no = node.borrow();
+
+        if let Some(vertices) = no.device_vertices() {
+            let verts = vertices.borrow();
+            if !verts.is_empty() {
+                NodeFunctions::draw_aabb(&verts, context);
+            }
+        }
+
+        // Local axes: +X red, +Y green, scaled to a fixed device length.
+        let device = *no.data().borrow().transform.get_device_transform();
+        let origin = Inspector::to_device(0.0, 0.0, &device);
+
+        context.set_draw_color(&Palette::RED());
+        Inspector::draw_axis(&origin, &Inspector::to_device(1.0, 0.0, &device), context);
+        context.set_draw_color(&Palette::GREEN());
+        Inspector::draw_axis(&origin, &Inspector::to_device(0.0, 1.0, &device), context);
+    }
+
+    fn draw_axis(origin: &Point, end: &Point, context: &mut Context) {
+        let dx = end.x - origin.x;
+        let dy = end.y - origin.y;
+        let length = (dx * dx + dy * dy).sqrt();
+        if length == 0.0 {
+            return;
+        }
+
+        context.render_line(
+            origin.x,
+            origin.y,
+            origin.x + dx / length * AXIS_LENGTH,
+            origin.y + dy / length * AXIS_LENGTH,
+        );
+    }
+
+    fn to_device(x: f64, y: f64, device: &AffineTransform) -> Point {
+        let mut p = Point::new();
+        AffineTransform::transform_to_point(&Point::from_xy(x, y), &mut p, device);
+        p
+    }
+
+    fn draw_properties(node: &RNode, context: &mut Context, data: &GlobalData) {
+        let no = node.borrow();
+        let nd = no.data().borrow();
+        let (x, y) = nd.transform.get_position();
+        let (sx, sy) = nd.transform.scale();
+
+        let lines = [
+            format!("Id: {}  Name: {}", nd.node.id(), nd.node.name()),
+            format!("Type: {:?}  Parent: {}", nd.node.node_type(), no.parent()),
+            format!("Pos: {:.2}, {:.2}", x, y),
+            format!("Rot: {:.2} deg", nd.transform.rotation_in_degrees()),
+            format!("Scale: {:.2}, {:.2}", sx, sy),
+            format!(
+                "Visible: {}  Dirty: {}",
+                nd.node.visible(),
+                nd.node.is_dirty()
+            ),
+            format!(
+                "Paused: {}  Timing: {}  Stage: {}",
+                nd.timing.paused(),
+                nd.is_timing_target(),
+                nd.node.is_on_stage()
+            ),
+        ];
+
+        let px = data.window_width as i32 - 40 * 16;
+        let mut py = 60;
+
+        context.set_draw_color(&Palette::AQUA());
+        for line in lines.iter() {
+            context.text(px, py, line, 2, 1);
+            py += LINE_HEIGHT;
+        }
+    }
+}
@@ -0,0 +1,420 @@
+extern crate sdl2;
+extern crate serde_json;
+
+use self::sdl2::{keyboard::Keycode, mouse::MouseButton};
+use self::serde_json::{Map, Value};
+
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::rc::Rc;
+
+use events::{
+    io_events::{IOEvent, IOEventData, KeyAction, MouseAction},
+    keyboard::RKeyboardState,
+    mouse::RMouseState,
+};
+
+// Named actions bound to keys, mouse buttons and the wheel so game code
+// doesn't match raw input:
+//
+//   actions.bind("jump", Binding::Key(Keycode::Space));
+//   actions.bind("move_x", Binding::KeyAxis(Keycode::A, Keycode::D));
+//   ...
+//   if actions.is_pressed("jump") { ... }
+//   let dx = actions.value("move_x") * speed;
+//
+// "Pressed" and "released" are true for the first update after the input
+// changed, even if the input went down and up between updates. "Held" is
+// the current state.
+//
+// Bindings are saved to, and loaded from, the "actions" section of the
+// config file (see World::configure):
+//
+//   "actions": {
+//     "jump": [{ "key": "Space" }, { "mouse": "Left" }],
+//     "move_x": [{ "negative": "A", "positive": "D" }],
+//     "zoom": [{ "wheel": "y" }]
+//   }
+
+pub type RActionMap = Rc<RefCell<ActionMap>>;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Binding {
+    Key(Keycode),
+    Mouse(MouseButton),
+    // Wheel motion since the last update
+    WheelX,
+    WheelY,
+    // -1.0 while `negative` is held, 1.0 while `positive` is held.
+    KeyAxis(Keycode, Keycode),
+}
+
+impl Binding {
+    fn uses_key(self, keycode: Keycode) -> bool {
+        match self {
+            Binding::Key(key) => key == keycode,
+            Binding::KeyAxis(negative, positive) => negative == keycode || positive == keycode,
+            _ => false,
+        }
+    }
+
+    fn to_json(self) -> Value {
+        let mut json = Map::new();
+        match self {
+            Binding::Key(key) => {
+                json.insert("key".to_string(), Value::from(key.name()));
+            }
+            Binding::Mouse(button) => {
+                json.insert("mouse".to_string(), Value::from(button_name(button)));
+            }
+            Binding::WheelX => {
+                json.insert("wheel".to_string(), Value::from("x"));
+            }
+            Binding::WheelY => {
+                json.insert("wheel".to_string(), Value::from("y"));
+            }
+            Binding::KeyAxis(negative, positive) => {
+                json.insert("negative".to_string(), Value::from(negative.name()));
+                json.insert("positive".to_string(), Value::from(positive.name()));
+            }
+        }
+        Value::Object(json)
+    }
+
+    fn from_json(json: &Value) -> Result<Self, String> {
+        if let Some(name) = json["key"].as_str() {
+            return Ok(Binding::Key(keycode(name)?));
+        }
+
+        if let Some(name) = json["mouse"].as_str() {
+            return match button_from_name(name) {
+                Some(button) => Ok(Binding::Mouse(button)),
+                None => Err(format!("unknown mouse button '{}'", name)),
+            };
+        }
+
+        if let Some(axis) = json["wheel"].as_str() {
+            return match axis {
+                "x" => Ok(Binding::WheelX),
+                "y" => Ok(Binding::WheelY),
+                _ => Err(format!("unknown wheel axis '{}'", axis)),
+            };
+        }
+
+        match (json["negative"].as_str(), json["positive"].as_str()) {
+            (Some(negative), Some(positive)) => {
+                Ok(Binding::KeyAxis(keycode(negative)?, keycode(positive)?))
+            }
+            _ => Err(format!("unknown binding {}", json)),
+        }
+    }
+}
+
+fn keycode(name: &str) -> Result<Keycode, String> {
+    match Keycode::from_name(name) {
+        Some(keycode) => Ok(keycode),
+        None => Err(format!("unknown key '{}'", name)),
+    }
+}
+
+fn button_name(button: MouseButton) -> &'static str {
+    match button {
+        MouseButton::Left => "Left",
+        MouseButton::Middle => "Middle",
+        MouseButton::Right => "Right",
+        MouseButton::X1 => "X1",
+        MouseButton::X2 => "X2",
+        MouseButton::Unknown => "Unknown",
+    }
+}
+
+fn button_from_name(name: &str) -> Option<MouseButton> {
+    match name {
+        "Left" => Some(MouseButton::Left),
+        "Middle" => Some(MouseButton::Middle),
+        "Right" => Some(MouseButton::Right),
+        "X1" => Some(MouseButton::X1),
+        "X2" => Some(MouseButton::X2),
+        _ => None,
+    }
+}
+
+#[derive(Default)]
+struct Action {
+    bindings: Vec<Binding>,
+
+    pressed: bool,
+    released: bool,
+    // Wheel motion since the last update
+    wheel: (f64, f64),
+}
+
+pub struct ActionMap {
+    // The World keeps these current before events reach the map.
+    keyboard: RKeyboardState,
+    mouse: RMouseState,
+
+    actions: BTreeMap<String, Action>,
+}
+
+impl ActionMap {
+    pub fn new(keyboard: RKeyboardState, mouse: RMouseState) -> RActionMap {
+        Rc::new(RefCell::new(Self {
+            keyboard,
+            mouse,
+            actions: BTreeMap::new(),
+        }))
+    }
+
+    // --------------------------------------------------------
+    // Bindings
+    // --------------------------------------------------------
+    pub fn bind(&mut self, action: &str, binding: Binding) {
+        let entry = self.actions.entry(action.to_string()).or_default();
+        if !entry.bindings.contains(&binding) {
+            entry.bindings.push(binding);
+        }
+    }
+
+    pub fn unbind(&mut self, action: &str, binding: Binding) {
+        if let Some(entry) = self.actions.get_mut(action) {
+            entry.bindings.retain(|b| *b != binding);
+        }
+    }
+
+    /// Replaces all of the action's bindings, for example, from a controls
+    /// menu.
+    pub fn rebind(&mut self, action: &str, bindings: Vec<Binding>) {
+        self.actions.entry(action.to_string()).or_default().bindings = bindings;
+    }
+
+    pub fn remove_action(&mut self, action: &str) {
+        self.actions.remove(action);
+    }
+
+    pub fn bindings(&self, action: &str) -> &[Binding] {
+        match self.actions.get(action) {
+            Some(entry) => &entry.bindings,
+            None => &[],
+        }
+    }
+
+    pub fn actions(&self) -> impl Iterator<Item = &String> {
+        self.actions.keys()
+    }
+
+    // --------------------------------------------------------
+    // Queries
+    // --------------------------------------------------------
+    /// True for the first update after a bound input went down.
+    pub fn is_pressed(&self, action: &str) -> bool {
+        self.actions.get(action).is_some_and(|entry| entry.pressed)
+    }
+
+    /// True for the first update after the last held input was released.
+    pub fn is_released(&self, action: &str) -> bool {
+        self.actions.get(action).is_some_and(|entry| entry.released)
+    }
+
+    pub fn is_held(&self, action: &str) -> bool {
+        match self.actions.get(action) {
+            Some(entry) => entry.bindings.iter().any(|b| self.binding_held(b)),
+            None => false,
+        }
+    }
+
+    /// The bound input with the largest magnitude: 0.0 or 1.0 for keys and
+    /// buttons, -1.0 -> 1.0 for key axes and the wheel motion since the
+    /// last update for wheel axes.
+    pub fn value(&self, action: &str) -> f64 {
+        let entry = match self.actions.get(action) {
+            Some(entry) => entry,
+            None => return 0.0,
+        };
+
+        let keyboard = self.keyboard.borrow();
+        let mut value: f64 = 0.0;
+
+        for binding in entry.bindings.iter() {
+            let v = match *binding {
+                Binding::KeyAxis(negative, positive) => {
+                    let mut v = 0.0;
+                    if keyboard.is_key_down(negative) {
+                        v -= 1.0;
+                    }
+                    if keyboard.is_key_down(positive) {
+                        v += 1.0;
+                    }
+                    v
+                }
+                Binding::WheelX => entry.wheel.0,
+                Binding::WheelY => entry.wheel.1,
+                _ => {
+                    if self.binding_held(binding) {
+                        1.0
+                    } else {
+                        0.0
+                    }
+                }
+            };
+
+            if v.abs() > value.abs() {
+                value = v;
+            }
+        }
+
+        value
+    }
+
+    fn binding_held(&self, binding: &Binding) -> bool {
+        match *binding {
+            Binding::Key(key) => self.keyboard.borrow().is_key_down(key),
+            Binding::KeyAxis(negative, positive) => {
+                let keyboard = self.keyboard.borrow();
+                keyboard.is_key_down(negative) || keyboard.is_key_down(positive)
+            }
+            Binding::Mouse(button) => self.mouse.borrow().is_button_down(button),
+            Binding::WheelX | Binding::WheelY => false,
+        }
+    }
+
+    // --------------------------------------------------------
+    // Driven by the engine
+    // --------------------------------------------------------
+    /// Called by the NodeManager for each input event, except key presses
+    /// a node handled, for example, typing into a focused text field.
+    pub fn io_event(&mut self, io_event: &IOEventData) {
+        match io_event.event {
+            IOEvent::Keyboard => {
+                let key = match io_event.key {
+                    Some(key) => key,
+                    None => return,
+                };
+                let keycode = match key.keycode {
+                    Some(keycode) => keycode,
+                    None => return,
+                };
+
+                match key.action {
+                    KeyAction::Down => self.input_changed(|b| b.uses_key(keycode), true),
+                    KeyAction::Up => self.input_changed(|b| b.uses_key(keycode), false),
+                    KeyAction::Repeat => (),
+                }
+            }
+            IOEvent::Mouse => {
+                let mouse = match io_event.mouse {
+                    Some(mouse) => mouse,
+                    None => return,
+                };
+
+                match (mouse.action, mouse.button) {
+                    (MouseAction::ButtonDown, Some(button)) => {
+                        self.input_changed(|b| *b == Binding::Mouse(button), true)
+                    }
+                    (MouseAction::ButtonUp, Some(button)) => {
+                        self.input_changed(|b| *b == Binding::Mouse(button), false)
+                    }
+                    (MouseAction::Wheel, _) => {
+                        let (wx, wy) = mouse.wheel;
+                        for entry in self.actions.values_mut() {
+                            for binding in entry.bindings.iter() {
+                                match *binding {
+                                    Binding::WheelX if wx != 0 => {
+                                        entry.wheel.0 += wx as f64;
+                                        entry.pressed = true;
+                                    }
+                                    Binding::WheelY if wy != 0 => {
+                                        entry.wheel.1 += wy as f64;
+                                        entry.pressed = true;
+                                    }
+                                    _ => (),
+                                }
+                            }
+                        }
+                    }
+                    _ => (),
+                }
+            }
+            _ => (),
+        }
+    }
+
+    fn input_changed<F>(&mut self, bound: F, down: bool)
+    where
+        F: Fn(&Binding) -> bool,
+    {
+        let names: Vec<String> = self
+            .actions
+            .iter()
+            .filter(|(_, entry)| entry.bindings.iter().any(&bound))
+            .map(|(name, _)| name.clone())
+            .collect();
+
+        for name in names.iter() {
+            // Releasing one of several held inputs doesn't release the action.
+            let released = !down && !self.is_held(name);
+            if let Some(entry) = self.actions.get_mut(name) {
+                if down {
+                    entry.pressed = true;
+                } else if released {
+                    entry.released = true;
+                }
+            }
+        }
+    }
+
+    /// Called by the World after each update.
+    pub fn end_update(&mut self) {
+        for entry in self.actions.values_mut() {
+            entry.pressed = false;
+            entry.released = false;
+            entry.wheel = (0.0, 0.0);
+        }
+    }
+
+    // --------------------------------------------------------
+    // Persistence
+    // --------------------------------------------------------
+    pub fn to_json(&self) -> Value {
+        let mut json = Map::new();
+        for (name, entry) in self.actions.iter() {
+            let bindings = entry.bindings.iter().map(|b| b.to_json()).collect();
+            json.insert(name.clone(), Value::Array(bindings));
+        }
+        Value::Object(json)
+    }
+
+    /// Replaces the bindings of every action listed in `json`. Actions
+    /// that aren't listed keep their bindings, so defaults can be bound in
+    /// code before the config is loaded.
+    pub fn load_json(&mut self, json: &Value) -> Result<(), String> {
+        let actions = match json.as_object() {
+            Some(actions) => actions,
+            None => return Err(String::from("\"actions\" should be an object")),
+        };
+
+        // Parse everything before changing anything.
+        let mut parsed = Vec::new();
+        for (name, list) in actions.iter() {
+            let list = match list.as_array() {
+                Some(list) => list,
+                None => return Err(format!("'{}': bindings should be a list", name)),
+            };
+
+            let mut bindings = Vec::new();
+            for binding in list.iter() {
+                match Binding::from_json(binding) {
+                    Ok(binding) => bindings.push(binding),
+                    Err(err) => return Err(format!("'{}': {}", name, err)),
+                }
+            }
+            parsed.push((name.clone(), bindings));
+        }
+
+        for (name, bindings) in parsed {
+            self.rebind(&name, bindings);
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,146 @@
+use std::any::{Any, TypeId};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+// The event bus decouples node-to-node messaging. Nodes subscribe, by id,
+// to either a typed message (any 'static type) or a named signal. Messages
+// posted during update() are queued and only delivered when the NodeManager
+// reaches a safe point, which is after all updates for the frame have run.
+//
+// Nodes typically hold a clone of the bus handle (see GlobalData::event_bus)
+// so they can post from update() without needing GlobalData.
+
+pub type REventBus = Rc<RefCell<EventBus>>;
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+pub enum Channel {
+    Typed(TypeId),
+    Signal(String),
+}
+
+pub struct Event {
+    pub channel: Channel,
+    // Id of the node that posted the event. 0 = not sent by a node.
+    pub sender: usize,
+    payload: Option<Box<dyn Any>>,
+}
+
+impl Event {
+    /// Returns the message if this event carries a message of type `T`.
+    pub fn message<T: Any>(&self) -> Option<&T> {
+        match self.payload {
+            Some(ref payload) => payload.downcast_ref::<T>(),
+            None => None,
+        }
+    }
+
+    pub fn is_signal(&self, name: &str) -> bool {
+        match self.channel {
+            Channel::Signal(ref signal) => signal == name,
+            _ => false,
+        }
+    }
+}
+
+pub struct EventBus {
+    // Channel -> subscribed node ids
+    subscriptions: HashMap<Channel, Vec<usize>>,
+
+    // Events waiting for the next safe point.
+    queue: Vec<Event>,
+}
+
+impl EventBus {
+    pub fn new() -> REventBus {
+        Rc::new(RefCell::new(Self {
+            subscriptions: HashMap::new(),
+            queue: Vec::new(),
+        }))
+    }
+
+    // --------------------------------------------------------
+    // Subscriptions
+    // --------------------------------------------------------
+    /// Subscribe `node_id` to messages of type `T`.
+    pub fn subscribe<T: Any>(&mut self, node_id: usize) {
+        self.add_subscriber(Channel::Typed(TypeId::of::<T>()), node_id);
+    }
+
+    /// Subscribe `node_id` to the named signal.
+    pub fn subscribe_signal(&mut self, name: &str, node_id: usize) {
+        self.add_subscriber(Channel::Signal(name.to_string()), node_id);
+    }
+
+    pub fn unsubscribe<T: Any>(&mut self, node_id: usize) {
+        self.remove_subscriber(&Channel::Typed(TypeId::of::<T>()), node_id);
+    }
+
+    pub fn unsubscribe_signal(&mut self, name: &str, node_id: usize) {
+        self.remove_subscriber(&Channel::Signal(name.to_string()), node_id);
+    }
+
+    /// Removes every subscription held by `node_id`. Called when a node is flushed.
+    pub fn unsubscribe_all(&mut self, node_id: usize) {
+        for subscribers in self.subscriptions.values_mut() {
+            subscribers.retain(|id| *id != node_id);
+        }
+        self.subscriptions
+            .retain(|_, subscribers| !subscribers.is_empty());
+    }
+
+    pub fn subscribers(&self, channel: &Channel) -> Vec<usize> {
+        match self.subscriptions.get(channel) {
+            Some(subscribers) => subscribers.clone(),
+            None => Vec::new(),
+        }
+    }
+
+    pub fn subscription_count(&self) -> usize {
+        self.subscriptions.values().map(|s| s.len()).sum()
+    }
+
+    fn add_subscriber(&mut self, channel: Channel, node_id: usize) {
+        let subscribers = self.subscriptions.entry(channel).or_default();
+        if !subscribers.contains(&node_id) {
+            subscribers.push(node_id);
+        }
+    }
+
+    fn remove_subscriber(&mut self, channel: &Channel, node_id: usize) {
+        if let Some(subscribers) = self.subscriptions.get_mut(channel) {
+            subscribers.retain(|id| *id != node_id);
+        }
+    }
+
+    // --------------------------------------------------------
+    // Posting
+    // --------------------------------------------------------
+    /// Queue a typed message. It is delivered at the next safe point.
+    pub fn post<T: Any>(&mut self, sender: usize, message: T) {
+        self.queue.push(Event {
+            channel: Channel::Typed(TypeId::of::<T>()),
+            sender,
+            payload: Some(Box::new(message)),
+        });
+    }
+
+    /// Queue a named signal. It is delivered at the next safe point.
+    pub fn emit(&mut self, sender: usize, name: &str) {
+        self.queue.push(Event {
+            channel: Channel::Signal(name.to_string()),
+            sender,
+            payload: None,
+        });
+    }
+
+    pub fn is_queue_empty(&self) -> bool {
+        self.queue.is_empty()
+    }
+
+    /// Removes all queued events. Events posted while these are being
+    /// delivered are held for the next safe point.
+    pub fn take_queue(&mut self) -> Vec<Event> {
+        std::mem::take(&mut self.queue)
+    }
+}
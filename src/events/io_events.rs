@@ -1,16 +1,148 @@
+extern crate sdl2;
+
+pub use self::sdl2::keyboard::{Keycode, Mod, Scancode};
+pub use self::sdl2::mouse::MouseButton;
+
 use nodes::{node_nil::NodeNil, node_trait::RNode};
 
+#[derive(Debug, PartialEq, Clone, Copy)]
 pub enum IOEvent {
     Undefined,
     Mouse,
     Joystick,
     Keyboard,
+    // Committed text, for example, a typed character
+    TextInput,
+    // Text being composed by an input method, not yet committed
+    TextEditing,
+}
+
+// An event travels from the scene down to its target node (Capture), is
+// handled by the target (Target) and then travels back up (Bubble). Only
+// nodes registered for the event type see it, see
+// GlobalData::register_io_event_target.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum EventPhase {
+    Capture,
+    Target,
+    Bubble,
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum EventResult {
+    // Stops propagation
+    Handled,
+    Unhandled,
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum KeyAction {
+    Down,
+    Up,
+    // Key held down long enough for the OS to repeat it
+    Repeat,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct KeyData {
+    pub action: KeyAction,
+    pub keycode: Option<Keycode>,
+    pub scancode: Option<Scancode>,
+    pub modifiers: Mod,
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum MouseAction {
+    Move,
+    ButtonDown,
+    ButtonUp,
+    // Sent after the ButtonDown of a second click
+    DoubleClick,
+    Wheel,
+    DragStart,
+    DragMove,
+    DragEnd,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct MouseData {
+    pub action: MouseAction,
+    // The button pressed, released or dragging. None for Move and Wheel.
+    pub button: Option<MouseButton>,
+    // 1 for a single click, 2 for a double click, etc.
+    pub clicks: u8,
+    // Scroll amount, positive y is away from the user.
+    pub wheel: (i32, i32),
+    // Device position of the press that started a drag
+    pub drag_origin: (i32, i32),
+    pub modifiers: Mod,
+}
+
+impl MouseData {
+    pub fn new(action: MouseAction, modifiers: Mod) -> Self {
+        Self {
+            action,
+            button: None,
+            clicks: 0,
+            wheel: (0, 0),
+            drag_origin: (0, 0),
+            modifiers,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum PointerAction {
+    // The pointer moved onto or off of the node
+    Enter,
+    Leave,
+    // The pointer moved while over the node
+    Hover,
+    Press,
+    // Sent to the pressed node, even if the pointer has moved off of it
+    Release,
+    // Press and release on the same node
+    Click,
+}
+
+// A mouse event targeted at the frontmost node under the pointer. See
+// HitTest.
+#[derive(Debug, Clone, Copy)]
+pub struct PointerEvent {
+    pub action: PointerAction,
+    pub button: Option<MouseButton>,
+    // Node-space
+    pub local: (f64, f64),
+    // Device-space
+    pub coord: (i32, i32),
+    // View-space
+    pub view: (f64, f64),
+    pub modifiers: Mod,
+}
+
+#[derive(Debug, Clone)]
+pub struct TextData {
+    pub text: String,
+    // TextEditing: the cursor position and selection length, in chars,
+    // within the composition.
+    pub start: i32,
+    pub length: i32,
 }
 
 pub struct IOEventData {
     pub event: IOEvent,
+    // Device-space
     pub coord: (i32, i32),
+    // View-space, set by the NodeManager for IOEvent::Mouse
+    pub view: (f64, f64),
+    // The target node, set by the NodeManager
     pub node: RNode,
+    // Set for IOEvent::Keyboard
+    pub key: Option<KeyData>,
+    // Set for IOEvent::Mouse
+    pub mouse: Option<MouseData>,
+    // Set for IOEvent::TextInput and IOEvent::TextEditing
+    pub text: Option<TextData>,
 }
 
 impl IOEventData {
@@ -18,15 +150,86 @@ impl IOEventData {
         Self {
             event: IOEvent::Undefined,
             coord: (0, 0),
+            view: (0.0, 0.0),
             node: NodeNil::new(),
+            key: None,
+            mouse: None,
+            text: None,
         }
     }
 
     pub fn new_mouse_event(x: i32, y: i32) -> Self {
+        IOEventData::new_mouse_action(x, y, MouseData::new(MouseAction::Move, Mod::NOMOD))
+    }
+
+    pub fn new_mouse_action(x: i32, y: i32, mouse: MouseData) -> Self {
         Self {
             event: IOEvent::Mouse,
             coord: (x, y),
+            view: (0.0, 0.0),
+            node: NodeNil::new(),
+            key: None,
+            mouse: Some(mouse),
+            text: None,
+        }
+    }
+
+    pub fn new_key_event(
+        action: KeyAction,
+        keycode: Option<Keycode>,
+        scancode: Option<Scancode>,
+        modifiers: Mod,
+    ) -> Self {
+        Self {
+            event: IOEvent::Keyboard,
+            coord: (0, 0),
+            view: (0.0, 0.0),
+            node: NodeNil::new(),
+            key: Some(KeyData {
+                action,
+                keycode,
+                scancode,
+                modifiers,
+            }),
+            mouse: None,
+            text: None,
+        }
+    }
+
+    pub fn new_text_input(text: String) -> Self {
+        IOEventData::new_text_event(IOEvent::TextInput, text, 0, 0)
+    }
+
+    pub fn new_text_editing(text: String, start: i32, length: i32) -> Self {
+        IOEventData::new_text_event(IOEvent::TextEditing, text, start, length)
+    }
+
+    fn new_text_event(event: IOEvent, text: String, start: i32, length: i32) -> Self {
+        Self {
+            event,
+            coord: (0, 0),
+            view: (0.0, 0.0),
             node: NodeNil::new(),
+            key: None,
+            mouse: None,
+            text: Some(TextData {
+                text,
+                start,
+                length,
+            }),
+        }
+    }
+
+    /// True for a key down (not a repeat) of `keycode`.
+    pub fn is_key_pressed(&self, keycode: Keycode) -> bool {
+        match self.key {
+            Some(ref key) => key.action == KeyAction::Down && key.keycode == Some(keycode),
+            None => false,
         }
     }
+
+    /// The mouse action, if this is a mouse event.
+    pub fn mouse_action(&self) -> Option<MouseAction> {
+        self.mouse.map(|mouse| mouse.action)
+    }
 }
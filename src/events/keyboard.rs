@@ -0,0 +1,81 @@
+extern crate sdl2;
+
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::rc::Rc;
+
+use self::sdl2::keyboard::{Keycode, Mod, Scancode};
+
+// A snapshot of which keys are held down. The World updates it from key
+// events before they are routed to nodes, so it is current during update().
+//
+// Nodes typically hold a clone of the handle (see GlobalData::keyboard) so
+// they can poll keys from update() without needing GlobalData.
+
+pub type RKeyboardState = Rc<RefCell<KeyboardState>>;
+
+pub struct KeyboardState {
+    keys: HashSet<Keycode>,
+    scancodes: HashSet<Scancode>,
+    modifiers: Mod,
+}
+
+impl KeyboardState {
+    pub fn new() -> RKeyboardState {
+        Rc::new(RefCell::new(Self {
+            keys: HashSet::new(),
+            scancodes: HashSet::new(),
+            modifiers: Mod::NOMOD,
+        }))
+    }
+
+    pub fn is_key_down(&self, keycode: Keycode) -> bool {
+        self.keys.contains(&keycode)
+    }
+
+    /// Scancodes are physical key positions, independent of keyboard layout.
+    pub fn is_scancode_down(&self, scancode: Scancode) -> bool {
+        self.scancodes.contains(&scancode)
+    }
+
+    pub fn modifiers(&self) -> Mod {
+        self.modifiers
+    }
+
+    pub fn any_key_down(&self) -> bool {
+        !self.keys.is_empty() || !self.scancodes.is_empty()
+    }
+
+    pub fn key_down(
+        &mut self,
+        keycode: Option<Keycode>,
+        scancode: Option<Scancode>,
+        modifiers: Mod,
+    ) {
+        if let Some(keycode) = keycode {
+            self.keys.insert(keycode);
+        }
+        if let Some(scancode) = scancode {
+            self.scancodes.insert(scancode);
+        }
+        self.modifiers = modifiers;
+    }
+
+    pub fn key_up(&mut self, keycode: Option<Keycode>, scancode: Option<Scancode>, modifiers: Mod) {
+        if let Some(keycode) = keycode {
+            self.keys.remove(&keycode);
+        }
+        if let Some(scancode) = scancode {
+            self.scancodes.remove(&scancode);
+        }
+        self.modifiers = modifiers;
+    }
+
+    /// Releases everything, for example, when the window loses focus and
+    /// the key up events won't arrive.
+    pub fn clear(&mut self) {
+        self.keys.clear();
+        self.scancodes.clear();
+        self.modifiers = Mod::NOMOD;
+    }
+}
@@ -1 +1,5 @@
-pub mod io_events;
\ No newline at end of file
+pub mod action_map;
+pub mod event_bus;
+pub mod io_events;
+pub mod keyboard;
+pub mod mouse;
@@ -0,0 +1,117 @@
+extern crate sdl2;
+
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::rc::Rc;
+
+use self::sdl2::mouse::MouseButton;
+
+use events::io_events::MouseAction;
+
+// Which mouse buttons are held down and whether a drag is in progress.
+// The World updates it from mouse events before they are routed to nodes.
+//
+// A drag starts once the pointer moves more than the drag threshold from
+// where a button was pressed, and ends when that button is released. Only
+// the first button pressed can start a drag.
+
+const DRAG_THRESHOLD: i32 = 4; // pixels
+
+pub type RMouseState = Rc<RefCell<MouseState>>;
+
+// (action, button, device position where the drag began)
+pub type Drag = (MouseAction, MouseButton, (i32, i32));
+
+pub struct MouseState {
+    buttons: HashSet<MouseButton>,
+
+    // The button and device position of the press that may start a drag
+    press: Option<(MouseButton, (i32, i32))>,
+    dragging: bool,
+
+    threshold: i32,
+}
+
+impl MouseState {
+    pub fn new() -> RMouseState {
+        Rc::new(RefCell::new(Self {
+            buttons: HashSet::new(),
+            press: None,
+            dragging: false,
+            threshold: DRAG_THRESHOLD,
+        }))
+    }
+
+    pub fn is_button_down(&self, button: MouseButton) -> bool {
+        self.buttons.contains(&button)
+    }
+
+    pub fn any_button_down(&self) -> bool {
+        !self.buttons.is_empty()
+    }
+
+    pub fn is_dragging(&self) -> bool {
+        self.dragging
+    }
+
+    /// Device position where the current, or potential, drag began.
+    pub fn drag_origin(&self) -> Option<(i32, i32)> {
+        self.press.map(|(_, origin)| origin)
+    }
+
+    /// Distance in pixels the pointer must move before a drag starts.
+    pub fn set_drag_threshold(&mut self, pixels: i32) {
+        self.threshold = pixels;
+    }
+
+    pub fn button_down(&mut self, button: MouseButton, x: i32, y: i32) {
+        self.buttons.insert(button);
+
+        if self.press.is_none() {
+            self.press = Some((button, (x, y)));
+        }
+    }
+
+    /// Returns DragEnd, the drag button and the drag origin if the release
+    /// ends a drag.
+    pub fn button_up(&mut self, button: MouseButton) -> Option<Drag> {
+        self.buttons.remove(&button);
+
+        match self.press {
+            Some((pressed, origin)) if pressed == button => {
+                self.press = None;
+                if self.dragging {
+                    self.dragging = false;
+                    return Some((MouseAction::DragEnd, button, origin));
+                }
+                None
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns DragStart or DragMove, the drag button and the drag origin if
+    /// the motion is part of a drag.
+    pub fn motion(&mut self, x: i32, y: i32) -> Option<Drag> {
+        let (button, origin) = self.press?;
+
+        if self.dragging {
+            return Some((MouseAction::DragMove, button, origin));
+        }
+
+        let (dx, dy) = (x - origin.0, y - origin.1);
+        if dx * dx + dy * dy > self.threshold * self.threshold {
+            self.dragging = true;
+            return Some((MouseAction::DragStart, button, origin));
+        }
+
+        None
+    }
+
+    /// Releases everything, for example, when the window loses focus.
+    pub fn clear(&mut self) {
+        self.buttons.clear();
+        self.press = None;
+        self.dragging = false;
+    }
+}
@@ -44,4 +44,8 @@ impl AABBox {
         self.min.set_xy(minx, miny);
         self.max.set_xy(maxx, maxy);
     }
+
+    pub fn contains_point(&self, p: &Point) -> bool {
+        p.x >= self.min.x && p.x <= self.max.x && p.y >= self.min.y && p.y <= self.max.y
+    }
 }
@@ -1,3 +1,5 @@
 pub mod aabb;
+pub mod path;
 pub mod point;
+pub mod polygon;
 pub mod rectangle;
@@ -0,0 +1,318 @@
+use std::f64::consts::PI;
+
+use geometry::point::Point;
+use geometry::polygon::Polygon;
+
+// A shape made of straight and curved segments, built the way a canvas
+// path is:
+//
+//   let mut heart = Path::new();
+//   heart.move_to(0.0, 3.0);
+//   heart.cubic_to(-6.0, -1.0, -3.0, -6.0, 0.0, -3.0);
+//   heart.cubic_to(3.0, -6.0, 6.0, -1.0, 0.0, 3.0);
+//   heart.close();
+//
+// Each move_to starts a subpath. Before drawing, curves are flattened into
+// polylines that stay within a tolerance of them. Filling follows the
+// even-odd rule for subpaths that don't cross each other, so a subpath
+// inside another is a hole, one inside that is filled again, and so on.
+// Open subpaths are filled as if closed.
+
+// Most straight edges one curve is split into
+const MAX_SEGMENTS: usize = 1024;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum PathCommand {
+    MoveTo(f64, f64),
+    LineTo(f64, f64),
+    // Control point, end point
+    QuadTo(f64, f64, f64, f64),
+    // First and second control points, end point
+    CubicTo(f64, f64, f64, f64, f64, f64),
+    // Center, radius, start angle and sweep in radians. A line joins the
+    // current point to the arc's start.
+    Arc(f64, f64, f64, f64, f64),
+    // Joins the current point to the subpath's first point.
+    Close,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Path {
+    commands: Vec<PathCommand>,
+}
+
+impl Path {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn commands(&self) -> &[PathCommand] {
+        &self.commands
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.commands.clear();
+    }
+
+    pub fn push(&mut self, command: PathCommand) {
+        self.commands.push(command);
+    }
+
+    pub fn move_to(&mut self, x: f64, y: f64) {
+        self.push(PathCommand::MoveTo(x, y));
+    }
+
+    /// Without a current point this starts a subpath at (x, y).
+    pub fn line_to(&mut self, x: f64, y: f64) {
+        self.push(PathCommand::LineTo(x, y));
+    }
+
+    pub fn quad_to(&mut self, cx: f64, cy: f64, x: f64, y: f64) {
+        self.push(PathCommand::QuadTo(cx, cy, x, y));
+    }
+
+    pub fn cubic_to(&mut self, c1x: f64, c1y: f64, c2x: f64, c2y: f64, x: f64, y: f64) {
+        self.push(PathCommand::CubicTo(c1x, c1y, c2x, c2y, x, y));
+    }
+
+    /// Angles are in radians, a positive sweep turns from +x toward +y.
+    pub fn arc(&mut self, cx: f64, cy: f64, radius: f64, start: f64, sweep: f64) {
+        self.push(PathCommand::Arc(cx, cy, radius, start, sweep));
+    }
+
+    pub fn close(&mut self) {
+        self.push(PathCommand::Close);
+    }
+
+    /// The path as polylines within `tolerance` of its curves.
+    pub fn flatten(&self, tolerance: f64) -> FlatPath {
+        let tolerance = tolerance.max(1.0e-6);
+        let mut flat = FlatPath::default();
+        // Where the subpath being built starts in flat.points
+        let mut start = 0;
+        let mut current: Option<(f64, f64)> = None;
+
+        for command in self.commands.iter() {
+            // Every segment starts from the current point.
+            if let Some((x, y)) = current {
+                match *command {
+                    PathCommand::MoveTo(..) | PathCommand::Close => (),
+                    _ => flat.add(x, y),
+                }
+            }
+
+            match *command {
+                PathCommand::MoveTo(x, y) => {
+                    flat.end_subpath(start, false);
+                    start = flat.points.len();
+                    current = Some((x, y));
+                }
+                PathCommand::LineTo(x, y) => {
+                    flat.add(x, y);
+                    current = Some((x, y));
+                }
+                PathCommand::QuadTo(cx, cy, x, y) => {
+                    let (x0, y0) = current.unwrap_or((cx, cy));
+
+                    // Chords of n equal steps stay within |p0 - 2c + p1| / 4n²
+                    let dd = ((x0 - 2.0 * cx + x).powi(2) + (y0 - 2.0 * cy + y).powi(2)).sqrt();
+                    let n = segments((dd / (4.0 * tolerance)).sqrt());
+                    for i in 0..=n {
+                        let t = i as f64 / n as f64;
+                        let u = 1.0 - t;
+                        flat.add(
+                            u * u * x0 + 2.0 * u * t * cx + t * t * x,
+                            u * u * y0 + 2.0 * u * t * cy + t * t * y,
+                        );
+                    }
+                    current = Some((x, y));
+                }
+                PathCommand::CubicTo(c1x, c1y, c2x, c2y, x, y) => {
+                    let (x0, y0) = current.unwrap_or((c1x, c1y));
+
+                    // and a cubic's within 3m / 4n², m the larger second
+                    // difference of its control points.
+                    let m = ((x0 - 2.0 * c1x + c2x).powi(2) + (y0 - 2.0 * c1y + c2y).powi(2))
+                        .sqrt()
+                        .max(
+                            ((c1x - 2.0 * c2x + x).powi(2) + (c1y - 2.0 * c2y + y).powi(2)).sqrt(),
+                        );
+                    let n = segments((3.0 * m / (4.0 * tolerance)).sqrt());
+                    for i in 0..=n {
+                        let t = i as f64 / n as f64;
+                        let u = 1.0 - t;
+                        let (b0, b1, b2, b3) =
+                            (u * u * u, 3.0 * u * u * t, 3.0 * u * t * t, t * t * t);
+                        flat.add(
+                            b0 * x0 + b1 * c1x + b2 * c2x + b3 * x,
+                            b0 * y0 + b1 * c1y + b2 * c2y + b3 * y,
+                        );
+                    }
+                    current = Some((x, y));
+                }
+                PathCommand::Arc(cx, cy, radius, start_angle, sweep) => {
+                    let sweep = sweep.clamp(-2.0 * PI, 2.0 * PI);
+                    let radius = radius.abs();
+
+                    // Each edge spans the angle at which its chord stays
+                    // within the tolerance.
+                    let step = if radius > tolerance {
+                        2.0 * (1.0 - tolerance / radius).acos()
+                    } else {
+                        PI / 2.0
+                    };
+                    let n = segments(sweep.abs() / step);
+                    for i in 0..=n {
+                        let angle = start_angle + sweep * i as f64 / n as f64;
+                        flat.add(cx + radius * angle.cos(), cy + radius * angle.sin());
+                    }
+                    current = flat.points.last().map(|p| (p.x, p.y));
+                }
+                PathCommand::Close => {
+                    // Drawing goes on from the subpath's first point.
+                    let first = flat.points.get(start).cloned();
+                    flat.end_subpath(start, true);
+                    start = flat.points.len();
+                    if let Some(first) = first {
+                        current = Some((first.x, first.y));
+                    }
+                }
+            }
+        }
+
+        flat.end_subpath(start, false);
+        flat
+    }
+}
+
+// Splits a curve into about `count` straight edges.
+fn segments(count: f64) -> usize {
+    if count.is_finite() {
+        (count.ceil() as usize).clamp(1, MAX_SEGMENTS)
+    } else {
+        MAX_SEGMENTS
+    }
+}
+
+/// A path flattened into polylines.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FlatPath {
+    pub points: Vec<Point>,
+    // Where each subpath ends in points, and whether it is closed
+    pub subpaths: Vec<(usize, bool)>,
+}
+
+impl FlatPath {
+    // Adds a point to the subpath being built, unless it repeats the last.
+    fn add(&mut self, x: f64, y: f64) {
+        let p = Point::from_xy(x, y);
+        let open = self.subpaths.last().map_or(0, |&(end, _)| end);
+        if self.points.len() == open || self.points.last() != Some(&p) {
+            self.points.push(p);
+        }
+    }
+
+    // Ends the subpath begun at `start`. A lone point is dropped.
+    fn end_subpath(&mut self, start: usize, closed: bool) {
+        let count = self.points.len() - start;
+        if count == 0 {
+            return;
+        }
+        if count == 1 {
+            self.points.truncate(start);
+            return;
+        }
+
+        if closed && count > 2 && self.points[start] == self.points[self.points.len() - 1] {
+            self.points.pop();
+        }
+        self.subpaths.push((self.points.len(), closed));
+    }
+
+    // Where subpath `index` starts in points
+    fn subpath_start(&self, index: usize) -> usize {
+        if index == 0 {
+            0
+        } else {
+            self.subpaths[index - 1].0
+        }
+    }
+
+    /// The points of each subpath, and whether it is closed.
+    pub fn polylines(&self) -> impl Iterator<Item = (&[Point], bool)> {
+        (0..self.subpaths.len()).map(move |i| {
+            let (end, closed) = self.subpaths[i];
+            (&self.points[self.subpath_start(i)..end], closed)
+        })
+    }
+
+    /// True if `p` is inside the filled area.
+    pub fn contains_point(&self, p: &Point) -> bool {
+        self.polylines()
+            .filter(|&(points, _)| Polygon::contains_point(points, p))
+            .count()
+            % 2
+            == 1
+    }
+
+    /// Triangles filling the path, as indices into points.
+    pub fn triangulate(&self) -> Result<Vec<[usize; 3]>, String> {
+        // (first index, points) of each subpath with an area
+        let contours: Vec<(usize, &[Point])> = (0..self.subpaths.len())
+            .map(|i| {
+                let start = self.subpath_start(i);
+                (start, &self.points[start..self.subpaths[i].0])
+            })
+            .filter(|(_, points)| points.len() > 2)
+            .collect();
+
+        // How many contours each is inside of, and the innermost of them.
+        let nesting: Vec<(usize, Option<usize>)> = contours
+            .iter()
+            .enumerate()
+            .map(|(i, (_, points))| {
+                let mut depth = 0;
+                let mut parent: Option<(usize, f64)> = None;
+                for (j, (_, other)) in contours.iter().enumerate() {
+                    if i == j || !Polygon::contains_point(other, &points[0]) {
+                        continue;
+                    }
+                    depth += 1;
+                    let area = Polygon::signed_area(other).abs();
+                    parent = match parent {
+                        Some((_, smallest)) if smallest <= area => parent,
+                        _ => Some((j, area)),
+                    };
+                }
+                (depth, parent.map(|(j, _)| j))
+            })
+            .collect();
+
+        let mut triangles = Vec::new();
+        for (i, &(start, outline)) in contours.iter().enumerate() {
+            if nesting[i].0 % 2 == 1 {
+                continue;
+            }
+
+            // Polygon numbers the outline's points then each hole's.
+            let mut indices: Vec<usize> = (start..start + outline.len()).collect();
+            let mut holes = Vec::new();
+            for (j, &(hole_start, hole)) in contours.iter().enumerate() {
+                if nesting[j].0 % 2 == 1 && nesting[j].1 == Some(i) {
+                    indices.extend(hole_start..hole_start + hole.len());
+                    holes.push(hole.to_vec());
+                }
+            }
+
+            for t in Polygon::triangulate(outline, &holes)? {
+                triangles.push([indices[t[0]], indices[t[1]], indices[t[2]]]);
+            }
+        }
+
+        Ok(triangles)
+    }
+}
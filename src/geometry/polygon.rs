@@ -0,0 +1,270 @@
+use std::cmp::Ordering;
+use std::ops::Range;
+
+use geometry::point::Point;
+
+pub struct Polygon;
+
+impl Polygon {
+    /// Even-odd test, so self-intersecting polygons have holes where they
+    /// overlap. The polygon is implicitly closed.
+    pub fn contains_point(vertices: &[Point], p: &Point) -> bool {
+        if vertices.len() < 3 {
+            return false;
+        }
+
+        let mut inside = false;
+        let mut j = vertices.len() - 1;
+
+        for (i, vi) in vertices.iter().enumerate() {
+            let vj = &vertices[j];
+            if (vi.y > p.y) != (vj.y > p.y)
+                && p.x < (vj.x - vi.x) * (p.y - vi.y) / (vj.y - vi.y) + vi.x
+            {
+                inside = !inside;
+            }
+            j = i;
+        }
+
+        inside
+    }
+}
+
+// Ear-clipping triangulation.
+//
+// Holes are joined to the outline by a bridge: a pair of edges, there and
+// back, from a hole's rightmost vertex to a vertex of the outline it can
+// see. That leaves one ring, touching itself only along bridges, whose ears
+// are clipped off one at a time.
+impl Polygon {
+    /// Twice the signed area. Positive when the vertices run
+    /// counter-clockwise with y up, which is clockwise on screen.
+    pub fn signed_area(vertices: &[Point]) -> f64 {
+        let mut area = 0.0;
+        let mut j = vertices.len().saturating_sub(1);
+
+        for (i, vi) in vertices.iter().enumerate() {
+            let vj = &vertices[j];
+            area += (vj.x - vi.x) * (vj.y + vi.y);
+            j = i;
+        }
+
+        area
+    }
+
+    /// Triangles covering `outline` less `holes`, as indices into the
+    /// outline's vertices followed by each hole's vertices in turn. Either
+    /// winding is accepted. Contours are implicitly closed and must not
+    /// cross each other or themselves.
+    pub fn triangulate(outline: &[Point], holes: &[Vec<Point>]) -> Result<Vec<[usize; 3]>, String> {
+        if outline.len() < 3 {
+            return Err(String::from("a polygon needs at least 3 vertices"));
+        }
+
+        let mut points = outline.to_vec();
+        let mut ring = wind(&points, 0..outline.len(), true);
+
+        let mut rings = Vec::new();
+        for hole in holes.iter() {
+            if hole.len() < 3 {
+                return Err(String::from("a hole needs at least 3 vertices"));
+            }
+            let start = points.len();
+            points.extend_from_slice(hole);
+            rings.push(wind(&points, start..points.len(), false));
+        }
+
+        // Rightmost first, so bridges only reach holes already joined.
+        let rightmost = |ring: &Vec<usize>| {
+            ring.iter()
+                .map(|&i| points[i].x)
+                .fold(f64::NEG_INFINITY, f64::max)
+        };
+        rings.sort_by(|a, b| {
+            rightmost(b)
+                .partial_cmp(&rightmost(a))
+                .unwrap_or(Ordering::Equal)
+        });
+
+        for hole in rings.iter() {
+            ring = bridge(&points, ring, hole)?;
+        }
+
+        Ok(clip_ears(&points, ring))
+    }
+}
+
+// The indices of `range`, ordered to wind positive or negative.
+fn wind(points: &[Point], range: Range<usize>, positive: bool) -> Vec<usize> {
+    let mut ring: Vec<usize> = range.clone().collect();
+    if (Polygon::signed_area(&points[range]) > 0.0) != positive {
+        ring.reverse();
+    }
+    ring
+}
+
+// Twice the signed area of the triangle a, b, c.
+fn cross(a: &Point, b: &Point, c: &Point) -> f64 {
+    (b.x - a.x) * (c.y - a.y) - (b.y - a.y) * (c.x - a.x)
+}
+
+// Inside or on the edges of the positively wound triangle a, b, c.
+fn in_triangle(p: &Point, a: &Point, b: &Point, c: &Point) -> bool {
+    cross(a, b, p) >= 0.0 && cross(b, c, p) >= 0.0 && cross(c, a, p) >= 0.0
+}
+
+// Splices `hole` into `ring` through a bridge from the hole's rightmost
+// vertex.
+fn bridge(points: &[Point], ring: Vec<usize>, hole: &[usize]) -> Result<Vec<usize>, String> {
+    let mut start = 0;
+    for (k, &i) in hole.iter().enumerate() {
+        if points[i].x > points[hole[start]].x {
+            start = k;
+        }
+    }
+    let m = points[hole[start]];
+    let n = ring.len();
+
+    // The nearest edge to the right of m, where a ray along +x hits it,
+    // and which end of it to try.
+    let mut hit: Option<(f64, usize)> = None;
+    for i in 0..n {
+        let (a, b) = (&points[ring[i]], &points[ring[(i + 1) % n]]);
+        if a.y == b.y || m.y < a.y.min(b.y) || m.y > a.y.max(b.y) {
+            continue;
+        }
+
+        let x = a.x + (m.y - a.y) * (b.x - a.x) / (b.y - a.y);
+        if x < m.x || hit.is_some_and(|(nearest, _)| x >= nearest) {
+            continue;
+        }
+
+        let end = if a.y == m.y {
+            i
+        } else if b.y == m.y || b.x > a.x {
+            (i + 1) % n
+        } else {
+            i
+        };
+        hit = Some((x, end));
+    }
+
+    let (x, end) = match hit {
+        Some(hit) => hit,
+        None => return Err(String::from("a hole is outside the outline")),
+    };
+
+    // Unless it is on the ray, the end can be hidden by reflex vertices
+    // inside the triangle m, hit, end. If so the one closest in angle to the
+    // ray is visible instead.
+    let ray = Point::from_xy(x, m.y);
+    let p = points[ring[end]];
+    let (a, b) = if p.y < m.y { (p, ray) } else { (ray, p) };
+    let mut target = ring[end];
+    let mut best = (f64::INFINITY, f64::INFINITY);
+    if p.y != m.y {
+        for i in 0..n {
+            let v = &points[ring[i]];
+            let reflex = cross(
+                &points[ring[(i + n - 1) % n]],
+                v,
+                &points[ring[(i + 1) % n]],
+            ) < 0.0;
+            if !reflex || *v == p || !in_triangle(v, &m, &a, &b) {
+                continue;
+            }
+
+            let (dx, dy) = (v.x - m.x, v.y - m.y);
+            let key = ((dy.abs() / dx.max(1.0e-12)), dx * dx + dy * dy);
+            if key < best {
+                best = key;
+                target = ring[i];
+            }
+        }
+    }
+
+    // A vertex on an earlier bridge appears twice; use the copy whose
+    // corner m is inside.
+    let copies: Vec<usize> = (0..n).filter(|&i| ring[i] == target).collect();
+    let at = copies
+        .iter()
+        .cloned()
+        .find(|&i| {
+            let (prev, v, next) = (
+                &points[ring[(i + n - 1) % n]],
+                &points[ring[i]],
+                &points[ring[(i + 1) % n]],
+            );
+            if cross(prev, v, next) >= 0.0 {
+                cross(prev, v, &m) > 0.0 && cross(v, next, &m) > 0.0
+            } else {
+                cross(prev, v, &m) > 0.0 || cross(v, next, &m) > 0.0
+            }
+        })
+        .unwrap_or(copies[0]);
+
+    let mut joined = Vec::with_capacity(n + hole.len() + 2);
+    joined.extend_from_slice(&ring[..=at]);
+    joined.extend_from_slice(&hole[start..]);
+    joined.extend_from_slice(&hole[..=start]);
+    joined.push(ring[at]);
+    joined.extend_from_slice(&ring[at + 1..]);
+
+    Ok(joined)
+}
+
+fn clip_ears(points: &[Point], mut ring: Vec<usize>) -> Vec<[usize; 3]> {
+    let mut triangles = Vec::with_capacity(ring.len().saturating_sub(2));
+    let mut i = 0;
+    // Vertices tried since the last one was clipped
+    let mut tried = 0;
+    // Set when no ear is left, as happens with crossing edges. Any convex
+    // vertex is clipped so that something is drawn.
+    let mut forced = false;
+
+    while ring.len() > 3 {
+        let n = ring.len();
+        i %= n;
+        let (prev, vertex, next) = (ring[(i + n - 1) % n], ring[i], ring[(i + 1) % n]);
+        let (a, b, c) = (&points[prev], &points[vertex], &points[next]);
+        let area = cross(a, b, c);
+
+        if area == 0.0 {
+            // Nothing to fill
+            ring.remove(i);
+            tried = 0;
+            continue;
+        }
+
+        let ear = area > 0.0
+            && (forced
+                || ring.iter().all(|&k| {
+                    let p = &points[k];
+                    p == a || p == b || p == c || !in_triangle(p, a, b, c)
+                }));
+
+        if ear {
+            triangles.push([prev, vertex, next]);
+            ring.remove(i);
+            tried = 0;
+            forced = false;
+        } else {
+            i += 1;
+            tried += 1;
+            if tried > n {
+                if forced {
+                    // Nothing convex left
+                    return triangles;
+                }
+                forced = true;
+                tried = 0;
+            }
+        }
+    }
+
+    if ring.len() == 3 && cross(&points[ring[0]], &points[ring[1]], &points[ring[2]]) != 0.0 {
+        triangles.push([ring[0], ring[1], ring[2]]);
+    }
+
+    triangles
+}
@@ -140,4 +140,44 @@ impl Rectangle {
     pub fn contains_point(&self, p: &Point) -> bool {
         p.x >= self.min.x && p.x <= self.max.x && p.y >= self.min.y && p.y <= self.max.y
     }
+
+    /// The part of the segment from `a` to `b` inside the rectangle, as the
+    /// fractions (t0, t1) of the way from `a` to `b` where it enters and
+    /// leaves, or None if it misses (Liang-Barsky).
+    pub fn clip_segment(&self, a: (f64, f64), b: (f64, f64)) -> Option<(f64, f64)> {
+        let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+        let (mut t0, mut t1) = (0.0, 1.0);
+
+        // Each edge as (-direction, distance to it) along the outward normal
+        let edges = [
+            (-dx, a.0 - self.min.x),
+            (dx, self.max.x - a.0),
+            (-dy, a.1 - self.min.y),
+            (dy, self.max.y - a.1),
+        ];
+        for &(p, q) in edges.iter() {
+            if p == 0.0 {
+                // Parallel to the edge
+                if q < 0.0 {
+                    return None;
+                }
+                continue;
+            }
+
+            let t = q / p;
+            if p < 0.0 {
+                if t > t1 {
+                    return None;
+                }
+                t0 = f64::max(t0, t);
+            } else {
+                if t < t0 {
+                    return None;
+                }
+                t1 = f64::min(t1, t);
+            }
+        }
+
+        Some((t0, t1))
+    }
 }
@@ -1,4 +1,6 @@
 pub mod animation;
+pub mod assets;
+pub mod debug;
 pub mod events;
 pub mod geometry;
 pub mod math;
@@ -236,6 +236,15 @@ impl AffineTransform {
         f64::sqrt(self.d * self.d + self.c * self.c)
     }
 
+    /// The most any length is stretched by, its largest singular value.
+    /// Good with skew too.
+    pub fn max_scale(&self) -> f64 {
+        let (a, b, c, d) = (self.a, self.b, self.c, self.d);
+        let e = (a * a + b * b + c * c + d * d) / 2.0;
+        let f = (((a * a + b * b - c * c - d * d) / 2.0).powi(2) + (a * c + b * d).powi(2)).sqrt();
+        (e + f).sqrt()
+    }
+
     // Produces an Axis aligned bounding rectangle/box (AABB)
     pub fn transform_rectangle(&self, rect: &mut Rectangle) {
         // Each corner of the rectangle is transformed. The new positions
@@ -0,0 +1,85 @@
+use std::any::Any;
+
+use events::io_events::IOEventData;
+use nodes::node_properties::{NodeData, RNodeData};
+use rendering::render_context::Context;
+
+// A Component is a reusable behavior attached to a node. Instead of writing
+// a new node type for every combination of behaviors, a node holds a list of
+// components that receive the same callbacks the node does.
+//
+// Each callback is given the owning node's NodeData. A component that changes
+// the transform should mark the node dirty, the owning node then ripples the
+// dirty state to its children.
+
+pub type BComponent = Box<dyn Component>;
+
+pub trait Component {
+    fn as_any(&self) -> &dyn Any;
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+
+    fn name(&self) -> &str;
+
+    // Timing target
+    fn update(&mut self, _dt: f64, _data: &mut NodeData) {}
+
+    // Rendering: visiting and drawing
+    fn interpolate(&mut self, _interpolation: f64, _data: &mut NodeData) {}
+    // Called after the node's draw() with the node's transform applied.
+    fn draw(&mut self, _context: &mut Context, _data: &NodeData) {}
+
+    // IO Events
+    fn io_event(&mut self, _io_event: &IOEventData, _data: &mut NodeData) {}
+}
+
+pub struct Components;
+
+impl Components {
+    pub fn update(data: &RNodeData, dt: f64) -> bool {
+        Components::dispatch(data, |component, node_data| component.update(dt, node_data))
+    }
+
+    pub fn interpolate(data: &RNodeData, interpolation: f64) -> bool {
+        Components::dispatch(data, |component, node_data| {
+            component.interpolate(interpolation, node_data)
+        })
+    }
+
+    pub fn io_event(data: &RNodeData, io_event: &IOEventData) -> bool {
+        Components::dispatch(data, |component, node_data| {
+            component.io_event(io_event, node_data)
+        })
+    }
+
+    pub fn draw(data: &RNodeData, context: &mut Context) {
+        Components::dispatch(data, |component, node_data| {
+            component.draw(context, node_data)
+        });
+    }
+
+    // The components are moved out of the NodeData while they run so that
+    // each can be handed the NodeData mutably.
+    // Returns true if the callbacks changed the node from clean to dirty.
+    fn dispatch<F>(data: &RNodeData, mut callback: F) -> bool
+    where
+        F: FnMut(&mut dyn Component, &mut NodeData),
+    {
+        let mut components = std::mem::take(&mut data.borrow_mut().components);
+        if components.is_empty() {
+            return false;
+        }
+
+        let mut node_data = data.borrow_mut();
+        let was_dirty = node_data.node.is_dirty();
+
+        for component in components.iter_mut() {
+            callback(component.as_mut(), &mut node_data);
+        }
+
+        // Keep any components that were added during the callbacks.
+        components.append(&mut node_data.components);
+        node_data.components = components;
+
+        !was_dirty && node_data.node.is_dirty()
+    }
+}
@@ -0,0 +1,88 @@
+use std::any::Any;
+
+use math::interpolation::Interpolation;
+use nodes::{
+    components::component::Component,
+    node_properties::{NodeData, RNodeData},
+};
+
+// Makes the owning node follow another node's position plus an offset.
+// Positions are parent-space values, so the target should share the
+// owner's parent (or a parent with the same transform).
+//
+// `stiffness` is the fraction (0.0 -> 1.0] of the remaining distance covered
+// each update. 1.0 = snap to the target.
+
+pub struct FollowNode {
+    target: RNodeData,
+    offset: (f64, f64),
+    stiffness: f64,
+
+    started: bool,
+    from: (f64, f64),
+    to: (f64, f64),
+}
+
+impl FollowNode {
+    /// `target` must not be the owning node's own data.
+    pub fn new(target: RNodeData, offset: (f64, f64), stiffness: f64) -> Self {
+        Self {
+            target,
+            offset,
+            stiffness: stiffness.clamp(0.0, 1.0),
+            started: false,
+            from: (0.0, 0.0),
+            to: (0.0, 0.0),
+        }
+    }
+
+    pub fn set_offset(&mut self, x: f64, y: f64) {
+        self.offset = (x, y);
+    }
+
+    pub fn set_stiffness(&mut self, stiffness: f64) {
+        self.stiffness = stiffness.clamp(0.0, 1.0);
+    }
+}
+
+impl Component for FollowNode {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "FollowNode"
+    }
+
+    fn update(&mut self, _dt: f64, data: &mut NodeData) {
+        if !self.started {
+            self.to = data.transform.get_position();
+            self.started = true;
+        }
+
+        let target = self.target.borrow().transform.get_position();
+        let goal = (target.0 + self.offset.0, target.1 + self.offset.1);
+
+        self.from = self.to;
+        self.to = (
+            Interpolation::lerp(self.to.0, goal.0, self.stiffness),
+            Interpolation::lerp(self.to.1, goal.1, self.stiffness),
+        );
+    }
+
+    fn interpolate(&mut self, interpolation: f64, data: &mut NodeData) {
+        if !self.started {
+            return;
+        }
+
+        data.transform.set_position(
+            Interpolation::lerp(self.from.0, self.to.0, interpolation),
+            Interpolation::lerp(self.from.1, self.to.1, interpolation),
+        );
+        data.node.set_dirty(true);
+    }
+}
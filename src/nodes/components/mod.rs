@@ -0,0 +1,4 @@
+pub mod component;
+pub mod follow_node;
+pub mod oscillator;
+pub mod rotator;
@@ -0,0 +1,108 @@
+use std::any::Any;
+use std::f64::consts::PI;
+
+use math::interpolation::Interpolation;
+use nodes::{components::component::Component, node_properties::NodeData};
+
+// Moves the owning node back and forth along an axis about the position
+// it had at the first update.
+//
+//   position = anchor + axis * amplitude * sin(phase)
+//
+// `period` is the time, in milliseconds, of one full oscillation.
+
+pub struct Oscillator {
+    axis: (f64, f64),
+    amplitude: f64,
+    period: f64,
+
+    anchor: (f64, f64),
+    started: bool,
+
+    // Phase (radians) before and after the last update
+    from: f64,
+    to: f64,
+}
+
+impl Oscillator {
+    /// `axis` is normalized. A zero length axis oscillates along +X.
+    pub fn new(axis: (f64, f64), amplitude: f64, period: f64) -> Self {
+        let mut osc = Self {
+            axis: (1.0, 0.0),
+            amplitude,
+            period,
+            anchor: (0.0, 0.0),
+            started: false,
+            from: 0.0,
+            to: 0.0,
+        };
+
+        osc.set_axis(axis.0, axis.1);
+
+        osc
+    }
+
+    pub fn set_axis(&mut self, x: f64, y: f64) {
+        let length = (x * x + y * y).sqrt();
+        if length > 0.0 {
+            self.axis = (x / length, y / length);
+        }
+    }
+
+    pub fn set_amplitude(&mut self, amplitude: f64) {
+        self.amplitude = amplitude;
+    }
+
+    pub fn set_period(&mut self, period: f64) {
+        self.period = period;
+    }
+}
+
+impl Component for Oscillator {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "Oscillator"
+    }
+
+    fn update(&mut self, dt: f64, data: &mut NodeData) {
+        if !self.started {
+            self.anchor = data.transform.get_position();
+            self.started = true;
+        }
+
+        if self.period <= 0.0 {
+            return;
+        }
+
+        // Keep the phase bounded. Both ends are wrapped together so the
+        // interpolation between them remains continuous.
+        if self.to >= 2.0 * PI {
+            self.to -= 2.0 * PI;
+        }
+
+        self.from = self.to;
+        self.to += 2.0 * PI * dt / self.period;
+    }
+
+    fn interpolate(&mut self, interpolation: f64, data: &mut NodeData) {
+        if !self.started {
+            return;
+        }
+
+        let phase = Interpolation::lerp(self.from, self.to, interpolation);
+        let offset = self.amplitude * phase.sin();
+
+        data.transform.set_position(
+            self.anchor.0 + self.axis.0 * offset,
+            self.anchor.1 + self.axis.1 * offset,
+        );
+        data.node.set_dirty(true);
+    }
+}
@@ -0,0 +1,62 @@
+use std::any::Any;
+
+use animation::motion::AngularMotion;
+use nodes::{components::component::Component, node_properties::NodeData};
+
+// Rotates the owning node continuously by `step` degrees per update.
+// The rotation starts from the node's rotation at the first update.
+
+pub struct Rotator {
+    angle_motion: AngularMotion,
+    started: bool,
+}
+
+impl Rotator {
+    pub fn new(step: f64) -> Self {
+        let mut angle_motion = AngularMotion::new();
+        angle_motion.set_step_value(step);
+
+        Self {
+            angle_motion,
+            started: false,
+        }
+    }
+
+    pub fn set_step_value(&mut self, step: f64) {
+        self.angle_motion.set_step_value(step);
+    }
+}
+
+impl Component for Rotator {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "Rotator"
+    }
+
+    fn update(&mut self, dt: f64, data: &mut NodeData) {
+        if !self.started {
+            let degrees = data.transform.rotation_in_degrees();
+            self.angle_motion.set(degrees, degrees);
+            self.started = true;
+        }
+
+        self.angle_motion.update(dt);
+    }
+
+    fn interpolate(&mut self, interpolation: f64, data: &mut NodeData) {
+        if !self.started {
+            return;
+        }
+
+        let value = self.angle_motion.interpolate(interpolation);
+        data.transform.set_rotation_degrees(value);
+        data.node.set_dirty(true);
+    }
+}
@@ -0,0 +1,338 @@
+use std::any::Any;
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use animation::frame_animation::{Clip, ClipEvent, FrameAnimation};
+use assets::atlas::RAtlas;
+use geometry::{
+    point::{Point, RVertices},
+    rectangle::Rectangle,
+};
+use nodes::{
+    hit_test::HitShape,
+    node_group::NodeGroup,
+    node_properties::{NodeData, RNodeData},
+    node_trait::{NodeTrait, NodeType, RNode},
+};
+use rendering::render_context::Context;
+use world::World;
+
+// A sprite that shows frames from an atlas, playing named clips.
+//
+//   let atlas = Rc::new(Atlas::from_file("assets/hero.json", &mut textures)?);
+//   ...
+//   sprite.set_atlas(Some(atlas));
+//   sprite.on_clip_event(Box::new(|sprite, clip, event| {
+//       if clip == "attack" && event == ClipEvent::Completed {
+//           let _ = sprite.play("idle");
+//       }
+//   }));
+//   sprite.play("walk")?;
+//
+// Clips are looked up on the node first, then in the atlas. The node is a
+// timing target and advances during update(). As with SpriteNode, one unit
+// of node space is one texel and each frame's pivot sits at the node's
+// position.
+
+pub type ClipCallback = Box<dyn FnMut(&AnimatedSpriteNode, &str, ClipEvent)>;
+
+pub struct AnimatedSpriteNode {
+    data: RNodeData,
+
+    // Hierarchy
+    parent: Cell<usize>,
+
+    atlas: RefCell<Option<RAtlas>>,
+    clips: RefCell<HashMap<String, Clip>>,
+
+    // The clip being played and its name
+    clip: RefCell<Option<(String, Clip)>>,
+    animation: RefCell<FrameAnimation>,
+    // Atlas frame shown
+    frame: Cell<usize>,
+
+    callback: RefCell<Option<ClipCallback>>,
+
+    // The frame's corners in node space
+    vertices: RefCell<Vec<Point>>,
+    // Transformed vertices
+    bucket: RefCell<Vec<Point>>,
+}
+
+impl Drop for AnimatedSpriteNode {
+    fn drop(&mut self) {
+        println!("Dropping: '{}'", self.data().borrow().node.name());
+    }
+}
+
+impl AnimatedSpriteNode {
+    pub fn new(name: &str, parent: usize, world: &mut World) -> RNode {
+        let mut n = NodeData::new();
+        n.node.set_name(name.to_string());
+        n.node.set_type(NodeType::Node);
+        n.node.set_id(world.gen_id());
+        n.node.make_timing_target(true);
+
+        let sn = Self {
+            data: Rc::new(RefCell::new(n)),
+            parent: Cell::new(parent),
+            atlas: RefCell::new(None),
+            clips: RefCell::new(HashMap::new()),
+            clip: RefCell::new(None),
+            animation: RefCell::new(FrameAnimation::default()),
+            frame: Cell::new(0),
+            callback: RefCell::new(None),
+            vertices: RefCell::new(vec![Point::new(); 4]),
+            bucket: RefCell::new(vec![Point::new(); 4]),
+        };
+
+        let rc: RNode = Rc::new(RefCell::new(sn));
+
+        world.data_mut().add_node(rc.clone());
+
+        NodeGroup::attach_parent(&rc, world.data());
+
+        rc
+    }
+
+    pub fn atlas(&self) -> Option<RAtlas> {
+        self.atlas.borrow().clone()
+    }
+
+    /// Stops playback and shows the atlas's first frame.
+    pub fn set_atlas(&self, atlas: Option<RAtlas>) {
+        self.atlas.replace(atlas);
+        self.show_frame(0);
+    }
+
+    /// Adds, or replaces, a clip known only to this node.
+    pub fn add_clip(&self, name: &str, clip: Clip) {
+        self.clips.borrow_mut().insert(name.to_string(), clip);
+    }
+
+    /// Called with the clip's name when a clip loops or completes. The
+    /// callback may play another clip.
+    pub fn on_clip_event(&self, callback: ClipCallback) {
+        self.callback.replace(Some(callback));
+    }
+
+    // --------------------------------------------------------
+    // Playback
+    // --------------------------------------------------------
+    /// Plays `name` from its first frame unless it is already playing.
+    pub fn play(&self, name: &str) -> Result<(), String> {
+        let playing = match &*self.clip.borrow() {
+            Some((current, _)) => current == name && self.is_playing(),
+            None => false,
+        };
+        if playing {
+            return Ok(());
+        }
+
+        self.restart(name)
+    }
+
+    /// Plays `name` from its first frame.
+    pub fn restart(&self, name: &str) -> Result<(), String> {
+        let clip = self.find_clip(name)?;
+        if clip.frames().is_empty() {
+            return Err(format!("clip '{}' has no frames", name));
+        }
+
+        self.clip.replace(Some((name.to_string(), clip)));
+        self.animation.borrow_mut().restart();
+        self.sync_frame();
+
+        Ok(())
+    }
+
+    fn find_clip(&self, name: &str) -> Result<Clip, String> {
+        if let Some(clip) = self.clips.borrow().get(name) {
+            return Ok(clip.clone());
+        }
+
+        if let Some(atlas) = &*self.atlas.borrow() {
+            if let Some(clip) = atlas.clip(name) {
+                return Ok(clip.clone());
+            }
+        }
+
+        Err(format!("unknown clip '{}'", name))
+    }
+
+    /// Freezes on the current frame.
+    pub fn stop(&self) {
+        self.animation.borrow_mut().stop();
+    }
+
+    /// Continues a stopped clip from where it stopped.
+    pub fn resume(&self) {
+        if self.clip.borrow().is_some() {
+            self.animation.borrow_mut().resume();
+        }
+    }
+
+    pub fn is_playing(&self) -> bool {
+        self.animation.borrow().is_playing()
+    }
+
+    /// The name of the clip playing, or last played.
+    pub fn clip_name(&self) -> Option<String> {
+        self.clip.borrow().as_ref().map(|(name, _)| name.clone())
+    }
+
+    /// 1.0 plays clips as authored, 2.0 twice as fast.
+    pub fn set_speed(&self, speed: f64) {
+        self.animation.borrow_mut().set_speed(speed);
+    }
+
+    /// The atlas frame shown.
+    pub fn frame(&self) -> usize {
+        self.frame.get()
+    }
+
+    /// Stops any clip and shows atlas frame `index`.
+    pub fn show_frame(&self, index: usize) {
+        self.animation.borrow_mut().stop();
+        self.clip.replace(None);
+        self.frame.set(index);
+        self.shape_changed();
+    }
+
+    fn sync_frame(&self) {
+        let frame = match &*self.clip.borrow() {
+            Some((_, clip)) => self.animation.borrow().frame(clip),
+            None => None,
+        };
+
+        if let Some(frame) = frame {
+            if frame != self.frame.get() {
+                self.frame.set(frame);
+                self.shape_changed();
+            }
+        }
+    }
+
+    // --------------------------------------------------------
+    // Geometry
+    // --------------------------------------------------------
+    /// The shown frame's rectangle in node space.
+    pub fn local_bounds(&self) -> Rectangle {
+        let atlas = self.atlas.borrow();
+        let frame = atlas.as_ref().and_then(|a| a.frame(self.frame.get()));
+
+        match frame {
+            Some(frame) => {
+                let (width, height) = (frame.region.width as f64, frame.region.height as f64);
+                let (min_x, min_y) = (-frame.pivot.0 * width, -frame.pivot.1 * height);
+                Rectangle::from_min_max(min_x, min_y, min_x + width, min_y + height)
+            }
+            None => Rectangle::from_min_max(0.0, 0.0, 0.0, 0.0),
+        }
+    }
+
+    fn shape_changed(&self) {
+        let bounds = self.local_bounds();
+        {
+            let mut vertices = self.vertices.borrow_mut();
+            vertices[0] = Point::from_xy(bounds.min.x, bounds.min.y);
+            vertices[1] = Point::from_xy(bounds.max.x, bounds.min.y);
+            vertices[2] = Point::from_xy(bounds.max.x, bounds.max.y);
+            vertices[3] = Point::from_xy(bounds.min.x, bounds.max.y);
+        }
+        self.set_node_dirty(true);
+    }
+}
+
+impl NodeTrait for AnimatedSpriteNode {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    // --------------------------------------------------------
+    // Node properties
+    // --------------------------------------------------------
+    fn data(&self) -> &RNodeData {
+        &self.data
+    }
+
+    // --------------------------------------------------------
+    // Timing target
+    // --------------------------------------------------------
+    fn update(&self, dt: f64) {
+        // dt is in milliseconds, clips are timed in seconds.
+        let (name, events) = match &*self.clip.borrow() {
+            Some((name, clip)) => (
+                name.clone(),
+                self.animation.borrow_mut().advance(clip, dt / 1000.0),
+            ),
+            None => return,
+        };
+
+        self.sync_frame();
+
+        if events.is_empty() {
+            return;
+        }
+
+        // Taken out while it runs so it can replace itself.
+        let callback = self.callback.borrow_mut().take();
+        if let Some(mut callback) = callback {
+            for event in events {
+                callback(self, &name, event);
+            }
+
+            let mut slot = self.callback.borrow_mut();
+            if slot.is_none() {
+                *slot = Some(callback);
+            }
+        }
+    }
+
+    // --------------------------------------------------------
+    // Rendering
+    // --------------------------------------------------------
+    fn draw(&self, context: &mut Context) {
+        if self.is_node_dirty() {
+            context.transform(&self.vertices.borrow(), &self.bucket);
+            self.set_node_dirty(false);
+        }
+
+        if let Some(atlas) = &*self.atlas.borrow() {
+            if let Some(frame) = atlas.frame(self.frame.get()) {
+                context.render_texture(atlas.texture(), &frame.region, &self.local_bounds());
+            }
+        }
+    }
+
+    fn device_vertices(&self) -> Option<&RVertices> {
+        Some(&self.bucket)
+    }
+
+    // --------------------------------------------------------
+    // Input
+    // --------------------------------------------------------
+    fn hit_shape(&self) -> HitShape {
+        if self.atlas.borrow().is_some() {
+            HitShape::Polygon
+        } else {
+            HitShape::None
+        }
+    }
+
+    // --------------------------------------------------------
+    // Transformations
+    // --------------------------------------------------------
+    fn parent(&self) -> usize {
+        self.parent.get()
+    }
+
+    fn set_parent(&self, parent: usize) {
+        self.parent.replace(parent);
+    }
+}
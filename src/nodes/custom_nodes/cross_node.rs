@@ -3,7 +3,7 @@ use std::any::Any;
 use std::cell::{Cell, RefCell};
 use std::rc::Rc;
 
-use geometry::point::Point;
+use geometry::point::{Point, RVertices};
 use nodes::{
     node_group::NodeGroup,
     node_properties::{NodeData, RNodeData},
@@ -105,6 +105,10 @@ impl NodeTrait for CrossNode {
         context.render_lines(&self.bucket);
     }
 
+    fn device_vertices(&self) -> Option<&RVertices> {
+        Some(&self.bucket)
+    }
+
     // --------------------------------------------------------
     // Transformations
     // --------------------------------------------------------
@@ -1,4 +1,11 @@
+pub mod animated_sprite_node;
 pub mod cross_node;
 pub mod orbit_anchor_node;
+pub mod parallax_layer;
+pub mod path_node;
+pub mod polygon_node;
+pub mod sprite_node;
+pub mod text_field_node;
+pub mod text_node;
 pub mod transform_filter;
 pub mod vector_text_node;
@@ -0,0 +1,287 @@
+use std::any::Any;
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+use geometry::point::Point;
+use math::affine_transform::AffineTransform;
+use nodes::{
+    node_group::NodeGroup,
+    node_properties::{NodeData, RNodeData},
+    node_trait::{NodeTrait, NodeType, OChildren, RNode},
+};
+use rendering::render_context::Context;
+use world::{GlobalData, World};
+
+// A layer whose children scroll at a fraction of a camera offset.
+//
+//   scroll = -camera * factor
+//
+// A factor of 0.0 stays fixed to the view, 1.0 moves with the world and
+// values in between appear farther away. The camera offset is either set
+// each update with set_camera() or read from a node with follow().
+//
+// With wrapping enabled the children are treated as one tile of
+// `tile_size` (in the layer's local space) that repeats to cover the view
+// on the wrapped axis. Each visible copy is a separate visit so the
+// children's device vertices are recomputed for every copy. They keep the
+// last copy's, and hit tests on the other copies are mapped onto it.
+
+pub struct ParallaxLayer {
+    data: RNodeData,
+
+    // Hierarchy
+    parent: Cell<usize>,
+
+    children: OChildren,
+
+    factor: Cell<(f64, f64)>,
+    camera: Cell<(f64, f64)>,
+    // The camera is this node's position when set.
+    follow: RefCell<Option<RNodeData>>,
+
+    wrap: Cell<(bool, bool)>,
+    tile_size: Cell<(f64, f64)>,
+
+    // Scroll applied during the previous visit
+    last_scroll: Cell<(f64, f64)>,
+    // The device transform and tile offsets of the previous visit, in
+    // draw order
+    last_tiles: RefCell<(AffineTransform, Vec<(f64, f64)>)>,
+}
+
+impl Drop for ParallaxLayer {
+    fn drop(&mut self) {
+        println!("Dropping: '{}'", self.data().borrow().node.name());
+    }
+}
+
+impl ParallaxLayer {
+    pub fn new(name: &str, parent: usize, world: &mut World) -> RNode {
+        let mut n = NodeData::new();
+        n.node.set_name(name.to_string());
+        n.node.set_type(NodeType::Node);
+        n.node.set_id(world.gen_id());
+
+        let pl = Self {
+            data: Rc::new(RefCell::new(n)),
+            parent: Cell::new(parent),
+            children: Some(RefCell::new(Vec::new())),
+            factor: Cell::new((1.0, 1.0)),
+            camera: Cell::new((0.0, 0.0)),
+            follow: RefCell::new(None),
+            wrap: Cell::new((false, false)),
+            tile_size: Cell::new((0.0, 0.0)),
+            last_scroll: Cell::new((0.0, 0.0)),
+            last_tiles: RefCell::new((AffineTransform::new(), Vec::new())),
+        };
+
+        let rc: RNode = Rc::new(RefCell::new(pl));
+
+        world.data_mut().add_node(rc.clone());
+
+        NodeGroup::attach_parent(&rc, world.data());
+
+        rc
+    }
+
+    pub fn set_factor(&self, fx: f64, fy: f64) {
+        self.factor.set((fx, fy));
+    }
+
+    /// The camera offset in the layer's parent space.
+    pub fn set_camera(&self, x: f64, y: f64) {
+        self.camera.set((x, y));
+    }
+
+    /// Uses `target`'s position as the camera offset, `None` goes back to
+    /// set_camera().
+    pub fn follow(&self, target: Option<RNodeData>) {
+        self.follow.replace(target);
+    }
+
+    /// A tile size of zero on an axis disables wrapping on that axis.
+    pub fn set_wrap(&self, wrap_x: bool, wrap_y: bool, tile_width: f64, tile_height: f64) {
+        self.wrap.set((wrap_x, wrap_y));
+        self.tile_size.set((tile_width, tile_height));
+    }
+
+    fn scroll(&self) -> (f64, f64) {
+        let (cx, cy) = match &*self.follow.borrow() {
+            Some(target) => target.borrow().transform.get_position(),
+            None => self.camera.get(),
+        };
+        let (fx, fy) = self.factor.get();
+
+        (-cx * fx, -cy * fy)
+    }
+
+    // Offsets of every copy of the tile that overlaps the window. `device`
+    // maps the layer's local space to device space.
+    fn tile_offsets(
+        &self,
+        scroll: (f64, f64),
+        device: &AffineTransform,
+        gdata: &GlobalData,
+    ) -> Vec<(f64, f64)> {
+        let (wrap_x, wrap_y) = self.wrap.get();
+        let (tile_w, tile_h) = self.tile_size.get();
+        let wrap_x = wrap_x && tile_w > 0.0;
+        let wrap_y = wrap_y && tile_h > 0.0;
+
+        if !wrap_x && !wrap_y {
+            return vec![scroll];
+        }
+
+        // The window's bounds in local space
+        let inverse = device.inverse();
+        let w = gdata.window_width as f64;
+        let h = gdata.window_height as f64;
+        let mut min = (f64::MAX, f64::MAX);
+        let mut max = (f64::MIN, f64::MIN);
+        let mut local = Point::new();
+        for &(x, y) in [(0.0, 0.0), (w, 0.0), (0.0, h), (w, h)].iter() {
+            AffineTransform::transform_to_point(&Point::from_xy(x, y), &mut local, &inverse);
+            min = (min.0.min(local.x), min.1.min(local.y));
+            max = (max.0.max(local.x), max.1.max(local.y));
+        }
+
+        let xs = if wrap_x {
+            ParallaxLayer::span(scroll.0, tile_w, min.0, max.0)
+        } else {
+            vec![scroll.0]
+        };
+        let ys = if wrap_y {
+            ParallaxLayer::span(scroll.1, tile_h, min.1, max.1)
+        } else {
+            vec![scroll.1]
+        };
+
+        let mut offsets = Vec::with_capacity(xs.len() * ys.len());
+        for &y in ys.iter() {
+            for &x in xs.iter() {
+                offsets.push((x, y));
+            }
+        }
+
+        offsets
+    }
+
+    // Tile origins along one axis so the tiles cover [min, max]. The extra
+    // tile covers content that is either centered on, or starts at, the
+    // tile's origin.
+    fn span(scroll: f64, size: f64, min: f64, max: f64) -> Vec<f64> {
+        let first = ((min - scroll) / size).floor() as i64 - 1;
+        let last = ((max - scroll) / size).ceil() as i64;
+
+        (first..=last).map(|i| scroll + i as f64 * size).collect()
+    }
+}
+
+impl NodeTrait for ParallaxLayer {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    // --------------------------------------------------------
+    // Node properties
+    // --------------------------------------------------------
+    fn data(&self) -> &RNodeData {
+        &self.data
+    }
+
+    // --------------------------------------------------------
+    // Transformations
+    // --------------------------------------------------------
+    fn parent(&self) -> usize {
+        self.parent.get()
+    }
+
+    fn set_parent(&self, parent: usize) {
+        self.parent.replace(parent);
+    }
+
+    // --------------------------------------------------------
+    // Rendering: visiting and drawing
+    // --------------------------------------------------------
+    fn visit(&self, context: &mut Context, interpolation: f64, gdata: &GlobalData) {
+        if !self.is_visible() {
+            return;
+        }
+
+        context.save();
+
+        self.visit_transform(context, interpolation);
+        let device = *context.current();
+
+        self.draw_node(context);
+
+        let scroll = self.scroll();
+        let offsets = self.tile_offsets(scroll, &device, gdata);
+
+        // The children's cached vertices are stale if they are drawn more
+        // than once or the scroll moved.
+        let stale = offsets.len() > 1 || scroll != self.last_scroll.get();
+        self.last_scroll.set(scroll);
+        self.last_tiles.replace((device, offsets.clone()));
+
+        if let Some(children) = self.get_children() {
+            let mut offset_aft = AffineTransform::new();
+
+            for &(x, y) in offsets.iter() {
+                context.save();
+                offset_aft.make_translate(x, y);
+                context.apply(&offset_aft);
+
+                for child in children.borrow().iter() {
+                    let ch = child.borrow();
+                    if stale {
+                        ch.ripple_node_dirty(true);
+                    }
+                    ch.visit(context, interpolation, gdata);
+                }
+
+                context.restore();
+            }
+        }
+
+        context.restore();
+    }
+
+    // --------------------------------------------------------
+    // Hit testing
+    // --------------------------------------------------------
+    // A point on one copy is where the same spot of the children is on the
+    // last copy, shifted by the difference between the copies' offsets.
+    fn child_hit_points(&self, device: &Point) -> Vec<Point> {
+        let tiles = self.last_tiles.borrow();
+        let (aft, offsets) = &*tiles;
+        let last = match offsets.last() {
+            Some(&last) if offsets.len() > 1 => last,
+            _ => return vec![*device],
+        };
+
+        let mut local = Point::new();
+        AffineTransform::transform_to_point(device, &mut local, &aft.inverse());
+
+        // Later copies are drawn in front.
+        offsets
+            .iter()
+            .rev()
+            .map(|&(x, y)| {
+                let (dx, dy) = aft.mul_components(local.x + last.0 - x, local.y + last.1 - y);
+                Point::from_xy(dx, dy)
+            })
+            .collect()
+    }
+
+    // --------------------------------------------------------
+    // Grouping
+    // --------------------------------------------------------
+    fn get_children(&self) -> &OChildren {
+        &self.children
+    }
+}
@@ -0,0 +1,220 @@
+use std::any::Any;
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+use geometry::{
+    path::{FlatPath, Path},
+    point::{Point, RVertices},
+};
+use nodes::{
+    hit_test::HitShape,
+    node_group::NodeGroup,
+    node_properties::{NodeData, RNodeData},
+    node_trait::{NodeTrait, NodeType, RNode},
+};
+use rendering::{
+    color::Palette,
+    render_context::{Context, RenderStyle},
+};
+use world::World;
+
+// A leaf node that draws a Path of lines, Bezier curves and arcs, filled,
+// stroked or both.
+//
+//   let logo = PathNode::new("Logo", layer_id, world);
+//   if let Some(p) = logo.borrow().as_any().downcast_ref::<PathNode>() {
+//       let mut path = Path::new();
+//       path.move_to(-1.0, 0.0);
+//       path.quad_to(0.0, -1.0, 1.0, 0.0);
+//       path.close();
+//       p.set_path(path);
+//   }
+//
+// The path is flattened in node space, finely enough for the scale it is
+// drawn at, and kept along with its triangles. Only zooming in, or out by
+// more than half, flattens it again; otherwise drawing just transforms the
+// points.
+
+pub struct PathNode {
+    data: RNodeData,
+
+    // Hierarchy
+    parent: Cell<usize>,
+
+    path: RefCell<Path>,
+    // The path flattened in node space and its triangles
+    flat: RefCell<FlatPath>,
+    triangles: RefCell<Vec<[usize; 3]>>,
+    // The tolerance flat was made with, 0.0 when it is out of date
+    tolerance: Cell<f64>,
+    // Transformed points
+    bucket: RefCell<Vec<Point>>,
+
+    style: Cell<RenderStyle>,
+    fill_color: Cell<Palette>,
+    outline_color: Cell<Palette>,
+}
+
+impl Drop for PathNode {
+    fn drop(&mut self) {
+        println!("Dropping: '{}'", self.data().borrow().node.name());
+    }
+}
+
+impl PathNode {
+    pub fn new(name: &str, parent: usize, world: &mut World) -> RNode {
+        let mut n = NodeData::new();
+        n.node.set_name(name.to_string());
+        n.node.set_type(NodeType::Node);
+        n.node.set_id(world.gen_id());
+
+        let pn = Self {
+            data: Rc::new(RefCell::new(n)),
+            parent: Cell::new(parent),
+            path: RefCell::new(Path::new()),
+            flat: RefCell::new(FlatPath::default()),
+            triangles: RefCell::new(Vec::new()),
+            tolerance: Cell::new(0.0),
+            bucket: RefCell::new(Vec::new()),
+            style: Cell::new(RenderStyle::Filled),
+            fill_color: Cell::new(Palette::WHITE(255)),
+            outline_color: Cell::new(Palette::WHITE(255)),
+        };
+
+        let rc: RNode = Rc::new(RefCell::new(pn));
+
+        world.data_mut().add_node(rc.clone());
+
+        NodeGroup::attach_parent(&rc, world.data());
+
+        rc
+    }
+
+    pub fn path(&self) -> Path {
+        self.path.borrow().clone()
+    }
+
+    /// Replaces the path, in node space.
+    pub fn set_path(&self, path: Path) {
+        self.path.replace(path);
+        self.tolerance.set(0.0);
+    }
+
+    pub fn style(&self) -> RenderStyle {
+        self.style.get()
+    }
+
+    pub fn set_style(&self, style: RenderStyle) {
+        self.style.set(style);
+    }
+
+    pub fn set_fill_color(&self, color: Palette) {
+        self.fill_color.set(color);
+    }
+
+    /// The path is stroked with the context's stroke style.
+    pub fn set_outline_color(&self, color: Palette) {
+        self.outline_color.set(color);
+    }
+
+    /// True if `p`, in node space, is inside the filled area, as last
+    /// drawn.
+    pub fn contains_point(&self, p: &Point) -> bool {
+        self.flat.borrow().contains_point(p)
+    }
+
+    fn flatten(&self, tolerance: f64) {
+        let flat = self.path.borrow().flatten(tolerance);
+
+        let triangles = match flat.triangulate() {
+            Ok(triangles) => triangles,
+            Err(err) => {
+                println!("{}: {}", self.data().borrow().node.name(), err);
+                Vec::new()
+            }
+        };
+
+        self.bucket.replace(vec![Point::new(); flat.points.len()]);
+        self.flat.replace(flat);
+        self.triangles.replace(triangles);
+        self.tolerance.set(tolerance);
+        self.set_node_dirty(true);
+    }
+}
+
+impl NodeTrait for PathNode {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    // --------------------------------------------------------
+    // Node properties
+    // --------------------------------------------------------
+    fn data(&self) -> &RNodeData {
+        &self.data
+    }
+
+    // --------------------------------------------------------
+    // Rendering
+    // --------------------------------------------------------
+    fn draw(&self, context: &mut Context) {
+        let tolerance = context.curve_tolerance();
+        let flattened = self.tolerance.get();
+        if flattened == 0.0 || tolerance < flattened || tolerance > 2.0 * flattened {
+            self.flatten(tolerance);
+        }
+
+        if self.is_node_dirty() {
+            context.transform(&self.flat.borrow().points, &self.bucket);
+            self.set_node_dirty(false);
+        }
+
+        let flat = self.flat.borrow();
+        let triangles = self.triangles.borrow();
+        let style = self.style.get();
+
+        if style != RenderStyle::Outline {
+            context.set_draw_color(&self.fill_color.get());
+            context.render_flat_path(&self.bucket, &flat, &triangles, RenderStyle::Filled);
+        }
+
+        if style != RenderStyle::Filled {
+            context.set_draw_color(&self.outline_color.get());
+            context.render_flat_path(&self.bucket, &flat, &triangles, RenderStyle::Outline);
+        }
+    }
+
+    fn device_vertices(&self) -> Option<&RVertices> {
+        Some(&self.bucket)
+    }
+
+    // --------------------------------------------------------
+    // Input
+    // --------------------------------------------------------
+    fn hit_shape(&self) -> HitShape {
+        if self.flat.borrow().points.is_empty() {
+            HitShape::None
+        } else {
+            HitShape::Custom
+        }
+    }
+
+    fn contains_local_point(&self, p: &Point) -> bool {
+        self.contains_point(p)
+    }
+
+    // --------------------------------------------------------
+    // Transformations
+    // --------------------------------------------------------
+    fn parent(&self) -> usize {
+        self.parent.get()
+    }
+
+    fn set_parent(&self, parent: usize) {
+        self.parent.replace(parent);
+    }
+}
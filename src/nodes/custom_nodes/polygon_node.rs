@@ -0,0 +1,218 @@
+use std::any::Any;
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+use geometry::{
+    point::{Point, RVertices},
+    polygon::Polygon,
+};
+use nodes::{
+    hit_test::HitShape,
+    node_group::NodeGroup,
+    node_properties::{NodeData, RNodeData},
+    node_trait::{NodeTrait, NodeType, RNode},
+};
+use rendering::{
+    color::Palette,
+    render_context::{Context, RenderStyle},
+};
+use world::World;
+
+// A leaf node that draws a simple polygon, concave or not, with optional
+// holes: asteroids, terrain, islands with lakes.
+//
+//   let rock = PolygonNode::new("Rock", layer_id, world);
+//   if let Some(p) = rock.borrow().as_any().downcast_ref::<PolygonNode>() {
+//       p.set_polygon(&outline, &[crater])?;
+//       p.set_style(RenderStyle::Both);
+//   }
+//
+// The polygon is triangulated when it is set and the triangles are kept
+// until it is set again, so moving, rotating or scaling the node only
+// transforms the vertices.
+
+pub struct PolygonNode {
+    data: RNodeData,
+
+    // Hierarchy
+    parent: Cell<usize>,
+
+    // The outline's vertices followed by each hole's
+    vertices: RefCell<Vec<Point>>,
+    // Where each contour ends in vertices
+    contours: RefCell<Vec<usize>>,
+    // Indices into vertices
+    triangles: RefCell<Vec<[usize; 3]>>,
+    // Transformed vertices
+    bucket: RefCell<Vec<Point>>,
+
+    style: Cell<RenderStyle>,
+    fill_color: Cell<Palette>,
+    outline_color: Cell<Palette>,
+}
+
+impl Drop for PolygonNode {
+    fn drop(&mut self) {
+        println!("Dropping: '{}'", self.data().borrow().node.name());
+    }
+}
+
+impl PolygonNode {
+    pub fn new(name: &str, parent: usize, world: &mut World) -> RNode {
+        let mut n = NodeData::new();
+        n.node.set_name(name.to_string());
+        n.node.set_type(NodeType::Node);
+        n.node.set_id(world.gen_id());
+
+        let pn = Self {
+            data: Rc::new(RefCell::new(n)),
+            parent: Cell::new(parent),
+            vertices: RefCell::new(Vec::new()),
+            contours: RefCell::new(Vec::new()),
+            triangles: RefCell::new(Vec::new()),
+            bucket: RefCell::new(Vec::new()),
+            style: Cell::new(RenderStyle::Filled),
+            fill_color: Cell::new(Palette::WHITE(255)),
+            outline_color: Cell::new(Palette::WHITE(255)),
+        };
+
+        let rc: RNode = Rc::new(RefCell::new(pn));
+
+        world.data_mut().add_node(rc.clone());
+
+        NodeGroup::attach_parent(&rc, world.data());
+
+        rc
+    }
+
+    /// Replaces the shape, in node space. Contours may wind either way but
+    /// must not cross. On error the previous shape is kept.
+    pub fn set_polygon(&self, outline: &[Point], holes: &[Vec<Point>]) -> Result<(), String> {
+        let triangles = Polygon::triangulate(outline, holes)?;
+
+        let mut vertices = outline.to_vec();
+        let mut contours = vec![vertices.len()];
+        for hole in holes.iter() {
+            vertices.extend_from_slice(hole);
+            contours.push(vertices.len());
+        }
+
+        self.bucket.replace(vec![Point::new(); vertices.len()]);
+        self.vertices.replace(vertices);
+        self.contours.replace(contours);
+        self.triangles.replace(triangles);
+        self.set_node_dirty(true);
+
+        Ok(())
+    }
+
+    /// A polygon without holes.
+    pub fn set_outline(&self, outline: &[Point]) -> Result<(), String> {
+        self.set_polygon(outline, &[])
+    }
+
+    pub fn style(&self) -> RenderStyle {
+        self.style.get()
+    }
+
+    pub fn set_style(&self, style: RenderStyle) {
+        self.style.set(style);
+    }
+
+    pub fn set_fill_color(&self, color: Palette) {
+        self.fill_color.set(color);
+    }
+
+    /// The outline is stroked with the context's stroke style.
+    pub fn set_outline_color(&self, color: Palette) {
+        self.outline_color.set(color);
+    }
+
+    /// True if `p`, in node space, is inside the polygon and not in a
+    /// hole.
+    pub fn contains_point(&self, p: &Point) -> bool {
+        let vertices = self.vertices.borrow();
+        let mut start = 0;
+        let mut inside = false;
+        for &end in self.contours.borrow().iter() {
+            if Polygon::contains_point(&vertices[start..end], p) {
+                inside = !inside;
+            }
+            start = end;
+        }
+        inside
+    }
+}
+
+impl NodeTrait for PolygonNode {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    // --------------------------------------------------------
+    // Node properties
+    // --------------------------------------------------------
+    fn data(&self) -> &RNodeData {
+        &self.data
+    }
+
+    // --------------------------------------------------------
+    // Rendering
+    // --------------------------------------------------------
+    fn draw(&self, context: &mut Context) {
+        if self.is_node_dirty() {
+            context.transform(&self.vertices.borrow(), &self.bucket);
+            self.set_node_dirty(false);
+        }
+
+        let contours = self.contours.borrow();
+        let triangles = self.triangles.borrow();
+        let style = self.style.get();
+
+        if style != RenderStyle::Outline {
+            context.set_draw_color(&self.fill_color.get());
+            context.render_polygon(&self.bucket, &contours, &triangles, RenderStyle::Filled);
+        }
+
+        if style != RenderStyle::Filled {
+            context.set_draw_color(&self.outline_color.get());
+            context.render_polygon(&self.bucket, &contours, &triangles, RenderStyle::Outline);
+        }
+    }
+
+    fn device_vertices(&self) -> Option<&RVertices> {
+        Some(&self.bucket)
+    }
+
+    // --------------------------------------------------------
+    // Input
+    // --------------------------------------------------------
+    fn hit_shape(&self) -> HitShape {
+        // The device polygon test sees one contour, so with holes the node
+        // tests the point itself.
+        match self.contours.borrow().len() {
+            0 => HitShape::None,
+            1 => HitShape::Polygon,
+            _ => HitShape::Custom,
+        }
+    }
+
+    fn contains_local_point(&self, p: &Point) -> bool {
+        self.contains_point(p)
+    }
+
+    // --------------------------------------------------------
+    // Transformations
+    // --------------------------------------------------------
+    fn parent(&self) -> usize {
+        self.parent.get()
+    }
+
+    fn set_parent(&self, parent: usize) {
+        self.parent.replace(parent);
+    }
+}
@@ -0,0 +1,202 @@
+use std::any::Any;
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+use geometry::{
+    point::{Point, RVertices},
+    rectangle::Rectangle,
+};
+use nodes::{
+    hit_test::HitShape,
+    node_group::NodeGroup,
+    node_properties::{NodeData, RNodeData},
+    node_trait::{NodeTrait, NodeType, RNode},
+};
+use rendering::{
+    render_context::Context,
+    texture::{RTexture, TextureRegion},
+};
+use world::World;
+
+// A leaf node that draws an image, or part of one, under its transform.
+//
+// One unit of node space is one texel, so a scale of 1.0 draws the image
+// at its size in view-space. The pivot is the point of the image, as a
+// fraction of its width and height, that sits at the node's position and
+// that the node rotates and scales about. It defaults to the center.
+//
+//   let sprite = SpriteNode::new("Ship", layer_id, world);
+//   let texture = world.data().textures().borrow_mut().load("assets/ship.png")?;
+//   if let Some(s) = sprite.borrow().as_any().downcast_ref::<SpriteNode>() {
+//       s.set_texture(Some(texture));
+//   }
+
+pub struct SpriteNode {
+    data: RNodeData,
+
+    // Hierarchy
+    parent: Cell<usize>,
+
+    texture: RefCell<Option<RTexture>>,
+    // The texels drawn, None for the whole texture
+    region: Cell<Option<TextureRegion>>,
+    pivot: Cell<(f64, f64)>,
+
+    // The image's corners in node space
+    vertices: RefCell<Vec<Point>>,
+    // Transformed vertices
+    bucket: RefCell<Vec<Point>>,
+}
+
+impl Drop for SpriteNode {
+    fn drop(&mut self) {
+        println!("Dropping: '{}'", self.data().borrow().node.name());
+    }
+}
+
+impl SpriteNode {
+    pub fn new(name: &str, parent: usize, world: &mut World) -> RNode {
+        let mut n = NodeData::new();
+        n.node.set_name(name.to_string());
+        n.node.set_type(NodeType::Node);
+        n.node.set_id(world.gen_id());
+
+        let sn = Self {
+            data: Rc::new(RefCell::new(n)),
+            parent: Cell::new(parent),
+            texture: RefCell::new(None),
+            region: Cell::new(None),
+            pivot: Cell::new((0.5, 0.5)),
+            vertices: RefCell::new(vec![Point::new(); 4]),
+            bucket: RefCell::new(vec![Point::new(); 4]),
+        };
+
+        let rc: RNode = Rc::new(RefCell::new(sn));
+
+        world.data_mut().add_node(rc.clone());
+
+        NodeGroup::attach_parent(&rc, world.data());
+
+        rc
+    }
+
+    pub fn texture(&self) -> Option<RTexture> {
+        self.texture.borrow().clone()
+    }
+
+    pub fn set_texture(&self, texture: Option<RTexture>) {
+        self.texture.replace(texture);
+        self.shape_changed();
+    }
+
+    /// The texels drawn.
+    pub fn region(&self) -> Option<TextureRegion> {
+        match self.region.get() {
+            Some(region) => Some(region),
+            None => self.texture.borrow().as_ref().map(|t| t.region()),
+        }
+    }
+
+    /// Draws only `region` of the texture, None draws all of it.
+    pub fn set_region(&self, region: Option<TextureRegion>) {
+        self.region.set(region);
+        self.shape_changed();
+    }
+
+    pub fn pivot(&self) -> (f64, f64) {
+        self.pivot.get()
+    }
+
+    /// (0.0, 0.0) is the image's top-left corner, (1.0, 1.0) its
+    /// bottom-right.
+    pub fn set_pivot(&self, x: f64, y: f64) {
+        self.pivot.set((x, y));
+        self.shape_changed();
+    }
+
+    /// The image's rectangle in node space.
+    pub fn local_bounds(&self) -> Rectangle {
+        let (width, height) = match self.region() {
+            Some(region) => (region.width as f64, region.height as f64),
+            None => (0.0, 0.0),
+        };
+        let (px, py) = self.pivot.get();
+
+        let (min_x, min_y) = (-px * width, -py * height);
+        Rectangle::from_min_max(min_x, min_y, min_x + width, min_y + height)
+    }
+
+    fn shape_changed(&self) {
+        let bounds = self.local_bounds();
+        {
+            let mut vertices = self.vertices.borrow_mut();
+            vertices[0] = Point::from_xy(bounds.min.x, bounds.min.y);
+            vertices[1] = Point::from_xy(bounds.max.x, bounds.min.y);
+            vertices[2] = Point::from_xy(bounds.max.x, bounds.max.y);
+            vertices[3] = Point::from_xy(bounds.min.x, bounds.max.y);
+        }
+        self.set_node_dirty(true);
+    }
+}
+
+impl NodeTrait for SpriteNode {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    // --------------------------------------------------------
+    // Node properties
+    // --------------------------------------------------------
+    fn data(&self) -> &RNodeData {
+        &self.data
+    }
+
+    // --------------------------------------------------------
+    // Rendering
+    // --------------------------------------------------------
+    fn draw(&self, context: &mut Context) {
+        if self.is_node_dirty() {
+            context.transform(&self.vertices.borrow(), &self.bucket);
+            self.set_node_dirty(false);
+        }
+
+        let texture = match &*self.texture.borrow() {
+            Some(texture) => texture.clone(),
+            None => return,
+        };
+
+        if let Some(region) = self.region() {
+            context.render_texture(&texture, &region, &self.local_bounds());
+        }
+    }
+
+    fn device_vertices(&self) -> Option<&RVertices> {
+        Some(&self.bucket)
+    }
+
+    // --------------------------------------------------------
+    // Input
+    // --------------------------------------------------------
+    fn hit_shape(&self) -> HitShape {
+        if self.texture.borrow().is_some() {
+            HitShape::Polygon
+        } else {
+            HitShape::None
+        }
+    }
+
+    // --------------------------------------------------------
+    // Transformations
+    // --------------------------------------------------------
+    fn parent(&self) -> usize {
+        self.parent.get()
+    }
+
+    fn set_parent(&self, parent: usize) {
+        self.parent.replace(parent);
+    }
+}
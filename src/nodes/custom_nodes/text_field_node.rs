@@ -0,0 +1,455 @@
+use std::any::Any;
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+use events::io_events::{
+    EventPhase, EventResult, IOEvent, IOEventData, KeyAction, Keycode, Mod, PointerAction,
+    PointerEvent,
+};
+use geometry::point::{Point, RVertices};
+use nodes::{
+    custom_nodes::vector_text_node::VectorTextNode,
+    hit_test::HitShape,
+    node_group::NodeGroup,
+    node_nil::NodeNil,
+    node_properties::{NodeData, RNodeData},
+    node_trait::{NodeTrait, NodeType, OChildren, RNode},
+};
+use rendering::{color::Palette, render_context::Context};
+use world::World;
+
+// An editable single line of text. The text is drawn by a VectorTextNode
+// child, the field draws its box, selection and cursor.
+//
+// The field takes keyboard focus when clicked or tabbed to. While focused
+// it handles typed text, Backspace/Delete, Left/Right/Home/End (with Shift
+// to select) and Ctrl-A. Text being composed by an input method is shown
+// at the cursor until it is committed.
+//
+// In node-space the characters are centered on multiples of the font's
+// horizontal offset, with the baseline at y = 0 and the tops at y = -1.
+
+const DEFAULT_MAX_LENGTH: usize = 32;
+// Space between the text and the box
+const PADDING: f64 = 0.2;
+
+pub struct TextFieldNode {
+    data: RNodeData,
+
+    // Hierarchy
+    parent: Cell<usize>,
+
+    children: OChildren,
+
+    label: RefCell<RNode>,
+    // Horizontal distance between characters
+    advance: Cell<f64>,
+
+    text: RefCell<Vec<char>>,
+    composition: RefCell<String>,
+    cursor: Cell<usize>,
+    // The other end of the selection from the cursor, None if nothing is
+    // selected.
+    anchor: Cell<Option<usize>>,
+    max_length: Cell<usize>,
+
+    focused: Cell<bool>,
+
+    color: Cell<Palette>,
+
+    // Transformed box, selection and cursor vertices
+    outline: RefCell<Vec<Point>>,
+    selection: RefCell<Vec<Point>>,
+    caret: RefCell<Vec<Point>>,
+}
+
+impl Drop for TextFieldNode {
+    fn drop(&mut self) {
+        println!("Dropping: '{}'", self.data().borrow().node.name());
+    }
+}
+
+impl TextFieldNode {
+    pub fn new(name: &str, parent: usize, world: &mut World) -> RNode {
+        let mut n = NodeData::new();
+        n.node.set_name(name.to_string());
+        n.node.set_type(NodeType::Node);
+        n.node.set_id(world.gen_id());
+        let id = n.node.id();
+
+        let tf = Self {
+            data: Rc::new(RefCell::new(n)),
+            parent: Cell::new(parent),
+            children: Some(RefCell::new(Vec::new())),
+            label: RefCell::new(NodeNil::new()),
+            advance: Cell::new(1.0),
+            text: RefCell::new(Vec::new()),
+            composition: RefCell::new(String::new()),
+            cursor: Cell::new(0),
+            anchor: Cell::new(None),
+            max_length: Cell::new(DEFAULT_MAX_LENGTH),
+            focused: Cell::new(false),
+            color: Cell::new(Palette::WHITE(127)),
+            outline: RefCell::new(Vec::new()),
+            selection: RefCell::new(Vec::new()),
+            caret: RefCell::new(Vec::new()),
+        };
+
+        let rc: RNode = Rc::new(RefCell::new(tf));
+
+        world.data_mut().add_node(rc.clone());
+        world.data_mut().register_io_event_target(
+            rc.clone(),
+            &[IOEvent::Keyboard, IOEvent::TextInput, IOEvent::TextEditing],
+        );
+
+        NodeGroup::attach_parent(&rc, world.data());
+
+        // The label attaches to the field, so the field must be in the pool.
+        let label = VectorTextNode::new(&format!("{}Label", name), id, world);
+        if let Some(tf) = rc.borrow().as_any().downcast_ref::<TextFieldNode>() {
+            if let Some(vtn) = label.borrow().as_any().downcast_ref::<VectorTextNode>() {
+                tf.advance.set(vtn.font().get_horz_offset());
+            }
+            tf.label.replace(label.clone());
+        }
+
+        rc
+    }
+
+    // --------------------------------------------------------
+    // Properties
+    // --------------------------------------------------------
+    pub fn text(&self) -> String {
+        self.text.borrow().iter().collect()
+    }
+
+    /// Replaces the text, truncated to the max length, and moves the cursor
+    /// to the end.
+    pub fn set_text(&self, text: &str) {
+        let chars: Vec<char> = text.chars().take(self.max_length.get()).collect();
+        self.cursor.set(chars.len());
+        self.anchor.set(None);
+        self.text.replace(chars);
+        self.refresh();
+    }
+
+    pub fn max_length(&self) -> usize {
+        self.max_length.get()
+    }
+
+    /// Existing text longer than `max_length` is truncated.
+    pub fn set_max_length(&self, max_length: usize) {
+        self.max_length.set(max_length);
+        if self.text.borrow().len() > max_length {
+            self.text.borrow_mut().truncate(max_length);
+            self.cursor.set(self.cursor.get().min(max_length));
+            self.anchor
+                .set(self.anchor.get().map(|a| a.min(max_length)));
+        }
+        self.refresh();
+    }
+
+    pub fn cursor(&self) -> usize {
+        self.cursor.get()
+    }
+
+    /// The selected char range (start, end), if any.
+    pub fn selection(&self) -> Option<(usize, usize)> {
+        match self.anchor.get() {
+            Some(anchor) if anchor != self.cursor.get() => {
+                let cursor = self.cursor.get();
+                Some((anchor.min(cursor), anchor.max(cursor)))
+            }
+            _ => None,
+        }
+    }
+
+    pub fn set_color(&self, color: Palette) {
+        self.color.set(color);
+    }
+
+    // --------------------------------------------------------
+    // Editing
+    // --------------------------------------------------------
+    fn insert(&self, text: &str) {
+        self.delete_selection();
+
+        let room = self
+            .max_length
+            .get()
+            .saturating_sub(self.text.borrow().len());
+        let mut cursor = self.cursor.get();
+        {
+            let mut chars = self.text.borrow_mut();
+            for c in text.chars().filter(|c| !c.is_control()).take(room) {
+                chars.insert(cursor, c);
+                cursor += 1;
+            }
+        }
+        self.cursor.set(cursor);
+    }
+
+    // Returns false if nothing was selected.
+    fn delete_selection(&self) -> bool {
+        let (start, end) = match self.selection() {
+            Some(range) => range,
+            None => {
+                self.anchor.set(None);
+                return false;
+            }
+        };
+
+        self.text.borrow_mut().drain(start..end);
+        self.cursor.set(start);
+        self.anchor.set(None);
+        true
+    }
+
+    fn backspace(&self) {
+        let cursor = self.cursor.get();
+        if !self.delete_selection() && cursor > 0 {
+            self.text.borrow_mut().remove(cursor - 1);
+            self.cursor.set(cursor - 1);
+        }
+    }
+
+    fn delete(&self) {
+        let cursor = self.cursor.get();
+        if !self.delete_selection() && cursor < self.text.borrow().len() {
+            self.text.borrow_mut().remove(cursor);
+        }
+    }
+
+    // Moves the cursor to `to`. Selecting extends the selection, otherwise
+    // the selection is cleared.
+    fn move_cursor(&self, to: usize, select: bool) {
+        if select {
+            if self.anchor.get().is_none() {
+                self.anchor.set(Some(self.cursor.get()));
+            }
+        } else {
+            self.anchor.set(None);
+        }
+
+        self.cursor.set(to.min(self.text.borrow().len()));
+    }
+
+    fn key(&self, keycode: Keycode, modifiers: Mod) -> EventResult {
+        let shift = modifiers.intersects(Mod::LSHIFTMOD | Mod::RSHIFTMOD);
+        let ctrl = modifiers.intersects(Mod::LCTRLMOD | Mod::RCTRLMOD);
+        let cursor = self.cursor.get();
+        let len = self.text.borrow().len();
+
+        match keycode {
+            Keycode::Backspace => self.backspace(),
+            Keycode::Delete => self.delete(),
+            Keycode::Left => match self.selection() {
+                // Without Shift the cursor collapses to the selection's edge.
+                Some((start, _)) if !shift => self.move_cursor(start, false),
+                _ => self.move_cursor(cursor.saturating_sub(1), shift),
+            },
+            Keycode::Right => match self.selection() {
+                Some((_, end)) if !shift => self.move_cursor(end, false),
+                _ => self.move_cursor(cursor + 1, shift),
+            },
+            Keycode::Home => self.move_cursor(0, shift),
+            Keycode::End => self.move_cursor(len, shift),
+            Keycode::A if ctrl => {
+                self.anchor.set(Some(0));
+                self.cursor.set(len);
+            }
+            // Keys that type, whose text arrives as a TextInput event, are
+            // the field's too.
+            _ if !ctrl && (32..127).contains(&(keycode as i32)) => return EventResult::Handled,
+            _ => return EventResult::Unhandled,
+        }
+
+        self.refresh();
+        EventResult::Handled
+    }
+
+    // The text shown: the text with any composition at the cursor.
+    fn refresh(&self) {
+        let mut display: String = self.text.borrow().iter().take(self.cursor.get()).collect();
+        display.push_str(&self.composition.borrow());
+        display.extend(self.text.borrow().iter().skip(self.cursor.get()));
+
+        let label = self.label.borrow();
+        if let Some(vtn) = label.borrow().as_any().downcast_ref::<VectorTextNode>() {
+            vtn.set_text(&display);
+        }
+
+        self.ripple_node_dirty(true);
+    }
+
+    // Node-space x of the left edge of char `index`.
+    fn edge(&self, index: usize) -> f64 {
+        let advance = self.advance.get();
+        index as f64 * advance - advance / 2.0
+    }
+
+    fn transform_into(context: &Context, points: &[Point], bucket: &RVertices) {
+        bucket.borrow_mut().resize(points.len(), Point::new());
+        context.transform(&points.to_vec(), bucket);
+    }
+}
+
+impl NodeTrait for TextFieldNode {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    // --------------------------------------------------------
+    // Node properties
+    // --------------------------------------------------------
+    fn data(&self) -> &RNodeData {
+        &self.data
+    }
+
+    // --------------------------------------------------------
+    // Rendering
+    // --------------------------------------------------------
+    fn draw(&self, context: &mut Context) {
+        let top = -1.0 - PADDING;
+        let bottom = PADDING;
+
+        if self.is_node_dirty() {
+            let left = self.edge(0) - PADDING;
+            let right = self.edge(self.max_length.get()) + PADDING;
+            let outline = [
+                Point::from_xy(left, top),
+                Point::from_xy(right, top),
+                Point::from_xy(right, top),
+                Point::from_xy(right, bottom),
+                Point::from_xy(right, bottom),
+                Point::from_xy(left, bottom),
+                Point::from_xy(left, bottom),
+                Point::from_xy(left, top),
+            ];
+            TextFieldNode::transform_into(context, &outline, &self.outline);
+
+            match self.selection() {
+                Some((start, end)) => {
+                    let (x0, x1) = (self.edge(start), self.edge(end));
+                    let selection = [
+                        Point::from_xy(x0, bottom),
+                        Point::from_xy(x1, bottom),
+                        Point::from_xy(x1, top),
+                        Point::from_xy(x0, top),
+                    ];
+                    TextFieldNode::transform_into(context, &selection, &self.selection);
+                }
+                None => self.selection.borrow_mut().clear(),
+            }
+
+            let x = self.edge(self.cursor.get() + self.composition.borrow().chars().count());
+            let caret = [Point::from_xy(x, top), Point::from_xy(x, bottom)];
+            TextFieldNode::transform_into(context, &caret, &self.caret);
+
+            self.set_node_dirty(false);
+        }
+
+        if self.selection.borrow().len() == 4 {
+            context.set_draw_color(&Palette::RGBA(64, 96, 160, 255));
+            context.render_rectangle(&self.selection);
+        }
+
+        if self.focused.get() {
+            context.set_draw_color(&Palette::WHITE(255));
+            context.render_lines(&self.caret);
+        } else {
+            context.set_draw_color(&self.color.get());
+        }
+        context.render_lines(&self.outline);
+    }
+
+    fn device_vertices(&self) -> Option<&RVertices> {
+        Some(&self.outline)
+    }
+
+    // --------------------------------------------------------
+    // Transformations
+    // --------------------------------------------------------
+    fn parent(&self) -> usize {
+        self.parent.get()
+    }
+
+    fn set_parent(&self, parent: usize) {
+        self.parent.replace(parent);
+    }
+
+    // --------------------------------------------------------
+    // IO Events
+    // --------------------------------------------------------
+    fn io_event(&self, phase: EventPhase, io_event: &IOEventData) -> EventResult {
+        if phase != EventPhase::Target || !self.focused.get() {
+            return EventResult::Unhandled;
+        }
+
+        match io_event.event {
+            IOEvent::Keyboard => match io_event.key {
+                Some(key) if key.action != KeyAction::Up => match key.keycode {
+                    Some(keycode) => self.key(keycode, key.modifiers),
+                    None => EventResult::Unhandled,
+                },
+                _ => EventResult::Unhandled,
+            },
+            IOEvent::TextInput => {
+                if let Some(text) = &io_event.text {
+                    self.composition.borrow_mut().clear();
+                    self.insert(&text.text);
+                    self.refresh();
+                }
+                EventResult::Handled
+            }
+            IOEvent::TextEditing => {
+                if let Some(text) = &io_event.text {
+                    self.composition.replace(text.text.clone());
+                    self.refresh();
+                }
+                EventResult::Handled
+            }
+            _ => EventResult::Unhandled,
+        }
+    }
+
+    fn hit_shape(&self) -> HitShape {
+        HitShape::Bounds
+    }
+
+    // Clicking places the cursor at the nearest char edge.
+    fn pointer_event(&self, event: &PointerEvent) {
+        if event.action == PointerAction::Press {
+            let index = ((event.local.0 - self.edge(0)) / self.advance.get()).round();
+            self.move_cursor(index.max(0.0) as usize, false);
+            self.refresh();
+        }
+    }
+
+    fn focusable(&self) -> bool {
+        true
+    }
+
+    fn focus_changed(&self, focused: bool) {
+        self.focused.set(focused);
+        if !focused {
+            self.composition.borrow_mut().clear();
+            self.anchor.set(None);
+            self.refresh();
+        } else {
+            self.ripple_node_dirty(true);
+        }
+    }
+
+    // --------------------------------------------------------
+    // Grouping
+    // --------------------------------------------------------
+    fn get_children(&self) -> &OChildren {
+        &self.children
+    }
+}
@@ -0,0 +1,282 @@
+use std::any::Any;
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+use geometry::{
+    point::{Point, RVertices},
+    rectangle::Rectangle,
+};
+use nodes::{
+    hit_test::HitShape,
+    node_group::NodeGroup,
+    node_properties::{NodeData, RNodeData},
+    node_trait::{NodeTrait, NodeType, RNode},
+};
+use rendering::{
+    color::Palette,
+    font::{RFont, TextAlignment, TextLayout},
+    render_context::Context,
+    texture::RTexture,
+};
+use world::World;
+
+// A leaf node that draws text in a TrueType or OpenType font.
+//
+//   let label = TextNode::new("Score", layer_id, world);
+//   let font = world.data().fonts().borrow_mut().load("assets/DejaVuSans.ttf")?;
+//   if let Some(t) = label.borrow().as_any().downcast_ref::<TextNode>() {
+//       t.set_font(Some(font));
+//       t.set_size(24.0);
+//       t.set_text("Score\n1200");
+//   }
+//
+// The size is in node units, so the node's scale and its parents' scale it
+// like any other shape. The node's position is the top of the text, at its
+// left edge, center or right edge depending on the alignment.
+//
+// The text is drawn into an image at the pixel size it appears on screen
+// and the image is drawn under the node's transform. The image is kept
+// until the text, its style or that pixel size changes. A window keeps it
+// on the GPU, so moving or rotating the node only copies it again; a
+// sheared transform or a software target blends it pixel by pixel every
+// frame.
+
+// The rendered text and where it goes in node space
+type TextImage = (RTexture, Rectangle);
+
+pub struct TextNode {
+    data: RNodeData,
+
+    // Hierarchy
+    parent: Cell<usize>,
+
+    font: RefCell<Option<RFont>>,
+    text: RefCell<String>,
+    size: Cell<f64>,
+    color: Cell<Palette>,
+    alignment: Cell<TextAlignment>,
+    line_spacing: Cell<f64>,
+
+    layout: RefCell<TextLayout>,
+    // The rendered text, None if nothing is visible, with the pixels per
+    // unit it was drawn at. None until the text is drawn.
+    image: RefCell<Option<(Option<TextImage>, f64)>>,
+
+    // The text block's corners in node space
+    vertices: RefCell<Vec<Point>>,
+    // Transformed vertices
+    bucket: RefCell<Vec<Point>>,
+}
+
+impl Drop for TextNode {
+    fn drop(&mut self) {
+        println!("Dropping: '{}'", self.data().borrow().node.name());
+    }
+}
+
+impl TextNode {
+    pub fn new(name: &str, parent: usize, world: &mut World) -> RNode {
+        let mut n = NodeData::new();
+        n.node.set_name(name.to_string());
+        n.node.set_type(NodeType::Node);
+        n.node.set_id(world.gen_id());
+
+        let tn = Self {
+            data: Rc::new(RefCell::new(n)),
+            parent: Cell::new(parent),
+            font: RefCell::new(None),
+            text: RefCell::new(String::new()),
+            size: Cell::new(16.0),
+            color: Cell::new(Palette::WHITE(255)),
+            alignment: Cell::new(TextAlignment::Left),
+            line_spacing: Cell::new(1.0),
+            layout: RefCell::new(TextLayout::default()),
+            image: RefCell::new(None),
+            vertices: RefCell::new(vec![Point::new(); 4]),
+            bucket: RefCell::new(vec![Point::new(); 4]),
+        };
+
+        let rc: RNode = Rc::new(RefCell::new(tn));
+
+        world.data_mut().add_node(rc.clone());
+
+        NodeGroup::attach_parent(&rc, world.data());
+
+        rc
+    }
+
+    pub fn font(&self) -> Option<RFont> {
+        self.font.borrow().clone()
+    }
+
+    pub fn set_font(&self, font: Option<RFont>) {
+        self.font.replace(font);
+        self.text_changed();
+    }
+
+    pub fn text(&self) -> String {
+        self.text.borrow().clone()
+    }
+
+    /// '\n' starts a new line.
+    pub fn set_text(&self, text: &str) {
+        if *self.text.borrow() == text {
+            return;
+        }
+        self.text.replace(text.to_string());
+        self.text_changed();
+    }
+
+    pub fn size(&self) -> f64 {
+        self.size.get()
+    }
+
+    /// From the lowest descender to the highest ascender, in node units.
+    pub fn set_size(&self, size: f64) {
+        self.size.set(size.max(0.0));
+        self.text_changed();
+    }
+
+    pub fn set_color(&self, color: Palette) {
+        self.color.set(color);
+        self.image.replace(None);
+    }
+
+    pub fn alignment(&self) -> TextAlignment {
+        self.alignment.get()
+    }
+
+    pub fn set_alignment(&self, alignment: TextAlignment) {
+        self.alignment.set(alignment);
+        self.text_changed();
+    }
+
+    /// 1.0 uses the font's line height, 2.0 leaves a blank line's worth
+    /// between lines.
+    pub fn set_line_spacing(&self, spacing: f64) {
+        self.line_spacing.set(spacing);
+        self.text_changed();
+    }
+
+    /// The text block's rectangle in node space.
+    pub fn local_bounds(&self) -> Rectangle {
+        let layout = self.layout.borrow();
+        let left = -layout.width * self.alignment.get().anchor();
+        Rectangle::from_min_max(left, 0.0, left + layout.width, layout.height)
+    }
+
+    fn text_changed(&self) {
+        let layout = match &*self.font.borrow() {
+            Some(font) => font.layout(
+                &self.text.borrow(),
+                self.size.get(),
+                self.alignment.get(),
+                self.line_spacing.get(),
+            ),
+            None => TextLayout::default(),
+        };
+        self.layout.replace(layout);
+        self.image.replace(None);
+
+        let bounds = self.local_bounds();
+        {
+            let mut vertices = self.vertices.borrow_mut();
+            vertices[0] = Point::from_xy(bounds.min.x, bounds.min.y);
+            vertices[1] = Point::from_xy(bounds.max.x, bounds.min.y);
+            vertices[2] = Point::from_xy(bounds.max.x, bounds.max.y);
+            vertices[3] = Point::from_xy(bounds.min.x, bounds.max.y);
+        }
+        self.set_node_dirty(true);
+    }
+
+    // Draws the text again if the pixels per unit have changed enough to
+    // change the pixel size.
+    fn update_image(&self, scale: f64) {
+        let size = self.size.get();
+        if let Some((_, drawn)) = &*self.image.borrow() {
+            if (size * drawn).round() == (size * scale).round() {
+                return;
+            }
+        }
+
+        let font = match &*self.font.borrow() {
+            Some(font) => font.clone(),
+            None => return,
+        };
+
+        let layout = self.layout.borrow();
+        let image =
+            font.rasterize(&layout, size, scale, self.color.get())
+                .map(|(texture, (x, y))| {
+                    let left = x - layout.width * self.alignment.get().anchor();
+                    let dest = Rectangle::from_min_max(
+                        left,
+                        y,
+                        left + texture.width() as f64 / scale,
+                        y + texture.height() as f64 / scale,
+                    );
+                    (Rc::new(texture), dest)
+                });
+
+        self.image.replace(Some((image, scale)));
+    }
+}
+
+impl NodeTrait for TextNode {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    // --------------------------------------------------------
+    // Node properties
+    // --------------------------------------------------------
+    fn data(&self) -> &RNodeData {
+        &self.data
+    }
+
+    // --------------------------------------------------------
+    // Rendering
+    // --------------------------------------------------------
+    fn draw(&self, context: &mut Context) {
+        if self.is_node_dirty() {
+            context.transform(&self.vertices.borrow(), &self.bucket);
+            self.set_node_dirty(false);
+        }
+
+        self.update_image(context.current().max_scale());
+
+        if let Some((Some((texture, dest)), _)) = &*self.image.borrow() {
+            context.render_texture(texture, &texture.region(), dest);
+        }
+    }
+
+    fn device_vertices(&self) -> Option<&RVertices> {
+        Some(&self.bucket)
+    }
+
+    // --------------------------------------------------------
+    // Input
+    // --------------------------------------------------------
+    fn hit_shape(&self) -> HitShape {
+        if self.layout.borrow().glyphs.is_empty() {
+            HitShape::None
+        } else {
+            HitShape::Polygon
+        }
+    }
+
+    // --------------------------------------------------------
+    // Transformations
+    // --------------------------------------------------------
+    fn parent(&self) -> usize {
+        self.parent.get()
+    }
+
+    fn set_parent(&self, parent: usize) {
+        self.parent.replace(parent);
+    }
+}
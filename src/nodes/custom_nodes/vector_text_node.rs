@@ -2,15 +2,32 @@ use std::any::Any;
 use std::cell::{Cell, RefCell};
 use std::rc::Rc;
 
-use geometry::point::Point;
+use geometry::{
+    point::{Point, RVertices},
+    rectangle::Rectangle,
+};
 use nodes::{
     node_group::NodeGroup,
     node_properties::{NodeData, RNodeData},
     node_trait::{NodeTrait, NodeType, RNode},
 };
-use rendering::{color::Palette, render_context::Context, vector_font::VectorFont};
+use rendering::{
+    color::Palette, font::TextAlignment, render_context::Context, vector_font::VectorFont,
+};
 use world::World;
 
+// A leaf node that draws text with the VectorFont.
+//
+//   let label = VectorTextNode::new("Status", layer_id, world);
+//   if let Some(vtn) = label.borrow().as_any().downcast_ref::<VectorTextNode>() {
+//       vtn.set_alignment(TextAlignment::Center);
+//       vtn.set_wrap_width(Some(20.0));
+//       vtn.set_text("Shields low, return to base");
+//   }
+//
+// The node's position is on the first line's baseline. With a wrap width
+// the text is broken at spaces into lines no wider than it.
+
 pub struct VectorTextNode {
     data: RNodeData,
 
@@ -18,7 +35,11 @@ pub struct VectorTextNode {
     parent: Cell<usize>,
 
     text: RefCell<String>,
+    // The text as laid out, after wrapping
+    lines: RefCell<String>,
     font: VectorFont,
+    alignment: Cell<TextAlignment>,
+    wrap_width: Cell<Option<f64>>,
 
     // Original model vertices
     vertices: RefCell<Vec<Point>>,
@@ -46,7 +67,10 @@ impl VectorTextNode {
             vertices: RefCell::new(Vec::new()),
             bucket: RefCell::new(Vec::new()),
             text: RefCell::new(String::from("")),
+            lines: RefCell::new(String::new()),
             font: VectorFont::new(),
+            alignment: Cell::new(TextAlignment::Left),
+            wrap_width: Cell::new(None),
         };
 
         let rc: RNode = Rc::new(RefCell::new(n));
@@ -58,30 +82,61 @@ impl VectorTextNode {
         rc
     }
 
-    pub fn set_text(&self, text: &String) {
-        self.text.borrow_mut().replace_range(.., text);
+    /// '\n' starts a new line.
+    pub fn set_text(&self, text: &str) {
+        self.text.replace(text.to_string());
+        self.rebuild();
+    }
 
-        // Use glyph properties to adjust char location.
-        let hoff = self.font.get_horz_offset();
-        let mut xpos = 0.0;
-        let mut p = Point::new();
+    pub fn text(&self) -> String {
+        self.text.borrow().clone()
+    }
 
-        // Rebuild vertex buffer to match text.
-        let txt = self.text.borrow();
-        let mut v = self.vertices.borrow_mut();
-        let mut b = self.bucket.borrow_mut();
+    pub fn font(&self) -> &VectorFont {
+        &self.font
+    }
 
-        for c in txt.chars() {
-            let lines = self.font.get_glyph(c).get_lines();
+    pub fn alignment(&self) -> TextAlignment {
+        self.alignment.get()
+    }
 
-            for l in lines.iter() {
-                p.set_xy(l.x + xpos, l.y);
-                v.push(p);
-                b.push(Point::new());
-            }
+    pub fn set_alignment(&self, alignment: TextAlignment) {
+        self.alignment.set(alignment);
+        self.rebuild();
+    }
 
-            xpos += hoff;
-        }
+    pub fn wrap_width(&self) -> Option<f64> {
+        self.wrap_width.get()
+    }
+
+    /// The widest a line may be in node units, None to only break lines
+    /// at '\n'.
+    pub fn set_wrap_width(&self, width: Option<f64>) {
+        self.wrap_width.set(width);
+        self.rebuild();
+    }
+
+    /// The text as drawn, with a '\n' wherever it was wrapped.
+    pub fn lines(&self) -> String {
+        self.lines.borrow().clone()
+    }
+
+    /// The text's rectangle in node space.
+    pub fn local_bounds(&self) -> Rectangle {
+        self.font.bounds(&self.lines.borrow(), self.alignment.get())
+    }
+
+    // Rebuilds the vertex buffer to match the text.
+    fn rebuild(&self) {
+        let lines = match self.wrap_width.get() {
+            Some(width) => self.font.wrap(&self.text.borrow(), width),
+            None => self.text.borrow().clone(),
+        };
+
+        let vertices = self.font.layout(&lines, self.alignment.get());
+        self.bucket.replace(vec![Point::new(); vertices.len()]);
+        self.vertices.replace(vertices);
+        self.lines.replace(lines);
 
         self.set_node_dirty(true);
     }
@@ -118,6 +173,10 @@ impl NodeTrait for VectorTextNode {
         context.render_lines(&self.bucket);
     }
 
+    fn device_vertices(&self) -> Option<&RVertices> {
+        Some(&self.bucket)
+    }
+
     // --------------------------------------------------------
     // Transformations
     // --------------------------------------------------------
@@ -0,0 +1,125 @@
+use std::cell::RefCell;
+
+use nodes::{node_functions::NodeFunctions, node_trait::RNode};
+use world::GlobalData;
+
+// Tracks the node that receives keyboard and text events.
+//
+// Nodes opt in by returning true from focusable(). Tab and Shift-Tab move
+// the focus through the focusable, visible nodes of the input scene in
+// draw order. A Tab that doesn't move the focus is delivered like any
+// other key. Clicking a focusable node focuses it. The NodeManager makes
+// the focused node the target of keyboard and text events so the events
+// travel the path from the scene down to it.
+
+#[derive(Default)]
+pub struct FocusManager {
+    // 0 = nothing focused
+    focused: usize,
+}
+
+impl FocusManager {
+    pub fn focused(&self) -> usize {
+        self.focused
+    }
+
+    /// The focused node if it is part of `root`'s tree.
+    pub fn focused_node(&self, root: &RNode, data: &GlobalData) -> Option<RNode> {
+        let node = data.find_node(&self.focused)?;
+
+        let path = NodeFunctions::path_from(root, node, data);
+        if path[0].borrow().id() == root.borrow().id() {
+            Some(node.clone())
+        } else {
+            None
+        }
+    }
+
+    /// Moves the focus to `node`, or clears it, signalling focus_changed
+    /// on the nodes losing and gaining focus.
+    pub fn set_focus(&mut self, node: Option<&RNode>, data: &GlobalData) {
+        let id = node.map_or(0, |node| node.borrow().id());
+        if id == self.focused {
+            return;
+        }
+
+        if let Some(old) = data.find_node(&self.focused) {
+            old.borrow().focus_changed(false);
+        }
+
+        self.focused = id;
+
+        if let Some(node) = node {
+            node.borrow().focus_changed(true);
+        }
+    }
+
+    /// Returns true if the focus moved.
+    pub fn focus_next(&mut self, root: &RNode, data: &GlobalData) -> bool {
+        self.cycle(root, data, true)
+    }
+
+    /// Returns true if the focus moved.
+    pub fn focus_previous(&mut self, root: &RNode, data: &GlobalData) -> bool {
+        self.cycle(root, data, false)
+    }
+
+    fn cycle(&mut self, root: &RNode, data: &GlobalData, forward: bool) -> bool {
+        let nodes = FocusManager::focusable_nodes(root);
+        if nodes.is_empty() {
+            return false;
+        }
+
+        let current = nodes
+            .iter()
+            .position(|node| node.borrow().id() == self.focused);
+
+        let next = match (current, forward) {
+            (Some(i), true) => (i + 1) % nodes.len(),
+            (Some(i), false) => (i + nodes.len() - 1) % nodes.len(),
+            (None, true) => 0,
+            (None, false) => nodes.len() - 1,
+        };
+
+        let previous = self.focused;
+        self.set_focus(Some(&nodes[next]), data);
+        self.focused != previous
+    }
+
+    // Focusable nodes in draw order, skipping hidden subtrees.
+    fn focusable_nodes(root: &RNode) -> Vec<RNode> {
+        let mut nodes = Vec::new();
+
+        let ro = root.borrow();
+        if !ro.is_visible() {
+            return nodes;
+        }
+
+        if ro.focusable() {
+            nodes.push(root.clone());
+        }
+
+        if let Some(children) = ro.get_children() {
+            FocusManager::sub_focusable_nodes(children, &mut nodes);
+        }
+
+        nodes
+    }
+
+    fn sub_focusable_nodes(children: &RefCell<Vec<RNode>>, nodes: &mut Vec<RNode>) {
+        for child in children.borrow().iter() {
+            let ch = child.borrow();
+            if !ch.is_visible() {
+                continue;
+            }
+
+            if ch.focusable() {
+                nodes.push(child.clone());
+            }
+
+            if let Some(sub_children) = ch.get_children() {
+                FocusManager::sub_focusable_nodes(sub_children, nodes);
+            }
+        }
+    }
+}
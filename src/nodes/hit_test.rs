@@ -0,0 +1,131 @@
+use geometry::{aabb::AABBox, point::Point, polygon::Polygon};
+use math::affine_transform::AffineTransform;
+use nodes::node_trait::RNode;
+
+// Finds the node under a device-space point.
+//
+// Nodes opt in by returning a HitShape other than None from hit_shape().
+// Shapes are tested against the node's state as of its last visit, so the
+// result matches what is on screen. Nodes drawn later are in front, so a
+// tree is searched in reverse draw order: later siblings before earlier
+// ones and children before their parent.
+//
+// A node that draws its children more than once hands its children each
+// copy's point in turn, see NodeTrait::child_hit_points.
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum HitShape {
+    // Not hit testable
+    None,
+    // The bounding box of the node's device vertices
+    Bounds,
+    // The polygon formed by the node's device vertices
+    Polygon,
+    // A node-space rectangle (min_x, min_y, max_x, max_y)
+    Rectangle(f64, f64, f64, f64),
+    // A node-space circle (center x, center y, radius)
+    Circle(f64, f64, f64),
+    // The node's own test, contains_local_point(), in node-space
+    Custom,
+}
+
+pub struct HitTest;
+
+impl HitTest {
+    /// The frontmost visible node of `root`'s tree that contains the
+    /// device point.
+    pub fn pick(root: &RNode, x: i32, y: i32) -> Option<RNode> {
+        HitTest::pick_point(root, x, y).map(|(node, _)| node)
+    }
+
+    /// pick() with the device point as the node was hit with it, for
+    /// to_local(). It differs from x, y when the node's parent draws it
+    /// more than once.
+    pub fn pick_point(root: &RNode, x: i32, y: i32) -> Option<(RNode, Point)> {
+        let device = Point::from_xy(x as f64, y as f64);
+
+        let ro = root.borrow();
+        if !ro.is_visible() {
+            return None;
+        }
+
+        HitTest::sub_pick(root, &device)
+    }
+
+    fn sub_pick(node: &RNode, device: &Point) -> Option<(RNode, Point)> {
+        let no = node.borrow();
+
+        if let Some(children) = no.get_children() {
+            for point in no.child_hit_points(device).iter() {
+                for child in children.borrow().iter().rev() {
+                    if !child.borrow().is_visible() {
+                        continue;
+                    }
+                    if let Some(hit) = HitTest::sub_pick(child, point) {
+                        return Some(hit);
+                    }
+                }
+            }
+        }
+
+        if HitTest::contains(node, device) {
+            return Some((node.clone(), *device));
+        }
+
+        None
+    }
+
+    /// True if the node's hit shape contains the device point.
+    pub fn contains(node: &RNode, device: &Point) -> bool {
+        let no = node.borrow();
+
+        match no.hit_shape() {
+            HitShape::None => false,
+            HitShape::Bounds => match no.device_vertices() {
+                Some(vertices) => {
+                    let vertices = vertices.borrow();
+                    if vertices.is_empty() {
+                        return false;
+                    }
+                    let mut aabb = AABBox::new();
+                    aabb.set_from_vertices(&vertices);
+                    aabb.contains_point(device)
+                }
+                None => false,
+            },
+            HitShape::Polygon => match no.device_vertices() {
+                Some(vertices) => Polygon::contains_point(&vertices.borrow(), device),
+                None => false,
+            },
+            HitShape::Rectangle(min_x, min_y, max_x, max_y) => {
+                let (lx, ly) = HitTest::to_local(node, device);
+                lx >= min_x && lx <= max_x && ly >= min_y && ly <= max_y
+            }
+            HitShape::Circle(cx, cy, radius) => {
+                let (lx, ly) = HitTest::to_local(node, device);
+                let (dx, dy) = (lx - cx, ly - cy);
+                dx * dx + dy * dy <= radius * radius
+            }
+            HitShape::Custom => {
+                let (lx, ly) = HitTest::to_local(node, device);
+                no.contains_local_point(&Point::from_xy(lx, ly))
+            }
+        }
+    }
+
+    /// Maps a device point into the node's space using the device
+    /// transform from the node's last visit.
+    pub fn to_local(node: &RNode, device: &Point) -> (f64, f64) {
+        let inverse = node
+            .borrow()
+            .data()
+            .borrow()
+            .transform
+            .get_device_transform()
+            .inverse();
+
+        let mut local = Point::new();
+        AffineTransform::transform_to_point(device, &mut local, &inverse);
+        (local.x, local.y)
+    }
+}
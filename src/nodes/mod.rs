@@ -1,3 +1,7 @@
+pub mod components;
+pub mod custom_nodes;
+pub mod focus_manager;
+pub mod hit_test;
 pub mod node_functions;
 pub mod node_group;
 pub mod node_manager;
@@ -5,4 +9,3 @@ pub mod node_nil;
 pub mod node_properties;
 pub mod node_trait;
 pub mod scenes;
-pub mod custom_nodes;
\ No newline at end of file
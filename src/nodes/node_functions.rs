@@ -113,13 +113,57 @@ impl NodeFunctions {
         NodeNil::new()
     }
 
+    /// The nodes from `root` down to `node`. Just `node` if it isn't in
+    /// `root`'s tree.
+    pub fn path_from(root: &RNode, node: &RNode, gdata: &GlobalData) -> Vec<RNode> {
+        let root_id = root.borrow().id();
+
+        let mut path = vec![node.clone()];
+        let mut current = node.clone();
+
+        while current.borrow().id() != root_id {
+            let parent = current.borrow().parent();
+            match gdata.find_node(&parent) {
+                Some(parent) => {
+                    path.push(parent.clone());
+                    current = parent.clone();
+                }
+                None => return vec![node.clone()],
+            }
+        }
+
+        path.reverse();
+        path
+    }
+
+    /// Collects the tree into a list ordered parents before children.
+    pub fn collect_tree(node: &RNode) -> Vec<RNode> {
+        let mut nodes = vec![node.clone()];
+
+        if let Some(children) = node.borrow().get_children() {
+            NodeFunctions::sub_collect_tree(children, &mut nodes);
+        }
+
+        nodes
+    }
+
+    fn sub_collect_tree(children: &RefCell<Vec<RNode>>, nodes: &mut Vec<RNode>) {
+        for child in children.borrow().iter() {
+            nodes.push(child.clone());
+
+            if let Some(sub_children) = child.borrow().get_children() {
+                NodeFunctions::sub_collect_tree(sub_children, nodes);
+            }
+        }
+    }
+
     // ------------------------------------------------------------
     // Timing
     // ------------------------------------------------------------
     /// Iterate through tree registering all node marked as registrable.
     pub fn register_timing_targets(node: &RNode, man: &mut NodeManager) {
         let no = node.borrow();
-        if no.data().borrow().node.canbe_timing_target() {
+        if no.data().borrow().is_timing_target() {
             man.register_timing_target(node.clone());
         }
 
@@ -132,13 +176,13 @@ impl NodeFunctions {
         for child in children.borrow().iter() {
             if let Some(sub_children) = child.borrow().get_children() {
                 let chi = child.borrow();
-                if chi.data().borrow().node.canbe_timing_target() {
+                if chi.data().borrow().is_timing_target() {
                     man.register_timing_target(child.clone());
                 }
                 NodeFunctions::sub_register_timing_targets(sub_children, man);
             } else {
                 let chi = child.borrow();
-                if chi.data().borrow().node.canbe_timing_target() {
+                if chi.data().borrow().is_timing_target() {
                     man.register_timing_target(child.clone());
                 }
             }
@@ -152,7 +196,7 @@ impl NodeFunctions {
 
     pub fn unregister_timing_targets(node: &RNode, man: &mut NodeManager) {
         let no = node.borrow();
-        if no.data().borrow().node.canbe_timing_target() {
+        if no.data().borrow().is_timing_target() {
             man.unschedule_timing_target(node.clone());
         }
 
@@ -165,13 +209,13 @@ impl NodeFunctions {
         for child in children.borrow().iter() {
             if let Some(sub_children) = child.borrow().get_children() {
                 let chi = child.borrow();
-                if chi.data().borrow().node.canbe_timing_target() {
+                if chi.data().borrow().is_timing_target() {
                     man.unschedule_timing_target(child.clone());
                 }
                 NodeFunctions::sub_unregister_timing_targets(sub_children, man);
             } else {
                 let chi = child.borrow();
-                if chi.data().borrow().node.canbe_timing_target() {
+                if chi.data().borrow().is_timing_target() {
                     man.unschedule_timing_target(child.clone());
                 }
             }
@@ -211,38 +255,6 @@ impl NodeFunctions {
         println!("{} ({})", name, id);
     }
 
-    // pub fn register_for_io_events(data: &mut GlobalData, parent: RNode, child: usize) {
-    //     let fin = NodeFunctions::find_node(child, &parent);
-    //     if !fin.borrow().is_nil() {
-    //         println!("Register '{}' for io events", fin.borrow().name());
-    //         data.register_io_event_targets(fin.clone());
-    //     } else {
-    //         println!("Could not find ({}) to register", child);
-    //     }
-
-    //     // self.io_event_targets.push(node);
-    //     println!("len: {}", data.io_event_targets_count());
-    // }
-
-    // pub fn unregister_for_io_events(data: &GlobalData, parent: RNode, child: usize) {
-    //     // let contains = self.io_event_targets.contains(&node);
-    //     // if contains {
-    //     //     return;
-    //     // }
-    //     let fin = NodeFunctions::find_node(child, &parent);
-    //     if !fin.borrow().is_nil() {
-    //         println!("Register '{}' for io events", fin.borrow().name());
-    //         let nb = fin.borrow();
-    //         data.io_event_targets
-    //             .retain(|n| n.borrow().id() != nb.data().borrow().node.id());
-    //     } else {
-    //         println!("Could not find ({}) to unregister", child);
-    //     }
-
-    //     // Keep all but the referenced `node`
-    //     println!("un len: {}", data.io_event_targets.len());
-    // }
-
     // --------------------------------------------------------------------------
     // Debug stuff (typically drawn in device space)
     // --------------------------------------------------------------------------
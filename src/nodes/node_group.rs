@@ -13,6 +13,8 @@ impl NodeGroup {
         let gpart = gdata.find_node(&op_node_id);
         if let Some(parent) = gpart {
             parent.borrow().add_child(node.clone());
+            bnode.on_attached(op_node_id);
+            gdata.queue_attached(bnode.id());
         } else {
             println!(
                 "attach_parent could not find parent '{}' to attach to.",
@@ -4,12 +4,20 @@ use self::sdl2::render::WindowCanvas;
 
 use std::cell::RefCell;
 
-use events::io_events::{IOEvent, IOEventData};
+use events::io_events::{
+    EventPhase, EventResult, IOEvent, IOEventData, KeyAction, Keycode, Mod, MouseAction,
+    MouseButton, MouseData, PointerAction, PointerEvent,
+};
+use geometry::point::Point;
 use nodes::{
+    components::component::Components,
+    focus_manager::FocusManager,
+    hit_test::HitTest,
+    node_functions::NodeFunctions,
     node_nil::NodeNil,
     node_trait::{NodeActions, NodeType, RNode},
 };
-use rendering::render_context::Context;
+use rendering::{render_context::Context, render_target::RenderTarget};
 use world::GlobalData;
 
 pub struct NodeManager {
@@ -21,11 +29,26 @@ pub struct NodeManager {
     stack: RefCell<NodeStack>,
 
     timing_targets: RefCell<Vec<RNode>>,
+
+    // Pointer targets: the node under the mouse and the node each held
+    // button was pressed on. 0 = none
+    hovered: usize,
+    pressed: Vec<(MouseButton, usize)>,
+
+    focus: FocusManager,
 }
 
 impl NodeManager {
     pub fn new(canvas: WindowCanvas, data: &GlobalData) -> Self {
-        let mut context = Context::new(canvas);
+        NodeManager::with_context(Context::new(canvas), data)
+    }
+
+    /// Renders into `target` instead of a window.
+    pub fn with_target(target: Box<dyn RenderTarget>, data: &GlobalData) -> Self {
+        NodeManager::with_context(Context::with_target(target), data)
+    }
+
+    fn with_context(mut context: Context, data: &GlobalData) -> Self {
         context.initialize(data);
 
         // TODO move clear flag into nodes. each nodes decides if it wants the background
@@ -35,6 +58,9 @@ impl NodeManager {
             context: context,
             stack: RefCell::new(NodeStack::new()),
             timing_targets: RefCell::new(Vec::new()),
+            hovered: 0,
+            pressed: Vec::new(),
+            focus: FocusManager::default(),
         }
     }
 
@@ -42,6 +68,10 @@ impl NodeManager {
         &mut self.context
     }
 
+    pub fn running_node(&self) -> RNode {
+        self.stack.borrow().running_node().clone()
+    }
+
     pub fn pre_visit(&self) {
         // Typically Scenes/Layers will clear the background themselves so the default
         // is to NOT perform a clear here.
@@ -63,26 +93,52 @@ impl NodeManager {
             self.set_next_node(data);
         }
 
+        // Nodes attached to a tree that is already on stage join the stage.
+        self.stage_attached_nodes(data);
+        self.update_timing_targets(data);
+
         // This will save view-space matrix
         self.context.save();
 
         // If mouse coords changed then update view coords.
         data.update_view_coords(&mut self.context);
 
-        let mut nodes = self.stack.borrow_mut();
-        let action = nodes.running_node().borrow().transition();
+        let action = self.stack.borrow().running_node().borrow().transition();
         match action {
             NodeActions::SceneReplaceTake => {
-                let id = nodes.running_node().borrow().take_transition_node();
-                let popped_id = nodes.replace_by_id(id, data);
-                // TODO need to make this recursive on children too.
-                self.unschedule_timing_target_by_id(popped_id);
+                let id = self
+                    .stack
+                    .borrow()
+                    .running_node()
+                    .borrow()
+                    .take_transition_node();
+                // Swap in the replacement now so it is drawn this frame.
+                self.replace_node_by_id(id, data);
             }
             _ => (),
         }
 
-        let rune = nodes.running_node().borrow();
-        rune.visit(&mut self.context, interpolation, data);
+        let pop = match self.stack.borrow().overlays.last() {
+            Some(overlay) => matches!(overlay.node.borrow().transition(), NodeActions::OverlayPop),
+            None => false,
+        };
+        if pop {
+            self.pop_overlay(data);
+        }
+
+        {
+            let nodes = self.stack.borrow();
+            let rune = nodes.running_node().borrow();
+            rune.visit(&mut self.context, interpolation, data);
+
+            // Overlays render on top of the running node, bottom up.
+            for overlay in nodes.overlays.iter() {
+                overlay
+                    .node
+                    .borrow()
+                    .visit(&mut self.context, interpolation, data);
+            }
+        }
 
         // Process view after visiting Nodes.
         self.context.restore();
@@ -95,43 +151,206 @@ impl NodeManager {
     }
 
     pub fn set_next_node(&self, data: &mut GlobalData) {
-        let mut nodes = self.stack.borrow_mut();
+        let outgoing: RNode;
+        let incoming: RNode;
+        let outgoing_is_nil: bool;
+        let outgoing_is_transition: bool;
+        let incoming_is_transition: bool;
+        {
+            let mut nodes = self.stack.borrow_mut();
+            outgoing = nodes.running_node().clone();
+            outgoing_is_nil = nodes.running_node_nil();
+            outgoing_is_transition = nodes.running_node_is_transition();
+            incoming_is_transition = nodes.next_node_is_transition();
+
+            // Make the running node the next active node.
+            nodes.make_running_node();
+
+            nodes.make_next_node_nil();
+
+            incoming = nodes.running_node().clone();
+        }
 
-        if !nodes.next_node_is_transition() {
-            if !nodes.running_node_nil() {
-                let rune = nodes.running_node().borrow();
+        print!("---- Running node ----: ");
+        println!("{}", incoming.borrow().to_string());
+
+        if incoming_is_transition {
+            self.begin_transition(&incoming, &outgoing, data);
+            return;
+        }
 
+        if !outgoing_is_nil {
+            if outgoing_is_transition {
+                // The transition is done. The node it replaced already
+                // started exiting when the transition began.
+                let replaced = outgoing.borrow().take_outgoing_node();
+                if let Some(replaced) = replaced {
+                    replaced.borrow().exit(data);
+                    self.retire_node(&replaced, data);
+                }
+            } else {
                 // It is not a transition so it must be a regular scene which means it
                 // needs to start transitioning off the stage.
                 // Signal node to start exiting the stage via a transition.
-                rune.start_exit_transition(data);
-                // Transition is complete signal node to complete its exit.
-                rune.exit(data);
-
-                // TODO Some nodes may need to release resources
-                // if nodes.signal_release() {
-                //     // rune.release();
-                // }
+                outgoing.borrow().start_exit_transition(data);
             }
+
+            // Transition is complete signal node to complete its exit.
+            outgoing.borrow().exit(data);
+            self.retire_node(&outgoing, data);
+        }
+
+        let rune = incoming.borrow();
+        if !rune.is_nil() {
+            // This is a regular node.
+            // Signal node that it should enter the stage. If a transition
+            // brought it in then it entered when the transition began.
+            self.enter_stage(&incoming);
+            if !outgoing_is_transition {
+                rune.enter();
+            }
+            rune.end_enter_transition();
+        }
+    }
+
+    // Both scenes stay on stage, and keep updating, while the transition
+    // renders them.
+    fn begin_transition(&self, transition: &RNode, outgoing: &RNode, data: &mut GlobalData) {
+        let target_id = {
+            let tran = transition.borrow();
+            tran.begin_transition(outgoing);
+            tran.get_transition_node()
         };
 
-        // Make the running node the next active node.
-        nodes.make_running_node();
+        if !outgoing.borrow().is_nil() {
+            outgoing.borrow().start_exit_transition(data);
+        }
 
-        nodes.make_next_node_nil();
+        self.enter_stage(transition);
 
-        let rune = nodes.running_node().borrow();
-        print!("---- Running node ----: ");
-        println!("{}", rune.to_string());
+        let target = data.find_node(&target_id).cloned();
+        match target {
+            Some(target) => {
+                self.enter_stage(&target);
+                target.borrow().enter();
+            }
+            None => println!("begin_transition couldn't find ({}) from pool", target_id),
+        }
+    }
 
-        // Are we transitioning from one node to the next.
-        if !nodes.running_node_is_transition() {
-            // This is a regular node.
-            // Signal node that it should enter the stage.
-            let rune = nodes.running_node().borrow();
-            if !rune.is_nil() {
-                rune.enter();
-                rune.end_enter_transition();
+    // Takes an exited node off stage. A node that is no longer on the stack
+    // has nothing to return to.
+    fn retire_node(&self, node: &RNode, data: &mut GlobalData) {
+        self.exit_stage(node);
+
+        let on_stack = self.stack.borrow().contains(node);
+        if !on_stack {
+            self.destroy_node(node, data);
+        }
+    }
+
+    // --------------------------------------------------------------------------
+    // Life cycle
+    // --------------------------------------------------------------------------
+    /// Places every node of the tree on stage. Timing targets are registered
+    /// before the node's `on_enter_stage` is called.
+    pub fn enter_stage(&self, node: &RNode) {
+        for n in NodeFunctions::collect_tree(node).iter() {
+            let no = n.borrow();
+            {
+                let mut data = no.data().borrow_mut();
+                if data.node.is_on_stage() {
+                    continue;
+                }
+                data.node.set_on_stage(true);
+            }
+
+            if no.data().borrow().is_timing_target() {
+                self.register_timing_target(n.clone());
+            }
+
+            no.on_enter_stage();
+        }
+    }
+
+    /// Takes every node of the tree off stage. Timing targets are unregistered
+    /// before the node's `on_exit_stage` is called.
+    pub fn exit_stage(&self, node: &RNode) {
+        for n in NodeFunctions::collect_tree(node).iter() {
+            let no = n.borrow();
+            {
+                let mut data = no.data().borrow_mut();
+                if !data.node.is_on_stage() {
+                    continue;
+                }
+                data.node.set_on_stage(false);
+            }
+
+            self.unschedule_timing_target_by_id(no.id());
+
+            no.on_exit_stage();
+        }
+    }
+
+    /// Takes the tree off stage, detaches it from its parent and releases
+    /// everything the engine holds for each node before flushing the tree.
+    pub fn destroy_node(&self, node: &RNode, data: &mut GlobalData) {
+        self.exit_stage(node);
+
+        let parent_id = node.borrow().parent();
+        if let Some(parent) = data.find_node(&parent_id) {
+            parent.borrow().remove_child(NodeFunctions::node_id(node));
+        }
+
+        for n in NodeFunctions::collect_tree(node).iter() {
+            let id = NodeFunctions::node_id(n);
+
+            n.borrow().on_destroyed();
+
+            data.take_node(&id);
+            data.unregister_io_event_target(id);
+            data.event_bus().borrow_mut().unsubscribe_all(id);
+        }
+
+        println!("destroy_node flushing '{}'", node.borrow().name());
+        node.borrow().flush(true);
+    }
+
+    fn stage_attached_nodes(&self, data: &GlobalData) {
+        for id in data.take_attached().iter() {
+            if let Some(node) = data.find_node(id) {
+                let parent_id = node.borrow().parent();
+                let parent_on_stage = match data.find_node(&parent_id) {
+                    Some(parent) => parent.borrow().data().borrow().node.is_on_stage(),
+                    None => false,
+                };
+
+                if parent_on_stage {
+                    self.enter_stage(node);
+                }
+            }
+        }
+    }
+
+    // Nodes on stage whose components changed start or stop being updated.
+    fn update_timing_targets(&self, data: &GlobalData) {
+        for id in data.take_timing_changes().iter() {
+            if let Some(node) = data.find_node(id) {
+                let (on_stage, timing) = {
+                    let no = node.borrow();
+                    let nd = no.data().borrow();
+                    (nd.node.is_on_stage(), nd.is_timing_target())
+                };
+
+                if !on_stage {
+                    continue;
+                }
+
+                if timing {
+                    self.register_timing_target(node.clone());
+                } else {
+                    self.unschedule_timing_target_by_id(*id);
+                }
             }
         }
     }
@@ -144,41 +363,375 @@ impl NodeManager {
         self.stack.borrow_mut().push(node);
     }
 
-    /// Replaces the running node
-    // pub fn replace_node(&self, node: RNode) {
-    //     {
-    //         let stack = self.stack.borrow();
-    //         let rune = stack.running_node().borrow();
-    //         if rune.is_nil() {
-    //             panic!("NodeManager::replace_node -- no running node.");
-    //         }
-    //     }
-    //     self.stack.borrow_mut().replace(node);
-    // }
+    /// Replaces the running node with a node from the pool. The outgoing
+    /// node exits and the replacement becomes the running node immediately.
+    pub fn replace_node_by_id(&self, node_id: usize, data: &mut GlobalData) {
+        self.stack.borrow_mut().replace_by_id(node_id, data);
+        self.set_next_node(data);
+    }
+
+    /// Replaces the running node with `node`.
+    pub fn replace_node(&self, node: RNode, data: &mut GlobalData) {
+        let id = node.borrow().id();
+        if data.find_node(&id).is_none() {
+            data.add_node(node);
+        }
+        self.replace_node_by_id(id, data);
+    }
+
+    // --------------------------------------------------------------------------
+    // Overlays
+    // --------------------------------------------------------------------------
+    /// Places `node` on top of the running node and any other overlays, for
+    /// example, a pause menu. Everything below keeps rendering. If
+    /// `pause_below` is set then everything below stops updating until the
+    /// overlay is popped.
+    pub fn push_overlay(&self, node: RNode, pause_below: bool) {
+        let mut paused = Vec::new();
+
+        if pause_below {
+            for below in self.stack.borrow().rendered_nodes().iter() {
+                for n in NodeFunctions::collect_tree(below).iter() {
+                    let no = n.borrow();
+                    // Nodes that are already paused are left for their
+                    // owners to resume.
+                    if !no.paused() {
+                        no.pause(true);
+                        paused.push(no.id());
+                    }
+                }
+            }
+        }
+
+        self.enter_stage(&node);
+        {
+            let no = node.borrow();
+            print!("---- Pushing Overlay ----: ");
+            println!("{}", no.to_string());
+            no.enter();
+            no.end_enter_transition();
+        }
+
+        self.stack
+            .borrow_mut()
+            .overlays
+            .push(Overlay { node, paused });
+    }
+
+    /// Removes the top overlay and resumes whatever it paused. The overlay
+    /// stays in the pool so it can be pushed again. Use destroy_node once it
+    /// isn't needed.
+    pub fn pop_overlay(&self, data: &mut GlobalData) -> Option<RNode> {
+        let overlay = self.stack.borrow_mut().overlays.pop()?;
+
+        {
+            let no = overlay.node.borrow();
+            print!("---- Popped Overlay ----: ");
+            println!("{}", no.to_string());
+            no.start_exit_transition(data);
+            no.exit(data);
+        }
+        self.exit_stage(&overlay.node);
+
+        for id in overlay.paused.iter() {
+            if let Some(node) = data.find_node(id) {
+                node.borrow().pause(false);
+            }
+        }
+
+        Some(overlay.node)
+    }
+
+    pub fn overlay_count(&self) -> usize {
+        self.stack.borrow().overlays.len()
+    }
 
     // --------------------------------------------------------------------------
     // IO events
     // --------------------------------------------------------------------------
-    pub fn io_event(&mut self, io_event: IOEventData, data: &mut GlobalData) {
+    /// Focuses `node`, or clears the focus with None.
+    pub fn set_focus(&mut self, node: Option<&RNode>, data: &GlobalData) {
+        self.focus.set_focus(node, data);
+    }
+
+    /// Id of the focused node, 0 if none.
+    pub fn focused(&self) -> usize {
+        self.focus.focused()
+    }
+
+    pub fn io_event(&mut self, mut io_event: IOEventData, data: &mut GlobalData) {
+        let root = match self.input_target() {
+            Some(root) => root,
+            None => {
+                data.actions().borrow_mut().io_event(&io_event);
+                return;
+            }
+        };
+
         match io_event.event {
             IOEvent::Mouse => {
-                data.set_mouse(io_event.coord.0, io_event.coord.1);
+                data.actions().borrow_mut().io_event(&io_event);
+
+                let (x, y) = io_event.coord;
+                data.set_mouse(x, y);
+                io_event.view = NodeFunctions::map_device_to_view(x, y, &mut self.context);
+
+                let hit = HitTest::pick_point(&root, x, y);
+                io_event.node = match &hit {
+                    Some((node, _)) => node.clone(),
+                    None => root.clone(),
+                };
+
+                // Clicking a focusable node focuses it, clicking anything
+                // else clears the focus.
+                if io_event.mouse_action() == Some(MouseAction::ButtonDown) {
+                    let focus = hit
+                        .as_ref()
+                        .map(|(node, _)| node)
+                        .filter(|node| node.borrow().focusable());
+                    self.focus.set_focus(focus, data);
+                }
+
+                self.deliver_io_event(&root, &io_event, data);
+                self.deliver_pointer_event(hit, &io_event, data);
+            }
+            IOEvent::Keyboard | IOEvent::TextInput | IOEvent::TextEditing => {
+                if let Some(key) = io_event.key {
+                    if key.keycode == Some(Keycode::Tab) && key.action != KeyAction::Up {
+                        let moved = if key.modifiers.intersects(Mod::LSHIFTMOD | Mod::RSHIFTMOD) {
+                            self.focus.focus_previous(&root, data)
+                        } else {
+                            self.focus.focus_next(&root, data)
+                        };
+                        // Games can bind Tab when there is nothing to focus.
+                        if moved {
+                            return;
+                        }
+                    }
+                }
+
+                io_event.node = match self.focus.focused_node(&root, data) {
+                    Some(node) => node,
+                    None => root.clone(),
+                };
+                let handled = self.deliver_io_event(&root, &io_event, data);
+
+                // Keys a node used, for example, typing into a text field,
+                // don't drive actions. Releases always do so that held
+                // actions end.
+                let released = io_event.key.map(|key| key.action) == Some(KeyAction::Up);
+                if !handled || released {
+                    data.actions().borrow_mut().io_event(&io_event);
+                }
+            }
+            _ => data.actions().borrow_mut().io_event(&io_event),
+        }
+    }
+
+    // Only the topmost scene that accepts input receives it.
+    fn input_target(&self) -> Option<RNode> {
+        self.stack
+            .borrow()
+            .rendered_nodes()
+            .into_iter()
+            .rev()
+            .find(|node| {
+                let no = node.borrow();
+                !no.is_nil() && no.accepts_io_events()
+            })
+    }
+
+    // Propagates the event along the path from `root` to the event's target
+    // node: down through the registered ancestors, to the target and back
+    // up, until a node handles it. Components of `root`'s tree all see the
+    // event. Returns true if a node handled it.
+    fn deliver_io_event(&self, root: &RNode, io_event: &IOEventData, data: &GlobalData) -> bool {
+        let path: Vec<RNode> = NodeFunctions::path_from(root, &io_event.node, data)
+            .into_iter()
+            .filter(|node| data.is_io_event_target(node.borrow().id(), io_event.event))
+            .collect();
+        let target_id = io_event.node.borrow().id();
+
+        let capture = path
+            .iter()
+            .filter(|node| node.borrow().id() != target_id)
+            .map(|node| (node, EventPhase::Capture));
+        let target = path
+            .iter()
+            .filter(|node| node.borrow().id() == target_id)
+            .map(|node| (node, EventPhase::Target));
+        let bubble = path
+            .iter()
+            .rev()
+            .filter(|node| node.borrow().id() != target_id)
+            .map(|node| (node, EventPhase::Bubble));
+
+        let mut handled = false;
+        for (node, phase) in capture.chain(target).chain(bubble) {
+            if node.borrow().io_event(phase, io_event) == EventResult::Handled {
+                handled = true;
+                break;
+            }
+        }
+
+        for node in NodeFunctions::collect_tree(root).iter() {
+            let no = node.borrow();
+            if Components::io_event(no.data(), io_event) {
+                no.ripple_node_dirty(true);
+            }
+        }
+
+        handled
+    }
 
-                let mut stack = self.stack.borrow_mut();
-                let mut rune = stack.running_node().borrow_mut();
-                if !rune.is_nil() {
-                    rune.io_event(&io_event);
+    // Sends pointer events to `hit`, the frontmost node under the mouse, and
+    // the device point it was hit with.
+    fn deliver_pointer_event(
+        &mut self,
+        hit: Option<(RNode, Point)>,
+        io_event: &IOEventData,
+        data: &GlobalData,
+    ) {
+        let mouse = match io_event.mouse {
+            Some(mouse) => mouse,
+            None => return,
+        };
+
+        // Nodes that weren't hit see the pointer where it is.
+        let (x, y) = io_event.coord;
+        let pointer = Point::from_xy(x as f64, y as f64);
+
+        let hit_id = hit.as_ref().map_or(0, |(node, _)| node.borrow().id());
+        let hit_point = hit.as_ref().map_or(pointer, |&(_, point)| point);
+
+        if hit_id != self.hovered {
+            if let Some(node) = data.find_node(&self.hovered) {
+                NodeManager::send_pointer_event(
+                    node,
+                    PointerAction::Leave,
+                    &pointer,
+                    io_event,
+                    &mouse,
+                );
+            }
+            if let Some((node, _)) = &hit {
+                NodeManager::send_pointer_event(
+                    node,
+                    PointerAction::Enter,
+                    &hit_point,
+                    io_event,
+                    &mouse,
+                );
+            }
+            self.hovered = hit_id;
+        } else if mouse.action == MouseAction::Move {
+            if let Some((node, _)) = &hit {
+                NodeManager::send_pointer_event(
+                    node,
+                    PointerAction::Hover,
+                    &hit_point,
+                    io_event,
+                    &mouse,
+                );
+            }
+        }
+
+        let button = match mouse.button {
+            Some(button) => button,
+            None => return,
+        };
+
+        match mouse.action {
+            MouseAction::ButtonDown => {
+                if let Some((node, _)) = &hit {
+                    NodeManager::send_pointer_event(
+                        node,
+                        PointerAction::Press,
+                        &hit_point,
+                        io_event,
+                        &mouse,
+                    );
+                    self.pressed.push((button, hit_id));
+                }
+            }
+            MouseAction::ButtonUp => {
+                let index = self.pressed.iter().position(|&(b, _)| b == button);
+                if let Some(index) = index {
+                    let (_, id) = self.pressed.remove(index);
+                    if let Some(node) = data.find_node(&id) {
+                        let device = if id == hit_id { &hit_point } else { &pointer };
+                        NodeManager::send_pointer_event(
+                            node,
+                            PointerAction::Release,
+                            device,
+                            io_event,
+                            &mouse,
+                        );
+                        if id == hit_id {
+                            NodeManager::send_pointer_event(
+                                node,
+                                PointerAction::Click,
+                                device,
+                                io_event,
+                                &mouse,
+                            );
+                        }
+                    }
                 }
             }
             _ => (),
         }
     }
 
+    fn send_pointer_event(
+        node: &RNode,
+        action: PointerAction,
+        device: &Point,
+        io_event: &IOEventData,
+        mouse: &MouseData,
+    ) {
+        let event = PointerEvent {
+            action,
+            button: mouse.button,
+            local: HitTest::to_local(node, device),
+            coord: io_event.coord,
+            view: io_event.view,
+            modifiers: mouse.modifiers,
+        };
+
+        node.borrow().pointer_event(&event);
+    }
+
+    // --------------------------------------------------------------------------
+    // Messaging
+    // --------------------------------------------------------------------------
+    /// Delivers queued events to subscribers. This should only be called at a
+    /// safe point, for example, after all updates for a frame have completed.
+    pub fn dispatch_events(&self, data: &GlobalData) {
+        let bus = data.event_bus();
+
+        // Events posted by handlers are queued for the next safe point.
+        let events = bus.borrow_mut().take_queue();
+
+        for event in events.iter() {
+            let subscribers = bus.borrow().subscribers(&event.channel);
+            for id in subscribers.iter() {
+                if let Some(node) = data.find_node(id) {
+                    node.borrow().on_event(event);
+                }
+            }
+        }
+    }
+
     // --------------------------------------------------------------------------
     // Timing
     // --------------------------------------------------------------------------
     pub fn register_timing_target(&self, node: RNode) {
-        self.timing_targets.borrow_mut().push(node);
+        let mut targets = self.timing_targets.borrow_mut();
+        let id = node.borrow().id();
+        if !targets.iter().any(|n| n.borrow().id() == id) {
+            targets.push(node);
+        }
     }
 
     pub fn unschedule_timing_target(&self, node: RNode) {
@@ -199,6 +752,9 @@ impl NodeManager {
             let t = target.borrow();
             if !t.paused() {
                 t.update(dt);
+                if Components::update(t.data(), dt) {
+                    t.ripple_node_dirty(true);
+                }
             }
         }
     }
@@ -207,8 +763,16 @@ impl NodeManager {
 // --------------------------------------------------------------------------
 // Internal node stack
 // --------------------------------------------------------------------------
+struct Overlay {
+    node: RNode,
+    // Ids of the nodes below that this overlay paused
+    paused: Vec<usize>,
+}
+
 struct NodeStack {
     nodes: Vec<RNode>,
+    // Scenes drawn on top of the running node, bottom first
+    overlays: Vec<Overlay>,
     // Indicates if a node should dispose completely once it isn't needed
     // anymore. For example, boot and splash scenes typically have this
     // enabled.
@@ -225,7 +789,12 @@ impl Drop for NodeStack {
             node.borrow().flush(true);
         }
 
+        for overlay in self.overlays.iter() {
+            overlay.node.borrow().flush(true);
+        }
+
         self.nodes.clear();
+        self.overlays.clear();
     }
 }
 
@@ -233,6 +802,7 @@ impl NodeStack {
     fn new() -> Self {
         Self {
             nodes: Vec::new(),
+            overlays: Vec::new(),
             next_node: NodeNil::new(),
             running_node: NodeNil::new(),
             signal_node_to_flush: false,
@@ -263,6 +833,11 @@ impl NodeStack {
         ns.is_node_type(NodeType::SceneTransition)
     }
 
+    fn contains(&self, node: &RNode) -> bool {
+        let id = node.borrow().id();
+        self.nodes.iter().any(|n| n.borrow().id() == id)
+    }
+
     fn make_next_node_nil(&mut self) {
         self.next_node = NodeNil::new();
     }
@@ -271,6 +846,13 @@ impl NodeStack {
         &self.running_node
     }
 
+    // The running node followed by the overlays, bottom to top.
+    fn rendered_nodes(&self) -> Vec<RNode> {
+        let mut nodes = vec![self.running_node.clone()];
+        nodes.extend(self.overlays.iter().map(|overlay| overlay.node.clone()));
+        nodes
+    }
+
     fn make_running_node(&mut self) {
         self.running_node = self.next_node.clone();
     }
@@ -346,35 +928,19 @@ impl NodeStack {
         self.signal_node_to_flush = true;
     }
 
-    fn replace_by_id(&mut self, node_id: usize, data: &mut GlobalData) -> usize {
-        // println!("Nodes on stack before pop: ({})", self.nodes.len());
-        // println!("+==+==+==+==+==+==+==+==+==+==+==+==+==+==+==+==+==");
-        // data.print_pool();
-        let mut popped_id = 0;
-
+    // The popped node is destroyed by the NodeManager once it has exited.
+    fn replace_by_id(&mut self, node_id: usize, data: &GlobalData) {
         if let Some(pscene) = self.nodes.pop() {
             let pops = pscene.borrow();
-            popped_id = pops.data().borrow().node.id();
             println!(
                 "replace_by_id has Popped '{}' ({})",
                 pops.to_string(),
-                popped_id
+                pops.id()
             );
-
-            // We also need to drop it from the pool
-            if let Some(node) = data.take_node(&popped_id) {
-                println!("replace_by_id flushing '{}'", node.borrow().name());
-                node.borrow().flush(true);
-            } else {
-                println!("replace_by_id couldn't take ({}) from pool", popped_id);
-            }
         } else {
             println!("replace_by_id WARNING nothing popped");
         }
 
-        // data.print_pool();
-        // println!("+==+==+==+==+==+==+==+==+==+==+==+==+==+==+==+==+==");
-
         if let Some(n) = data.find_node(&node_id) {
             // Make new node next node to run
             self.next_node = n.clone();
@@ -386,10 +952,6 @@ impl NodeStack {
             println!("replace_by_id couldn't find ({}) from pool", node_id);
         }
 
-        // println!("Nodes on stack after pop: ({})", self.nodes.len());
-
         self.signal_node_to_flush = true;
-
-        popped_id
     }
 }
@@ -2,7 +2,8 @@ use std::cell::RefCell;
 use std::rc::Rc;
 
 use math::affine_transform::AffineTransform;
-use nodes::node_trait::NodeType;
+use nodes::{components::component::BComponent, node_trait::NodeType};
+use rendering::stroke::RStrokeStyle;
 
 pub type RNodeData = Rc<RefCell<NodeData>>;
 
@@ -14,6 +15,12 @@ pub struct NodeData {
     pub transform: TransformProperties,
     pub timing: TimingProperties,
     pub transition: TransitionProperties,
+
+    // Attached behaviors
+    pub components: Vec<BComponent>,
+
+    // Line style for the node and its children, None inherits the parent's
+    pub stroke: Option<RStrokeStyle>,
 }
 
 impl NodeData {
@@ -23,8 +30,16 @@ impl NodeData {
             transform: TransformProperties::new(),
             timing: TimingProperties::new(),
             transition: TransitionProperties::new(),
+            components: Vec::new(),
+            stroke: None,
         }
     }
+
+    /// True if the node is updated each tick, either because it asked to
+    /// be or because it has components.
+    pub fn is_timing_target(&self) -> bool {
+        self.node.canbe_timing_target() || !self.components.is_empty()
+    }
 }
 
 // --------------------------------------------------------
@@ -45,6 +60,9 @@ pub struct NodeProperties {
 
     // The node "as a whole" dirty state
     dirty: bool,
+
+    // Life cycle: true while the node is part of a running tree.
+    on_stage: bool,
 }
 
 impl NodeProperties {
@@ -56,6 +74,7 @@ impl NodeProperties {
             visible: true,
             canbe_timing_target: false,
             dirty: true,
+            on_stage: false,
         }
     }
 
@@ -111,6 +130,14 @@ impl NodeProperties {
         self.canbe_timing_target = enabled;
     }
 
+    pub fn is_on_stage(&self) -> bool {
+        self.on_stage
+    }
+
+    pub fn set_on_stage(&mut self, on_stage: bool) {
+        self.on_stage = on_stage;
+    }
+
     pub fn to_string(&self) -> String {
         format!("[({}) : '{}']", self.id, self.name)
     }
@@ -212,6 +239,9 @@ pub struct TransformProperties {
 
     aft: AffineTransform,
     inverse: AffineTransform,
+
+    // Node-space to device-space as of the node's last visit.
+    device: AffineTransform,
 }
 
 impl TransformProperties {
@@ -222,6 +252,7 @@ impl TransformProperties {
             scale: (1.0, 1.0),
             aft: AffineTransform::new(),
             inverse: AffineTransform::new(),
+            device: AffineTransform::new(),
         }
     }
 
@@ -284,6 +315,14 @@ impl TransformProperties {
         self.scale = (scale, scale);
     }
 
+    pub fn get_device_transform(&self) -> &AffineTransform {
+        &self.device
+    }
+
+    pub fn set_device_transform(&mut self, aft: &AffineTransform) {
+        self.device = *aft;
+    }
+
     pub fn get_inverse(&self) -> &AffineTransform {
         &self.inverse
     }
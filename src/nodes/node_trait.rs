@@ -2,11 +2,20 @@ use std::any::Any;
 use std::cell::RefCell;
 use std::rc::Rc;
 
-use events::io_events::IOEventData;
+use events::{
+    event_bus::Event,
+    io_events::{EventPhase, EventResult, IOEventData, PointerEvent},
+};
+use geometry::point::{Point, RVertices};
 use math::affine_transform::AffineTransform;
-use nodes::{node_functions::NodeFunctions, node_properties::RNodeData};
-
-use rendering::render_context::Context;
+use nodes::{
+    components::component::{BComponent, Components},
+    hit_test::HitShape,
+    node_functions::NodeFunctions,
+    node_properties::RNodeData,
+};
+
+use rendering::{render_context::Context, stroke::StrokeStyle};
 use world::GlobalData;
 
 // The node system is similar to Inventor and/or Cocos2D:
@@ -17,6 +26,8 @@ pub enum NodeActions {
     SceneReplace,
     SceneReplaceTake,
     SceneReplaceTakeUnRegister,
+    // An overlay asks to be popped.
+    OverlayPop,
 }
 
 #[derive(Debug, PartialEq, Clone, Copy)]
@@ -104,29 +115,11 @@ pub trait NodeTrait {
         context.save();
         // context.print_stack(10);
 
-        // Because position and angles are dependent
-        // on lerping we perform interpolation first.
-        self.interpolate(interpolation);
-
-        // We need to scope the data() here because the draw() method will
-        // also want to borrow data().
-        {
-            let mut data = self.data().borrow_mut();
-            let aft: &AffineTransform;
-            if data.node.is_dirty() {
-                aft = data.transform.calc_transform();
-            } else {
-                aft = data.transform.get_transform();
-            }
-
-            context.apply(aft);
-            // println!("context.applied : {:?}", context.current());
-            // context.print_stack(10);
-        }
+        self.visit_transform(context, interpolation);
 
         if let Some(children) = self.get_children() {
             // println!("Drawing parent '{}'", self.name());
-            self.draw(context); // Draw parent
+            self.draw_node(context); // Draw parent
 
             // Visit any children contained by this node.
             for child in children.borrow().iter() {
@@ -141,7 +134,7 @@ pub trait NodeTrait {
         } else {
             // Just draw node
             // println!("Drawing leaf '{}'", self.name());
-            self.draw(context);
+            self.draw_node(context);
         }
 
         context.restore();
@@ -151,6 +144,51 @@ pub trait NodeTrait {
         // self.device_visit(context);
     }
 
+    // visit() calls this after saving the context. It interpolates the node
+    // then applies its transform and stroke to the context. Nodes that
+    // override visit() call it the same way.
+    fn visit_transform(&self, context: &mut Context, interpolation: f64) {
+        // Because position and angles are dependent
+        // on lerping we perform interpolation first.
+        self.interpolate(interpolation);
+        if Components::interpolate(self.data(), interpolation) {
+            self.ripple_node_dirty(true);
+        }
+
+        // We need to scope the data() here because the draw() method will
+        // also want to borrow data().
+        let mut data = self.data().borrow_mut();
+        let aft: &AffineTransform;
+        if data.node.is_dirty() {
+            aft = data.transform.calc_transform();
+        } else {
+            aft = data.transform.get_transform();
+        }
+
+        context.apply(aft);
+        // println!("context.applied : {:?}", context.current());
+        // context.print_stack(10);
+
+        let device = *context.current();
+        data.transform.set_device_transform(&device);
+
+        if let Some(stroke) = &data.stroke {
+            context.set_stroke(stroke.clone());
+        }
+    }
+
+    // Draws the node then its components.
+    fn draw_node(&self, context: &mut Context) {
+        self.draw(context);
+        Components::draw(self.data(), context);
+    }
+
+    /// The style lines are drawn with by this node and its children. None
+    /// uses the parent's style.
+    fn set_stroke(&self, stroke: Option<StrokeStyle>) {
+        self.data().borrow_mut().stroke = stroke.map(Rc::new);
+    }
+
     // visit() calls this method
     fn draw(&self, &mut Context) {
         // println!("{} has no rendering.", self.name());
@@ -158,6 +196,31 @@ pub trait NodeTrait {
 
     fn interpolate(&self, _interpolation: f64) {}
 
+    // --------------------------------------------------------
+    // Components
+    // --------------------------------------------------------
+    // Components are updated along with the node which means a node with
+    // components is a timing target. NodeManager picks up the change on the
+    // next visit if the node is on stage.
+    fn add_component(&self, component: BComponent, gdata: &GlobalData) {
+        self.data().borrow_mut().components.push(component);
+        gdata.queue_timing_change(self.id());
+    }
+
+    fn remove_component(&self, name: &str, gdata: &GlobalData) {
+        self.data()
+            .borrow_mut()
+            .components
+            .retain(|c| c.name() != name);
+        gdata.queue_timing_change(self.id());
+    }
+
+    // Device-space vertices as of the last draw, if the node has any.
+    // Typically this is the node's transformed "bucket".
+    fn device_vertices(&self) -> Option<&RVertices> {
+        None
+    }
+
     // --------------------------------------------------------
     // Grouping
     // --------------------------------------------------------
@@ -171,6 +234,14 @@ pub trait NodeTrait {
         }
     }
 
+    fn remove_child(&self, id: usize) {
+        if let Some(children) = self.get_children() {
+            children
+                .borrow_mut()
+                .retain(|child| child.borrow().id() != id);
+        }
+    }
+
     // --------------------------------------------------------
     // Transformations
     // --------------------------------------------------------
@@ -228,15 +299,54 @@ pub trait NodeTrait {
     // --------------------------------------------------------
     // IO Events
     // --------------------------------------------------------
-    fn io_event(&self, io_event: &IOEventData) {
-        if let Some(children) = self.get_children() {
-            for child in children.borrow().iter() {
-                println!("io_event '{}' of '{}'", child.borrow().name(), self.name());
-                child.borrow_mut().io_event(io_event);
-            }
-        }
+    // Scenes and overlays that only display, for example, a HUD, return
+    // false so input goes to the scene below them.
+    fn accepts_io_events(&self) -> bool {
+        true
+    }
+
+    // Called for the event types the node registered for (see
+    // GlobalData::register_io_event_target) when the node is on the path
+    // to the event's target. Returning Handled stops propagation.
+    fn io_event(&self, _phase: EventPhase, _io_event: &IOEventData) -> EventResult {
+        EventResult::Unhandled
     }
 
+    // Nodes that return a shape receive pointer events when they are the
+    // frontmost node under the mouse.
+    fn hit_shape(&self) -> HitShape {
+        HitShape::None
+    }
+
+    // Hit test for HitShape::Custom, with the pointer in node-space.
+    fn contains_local_point(&self, _p: &Point) -> bool {
+        false
+    }
+
+    // The device points to hit test the children with, frontmost first.
+    // A node that draws its children more than once, like a wrapping
+    // ParallaxLayer, maps the point from each copy onto the copy drawn
+    // last, whose transforms the children keep.
+    fn child_hit_points(&self, device: &Point) -> Vec<Point> {
+        vec![*device]
+    }
+
+    fn pointer_event(&self, _event: &PointerEvent) {}
+
+    // Focusable nodes can be given keyboard focus, see FocusManager.
+    fn focusable(&self) -> bool {
+        false
+    }
+
+    fn focus_changed(&self, _focused: bool) {}
+
+    // --------------------------------------------------------
+    // Messaging
+    // --------------------------------------------------------
+    // Called by the NodeManager, at a safe point, for each event this node
+    // subscribed to on the EventBus.
+    fn on_event(&self, _event: &Event) {}
+
     // --------------------------------------------------------
     // Mappings
     // --------------------------------------------------------
@@ -304,6 +414,24 @@ pub trait NodeTrait {
     // --------------------------------------------------------
     // Life cycle events
     // --------------------------------------------------------
+    // The on_xxx hooks are driven by the NodeManager and are called on every
+    // node of a tree, parents before children:
+    //
+    // on_attached:    the node was attached to `parent` (see NodeGroup).
+    // on_enter_stage: the tree became part of the running scene. Nodes marked
+    //                 `canbe_timing_target` are registered just before this.
+    // on_exit_stage:  the tree left the stage. Timing targets are
+    //                 unregistered just before this.
+    // on_destroyed:   the tree was flushed and the node was removed from the
+    //                 pool, io event targets and event bus subscriptions.
+    fn on_attached(&self, _parent: usize) {}
+    fn on_enter_stage(&self) {}
+    fn on_exit_stage(&self) {}
+    fn on_destroyed(&self) {}
+
+    // The remaining events are scene level events. The NodeManager only
+    // signals the scene node; scenes relay to their children via sub_enter
+    // and sub_exit if they need to.
     fn start_exit_transition(&self, &mut GlobalData) {}
     fn end_enter_transition(&self) {}
 
@@ -345,10 +473,9 @@ pub trait NodeTrait {
             //     children.borrow().len()
             // );
             self.sub_flush(flush, children);
-        } else {
-            // TODO perhaps unregister for stuff like io events and timing targets
-
         }
+        // Io events, timing targets and subscriptions are released by
+        // NodeManager::destroy_node prior to flushing.
     }
 
     fn sub_flush(&self, flush: bool, children: &RefCell<Vec<RNode>>) {
@@ -423,6 +550,16 @@ pub trait NodeTrait {
         0
     }
 
+    // SceneTransition nodes only. The NodeManager calls begin_transition when
+    // the transition becomes the running node; `outgoing` is the node being
+    // replaced (it may be nil). Once the transition is done the NodeManager
+    // takes the outgoing node back to complete its exit.
+    fn begin_transition(&self, _outgoing: &RNode) {}
+
+    fn take_outgoing_node(&self) -> Option<RNode> {
+        None
+    }
+
     // --------------------------------------------------------
     // Misc
     // --------------------------------------------------------
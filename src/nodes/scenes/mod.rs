@@ -1 +1,3 @@
 pub mod scene_boot;
+pub mod scene_loading;
+pub mod scene_transition;
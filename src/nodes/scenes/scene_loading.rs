@@ -0,0 +1,164 @@
+use std::any::Any;
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+use assets::loader::RLoadProgress;
+use nodes::{
+    node_properties::{NodeData, RNodeData},
+    node_trait::{NodeActions, NodeTrait, NodeType, RNode},
+};
+use rendering::{color::Palette, render_context::Context};
+use world::{GlobalData, World};
+
+// Displays a Loader's progress and replaces itself with the target scene
+// once the loader completes. The target scene is typically created empty
+// and the loader's steps attach the heavy parts to it:
+//
+//   let game = GameScene::new("GameScene", world);
+//   let mut loader = Loader::new();
+//   loader.add_step(move |world, _files| { /* attach children to game_id */ });
+//   let loading = SceneLoading::new("Loading", game_id, loader.progress(), world);
+//   world.start_loading(loader);
+//
+// The progress bar is drawn in device-space. Override the colors, or use
+// progress() to draw a custom bar in another scene.
+
+const BAR_HEIGHT: i32 = 24;
+
+pub struct SceneLoading {
+    replacement: Cell<usize>,
+
+    data: RNodeData,
+
+    parent: Cell<usize>,
+
+    progress: RLoadProgress,
+
+    bar_color: RefCell<Palette>,
+    text_color: RefCell<Palette>,
+}
+
+impl Drop for SceneLoading {
+    fn drop(&mut self) {
+        println!("Dropping: '{}'", self.data().borrow().node.name());
+    }
+}
+
+impl SceneLoading {
+    pub fn new(
+        name: &str,
+        replacement: usize,
+        progress: RLoadProgress,
+        world: &mut World,
+    ) -> RNode {
+        let mut n = NodeData::new();
+        n.node.set_name(name.to_string());
+        n.node.set_type(NodeType::Scene);
+        n.node.set_id(world.gen_id());
+
+        let sl = Self {
+            replacement: Cell::new(replacement),
+            data: Rc::new(RefCell::new(n)),
+            parent: Cell::new(replacement),
+            progress,
+            bar_color: RefCell::new(Palette::SOFT_GREEN()),
+            text_color: RefCell::new(Palette::WHITE(255)),
+        };
+
+        let rc: RNode = Rc::new(RefCell::new(sl));
+
+        world.data_mut().add_node(rc.clone());
+
+        rc
+    }
+
+    pub fn progress(&self) -> RLoadProgress {
+        self.progress.clone()
+    }
+
+    pub fn set_colors(&self, bar: Palette, text: Palette) {
+        self.bar_color.replace(bar);
+        self.text_color.replace(text);
+    }
+}
+
+impl NodeTrait for SceneLoading {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    // --------------------------------------------------------
+    // Node properties
+    // --------------------------------------------------------
+    fn data(&self) -> &RNodeData {
+        &self.data
+    }
+
+    // --------------------------------------------------------
+    // Transformations
+    // --------------------------------------------------------
+    fn parent(&self) -> usize {
+        self.parent.get()
+    }
+
+    fn set_parent(&self, parent: usize) {
+        self.parent.replace(parent);
+    }
+
+    // --------------------------------------------------------
+    // Rendering
+    // --------------------------------------------------------
+    fn visit(&self, context: &mut Context, _interpolation: f64, gdata: &GlobalData) {
+        let fraction = self.progress.borrow().fraction();
+
+        let width = gdata.window_width as i32;
+        let height = gdata.window_height as i32;
+
+        // A bar across the middle half of the window
+        let xmin = width / 4;
+        let xmax = width - width / 4;
+        let ymin = height / 2 - BAR_HEIGHT / 2;
+        let ymax = ymin + BAR_HEIGHT;
+        let fill = xmin + ((xmax - xmin) as f64 * fraction) as i32;
+
+        let bar = *self.bar_color.borrow();
+        context.set_draw_color(&bar);
+        if fill > xmin {
+            context.fill_rectangle(xmin, ymin, fill, ymax);
+        }
+        context.draw_rectangle(xmin, ymin, xmax, ymax);
+
+        let text = *self.text_color.borrow();
+        context.set_draw_color(&text);
+        context.text(
+            xmin,
+            ymin - BAR_HEIGHT - 8,
+            &format!("Loading {:3.0}%", fraction * 100.0),
+            3,
+            1,
+        );
+    }
+
+    // --------------------------------------------------------
+    // Transitions
+    // --------------------------------------------------------
+    fn transition(&self) -> NodeActions {
+        if self.progress.borrow().is_complete() {
+            return NodeActions::SceneReplaceTake;
+        }
+
+        NodeActions::NoAction
+    }
+
+    fn take_transition_node(&self) -> usize {
+        self.replacement.replace(0)
+    }
+
+    fn get_transition_node(&self) -> usize {
+        self.replacement.get()
+    }
+}
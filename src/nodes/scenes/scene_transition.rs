@@ -0,0 +1,391 @@
+use std::any::Any;
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+use math::{affine_transform::AffineTransform, interpolation::Interpolation};
+use nodes::{
+    node_properties::{NodeData, RNodeData},
+    node_trait::{NodeActions, NodeTrait, NodeType, RNode},
+};
+use rendering::{color::Palette, render_context::Context};
+use world::{GlobalData, World};
+
+// A transition renders the outgoing and incoming scenes together over a
+// duration and then hands the stage to the incoming scene. To use one,
+// make the transition the replacement of the outgoing scene:
+//
+//   let game = GameScene::new("GameScene", world);
+//   let fade = SceneTransition::new("Fade", Effect::Fade(Palette::BLACK()), 0.5, game_id, world);
+//   let splash = SplashScene::with_replacement("SplashScene", fade_id, world);
+//
+// The NodeManager signals start_exit_transition on the outgoing scene and
+// enter on the incoming scene when the transition begins. When it ends the
+// outgoing scene exits and the incoming scene gets end_enter_transition.
+
+// (outgoing, incoming)
+type Scenes<'a> = (Option<&'a RNode>, Option<&'a RNode>);
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Direction {
+    Left,
+    Right,
+    Up,
+    Down,
+}
+
+#[derive(Debug, Copy, Clone)]
+pub enum Effect {
+    // Fade the outgoing scene out to a color then fade the incoming in.
+    Fade(Palette),
+    // The incoming scene pushes the outgoing scene out, moving in Direction.
+    Slide(Direction),
+    // An edge moving in Direction uncovers the incoming scene.
+    Wipe(Direction),
+    // Blend from one scene to the other.
+    CrossFade,
+}
+
+pub struct SceneTransition {
+    replacement: Cell<usize>,
+
+    data: RNodeData,
+
+    parent: Cell<usize>,
+
+    effect: Effect,
+
+    // Milliseconds
+    duration: f64,
+    elapsed: Cell<f64>,
+
+    // Progress (0.0 -> 1.0) before and after the last update
+    from: Cell<f64>,
+    to: Cell<f64>,
+
+    incoming: usize,
+    outgoing: RefCell<Option<RNode>>,
+}
+
+impl Drop for SceneTransition {
+    fn drop(&mut self) {
+        println!("Dropping: '{}'", self.data().borrow().node.name());
+    }
+}
+
+impl SceneTransition {
+    pub fn new(
+        name: &str,
+        effect: Effect,
+        seconds: f64,
+        replacement: usize,
+        world: &mut World,
+    ) -> RNode {
+        let mut n = NodeData::new();
+        n.node.set_name(name.to_string());
+        n.node.set_type(NodeType::SceneTransition);
+        n.node.set_id(world.gen_id());
+        n.node.make_timing_target(true);
+
+        let st = Self {
+            replacement: Cell::new(replacement),
+            data: Rc::new(RefCell::new(n)),
+            parent: Cell::new(replacement),
+            effect,
+            duration: seconds * 1000.0,
+            elapsed: Cell::new(0.0),
+            from: Cell::new(0.0),
+            to: Cell::new(0.0),
+            incoming: replacement,
+            outgoing: RefCell::new(None),
+        };
+
+        let rc: RNode = Rc::new(RefCell::new(st));
+
+        world.data_mut().add_node(rc.clone());
+
+        rc
+    }
+
+    fn progress(&self, interpolation: f64) -> f64 {
+        Interpolation::lerp(self.from.get(), self.to.get(), interpolation).clamp(0.0, 1.0)
+    }
+
+    fn fade(
+        color: &Palette,
+        progress: f64,
+        (outgoing, incoming): Scenes,
+        context: &mut Context,
+        interpolation: f64,
+        gdata: &GlobalData,
+    ) {
+        // First half covers the outgoing scene, the second half uncovers
+        // the incoming scene.
+        let (scene, cover) = if progress < 0.5 {
+            (outgoing, progress * 2.0)
+        } else {
+            (incoming, (1.0 - progress) * 2.0)
+        };
+
+        if let Some(scene) = scene {
+            scene.borrow().visit(context, interpolation, gdata);
+        }
+
+        let alpha = (color.a as f64 * cover).round() as u8;
+        context.set_draw_color(&Palette::RGBA(color.r, color.g, color.b, alpha));
+        context.fill_rectangle(0, 0, gdata.window_width as i32, gdata.window_height as i32);
+    }
+
+    fn slide(
+        direction: Direction,
+        progress: f64,
+        (outgoing, incoming): Scenes,
+        context: &mut Context,
+        interpolation: f64,
+        gdata: &GlobalData,
+    ) {
+        // View-space distance to travel
+        let (dx, dy) = match direction {
+            Direction::Left => (-gdata.view_width, 0.0),
+            Direction::Right => (gdata.view_width, 0.0),
+            Direction::Up => (0.0, -gdata.view_height),
+            Direction::Down => (0.0, gdata.view_height),
+        };
+
+        if let Some(scene) = outgoing {
+            SceneTransition::visit_offset(
+                scene,
+                dx * progress,
+                dy * progress,
+                context,
+                interpolation,
+                gdata,
+            );
+        }
+
+        if let Some(scene) = incoming {
+            SceneTransition::visit_offset(
+                scene,
+                dx * (progress - 1.0),
+                dy * (progress - 1.0),
+                context,
+                interpolation,
+                gdata,
+            );
+        }
+    }
+
+    fn wipe(
+        direction: Direction,
+        progress: f64,
+        (outgoing, incoming): Scenes,
+        context: &mut Context,
+        interpolation: f64,
+        gdata: &GlobalData,
+    ) {
+        let width = gdata.window_width as i32;
+        let height = gdata.window_height as i32;
+
+        // Device-space regions (xmin, ymin, xmax, ymax) of each scene
+        let (out_region, in_region) = match direction {
+            Direction::Left => {
+                let edge = (width as f64 * (1.0 - progress)) as i32;
+                ((0, 0, edge, height), (edge, 0, width, height))
+            }
+            Direction::Right => {
+                let edge = (width as f64 * progress) as i32;
+                ((edge, 0, width, height), (0, 0, edge, height))
+            }
+            Direction::Up => {
+                let edge = (height as f64 * (1.0 - progress)) as i32;
+                ((0, 0, width, edge), (0, edge, width, height))
+            }
+            Direction::Down => {
+                let edge = (height as f64 * progress) as i32;
+                ((0, edge, width, height), (0, 0, width, edge))
+            }
+        };
+
+        for &(scene, region) in [(outgoing, out_region), (incoming, in_region)].iter() {
+            let (xmin, ymin, xmax, ymax) = region;
+            if let Some(scene) = scene {
+                if xmax > xmin && ymax > ymin {
+                    context.clip(xmin, ymin, xmax, ymax);
+                    scene.borrow().visit(context, interpolation, gdata);
+                }
+            }
+        }
+
+        context.unclip();
+    }
+
+    fn cross_fade(
+        progress: f64,
+        (outgoing, incoming): Scenes,
+        context: &mut Context,
+        interpolation: f64,
+        gdata: &GlobalData,
+    ) {
+        let opacity = context.opacity();
+
+        if let Some(scene) = outgoing {
+            context.set_opacity(opacity * (1.0 - progress));
+            scene.borrow().visit(context, interpolation, gdata);
+        }
+
+        if let Some(scene) = incoming {
+            context.set_opacity(opacity * progress);
+            scene.borrow().visit(context, interpolation, gdata);
+        }
+
+        context.set_opacity(opacity);
+    }
+
+    fn visit_offset(
+        scene: &RNode,
+        dx: f64,
+        dy: f64,
+        context: &mut Context,
+        interpolation: f64,
+        gdata: &GlobalData,
+    ) {
+        let mut offset = AffineTransform::new();
+        offset.make_translate(dx, dy);
+
+        context.save();
+        context.apply(&offset);
+
+        // The offset changes every frame so the cached device vertices
+        // are always stale.
+        let sc = scene.borrow();
+        sc.ripple_node_dirty(true);
+        sc.visit(context, interpolation, gdata);
+
+        context.restore();
+    }
+}
+
+impl NodeTrait for SceneTransition {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    // --------------------------------------------------------
+    // Node properties
+    // --------------------------------------------------------
+    fn data(&self) -> &RNodeData {
+        &self.data
+    }
+
+    // --------------------------------------------------------
+    // Transformations
+    // --------------------------------------------------------
+    fn parent(&self) -> usize {
+        self.parent.get()
+    }
+
+    fn set_parent(&self, parent: usize) {
+        self.parent.replace(parent);
+    }
+
+    // --------------------------------------------------------
+    // Timing target
+    // --------------------------------------------------------
+    fn update(&self, dt: f64) {
+        self.elapsed.set(self.elapsed.get() + dt);
+
+        self.from.set(self.to.get());
+        if self.duration > 0.0 {
+            self.to.set((self.elapsed.get() / self.duration).min(1.0));
+        } else {
+            self.to.set(1.0);
+        }
+    }
+
+    // --------------------------------------------------------
+    // Rendering
+    // --------------------------------------------------------
+    fn visit(&self, context: &mut Context, interpolation: f64, gdata: &GlobalData) {
+        let progress = self.progress(interpolation);
+
+        let outgoing = self.outgoing.borrow().clone();
+        let incoming = gdata.find_node(&self.incoming).cloned();
+
+        let scenes = (outgoing.as_ref(), incoming.as_ref());
+
+        match self.effect {
+            Effect::Fade(color) => {
+                SceneTransition::fade(&color, progress, scenes, context, interpolation, gdata)
+            }
+            Effect::Slide(direction) => {
+                SceneTransition::slide(direction, progress, scenes, context, interpolation, gdata)
+            }
+            Effect::Wipe(direction) => {
+                SceneTransition::wipe(direction, progress, scenes, context, interpolation, gdata)
+            }
+            Effect::CrossFade => {
+                SceneTransition::cross_fade(progress, scenes, context, interpolation, gdata)
+            }
+        }
+    }
+
+    // --------------------------------------------------------
+    // Life cycle events
+    // --------------------------------------------------------
+    fn enter(&self) {
+        self.elapsed.set(0.0);
+        self.from.set(0.0);
+        self.to.set(0.0);
+    }
+
+    fn exit(&self, data: &mut GlobalData) {
+        // Slide leaves the incoming scene's vertices at the last offset drawn.
+        if let Some(incoming) = data.find_node(&self.incoming) {
+            incoming.borrow().ripple_node_dirty(true);
+        }
+    }
+
+    // --------------------------------------------------------
+    // Transitions
+    // --------------------------------------------------------
+    fn transition(&self) -> NodeActions {
+        if self.to.get() >= 1.0 {
+            return NodeActions::SceneReplaceTake;
+        }
+
+        NodeActions::NoAction
+    }
+
+    fn take_transition_node(&self) -> usize {
+        self.replacement.replace(0)
+    }
+
+    fn get_transition_node(&self) -> usize {
+        self.replacement.get()
+    }
+
+    fn begin_transition(&self, outgoing: &RNode) {
+        self.enter();
+
+        if outgoing.borrow().is_nil() {
+            self.outgoing.replace(None);
+        } else {
+            self.outgoing.replace(Some(outgoing.clone()));
+        }
+    }
+
+    fn take_outgoing_node(&self) -> Option<RNode> {
+        let outgoing = self.outgoing.borrow_mut().take();
+
+        // A slid out scene may return later, for example, if it is still on
+        // the stack.
+        if let Some(node) = &outgoing {
+            node.borrow().ripple_node_dirty(true);
+        }
+
+        outgoing
+    }
+}
@@ -0,0 +1,292 @@
+extern crate ab_glyph;
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use self::ab_glyph::{point, Font as GlyphFont, FontArc, GlyphId, PxScale, ScaleFont};
+
+use rendering::{color::Palette, texture::Texture};
+
+// TrueType and OpenType fonts, rasterized by ab_glyph so no system library
+// is needed.
+//
+//   let font = world.data().fonts().borrow_mut().load("assets/DejaVuSans.ttf")?;
+//   let layout = font.layout("Score\n1200", 24.0, TextAlignment::Right, 1.0);
+//   let (image, offset) = font.rasterize(&layout, 24.0, 2.0, Palette::WHITE(255));
+//
+// Sizes are the height from the lowest descender to the highest ascender,
+// in whatever units the caller works in. Layouts are made in those units,
+// and rasterize() draws them at a number of pixels per unit. Rasterized
+// glyphs are cached by glyph and pixel size.
+
+pub type RFont = Rc<Font>;
+
+// Glyphs cached before the cache is emptied, as zooming through many
+// sizes would otherwise grow it without end.
+const MAX_CACHED_GLYPHS: usize = 4096;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum TextAlignment {
+    Left,
+    Center,
+    Right,
+}
+
+impl TextAlignment {
+    /// How far across a line of `width` its start is from the node's
+    /// position, as a fraction of the width.
+    pub fn anchor(&self) -> f64 {
+        match self {
+            TextAlignment::Left => 0.0,
+            TextAlignment::Center => 0.5,
+            TextAlignment::Right => 1.0,
+        }
+    }
+}
+
+/// A glyph placed by layout().
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct PlacedGlyph {
+    pub id: u16,
+    // Pen position on the baseline
+    pub x: f64,
+    pub y: f64,
+}
+
+/// Lines of text placed within a block whose top-left corner is the
+/// origin.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TextLayout {
+    pub glyphs: Vec<PlacedGlyph>,
+    pub width: f64,
+    pub height: f64,
+}
+
+// A glyph's coverage at one pixel size
+struct GlyphBitmap {
+    width: usize,
+    height: usize,
+    // Top-left corner from the pen position
+    left: i32,
+    top: i32,
+    coverage: Vec<u8>,
+}
+
+pub struct Font {
+    font: FontArc,
+    glyphs: RefCell<HashMap<(u16, u32), Rc<GlyphBitmap>>>,
+}
+
+impl Font {
+    pub fn from_bytes(bytes: Vec<u8>) -> Result<Self, String> {
+        match FontArc::try_from_vec(bytes) {
+            Ok(font) => Ok(Self {
+                font,
+                glyphs: RefCell::new(HashMap::new()),
+            }),
+            Err(err) => Err(err.to_string()),
+        }
+    }
+
+    // --------------------------------------------------------
+    // Metrics
+    // --------------------------------------------------------
+    /// Height above the baseline.
+    pub fn ascent(&self, size: f64) -> f64 {
+        self.font.as_scaled(size as f32).ascent() as f64
+    }
+
+    /// Depth below the baseline, as a positive number.
+    pub fn descent(&self, size: f64) -> f64 {
+        -self.font.as_scaled(size as f32).descent() as f64
+    }
+
+    /// Baseline to baseline distance the font suggests.
+    pub fn line_height(&self, size: f64) -> f64 {
+        let scaled = self.font.as_scaled(size as f32);
+        (scaled.height() + scaled.line_gap()) as f64
+    }
+
+    /// How far the pen moves after `c`, not counting kerning.
+    pub fn advance(&self, c: char, size: f64) -> f64 {
+        let scaled = self.font.as_scaled(size as f32);
+        scaled.h_advance(self.font.glyph_id(c)) as f64
+    }
+
+    /// The width of each line of `text`.
+    pub fn measure(&self, text: &str, size: f64) -> Vec<f64> {
+        text.split('\n')
+            .map(|line| {
+                let mut width = 0.0;
+                self.place_line(line, size, |_, x| width = x);
+                width
+            })
+            .collect()
+    }
+
+    // Calls `place` with each glyph's id and pen x, then with None and the
+    // line's width.
+    fn place_line<F: FnMut(Option<GlyphId>, f64)>(&self, line: &str, size: f64, mut place: F) {
+        let scaled = self.font.as_scaled(size as f32);
+        let mut x = 0.0f32;
+        let mut previous: Option<GlyphId> = None;
+
+        for c in line.chars() {
+            if c.is_control() {
+                continue;
+            }
+            let id = self.font.glyph_id(c);
+            if let Some(previous) = previous {
+                x += scaled.kern(previous, id);
+            }
+            place(Some(id), x as f64);
+            x += scaled.h_advance(id);
+            previous = Some(id);
+        }
+
+        place(None, x as f64);
+    }
+
+    // --------------------------------------------------------
+    // Layout
+    // --------------------------------------------------------
+    /// Places `text`, with lines split at '\n', aligned against the widest
+    /// line. `line_spacing` scales the font's line height.
+    pub fn layout(
+        &self,
+        text: &str,
+        size: f64,
+        alignment: TextAlignment,
+        line_spacing: f64,
+    ) -> TextLayout {
+        let widths = self.measure(text, size);
+        let width = widths.iter().cloned().fold(0.0, f64::max);
+        let line_height = self.line_height(size) * line_spacing;
+        let ascent = self.ascent(size);
+
+        let mut layout = TextLayout {
+            glyphs: Vec::new(),
+            width,
+            height: 0.0,
+        };
+
+        for (i, line) in text.split('\n').enumerate() {
+            let start = (width - widths[i]) * alignment.anchor();
+            let y = ascent + i as f64 * line_height;
+
+            self.place_line(line, size, |id, x| {
+                if let Some(id) = id {
+                    layout.glyphs.push(PlacedGlyph {
+                        id: id.0,
+                        x: start + x,
+                        y,
+                    });
+                }
+            });
+        }
+
+        layout.height = (widths.len() - 1) as f64 * line_height + ascent + self.descent(size);
+        layout
+    }
+
+    // --------------------------------------------------------
+    // Rasterizing
+    // --------------------------------------------------------
+    /// Draws `layout`, made at `size`, at `scale` pixels per unit in
+    /// `color`. Returns the image and where its top-left corner is in the
+    /// layout, in units, or None if nothing is visible.
+    pub fn rasterize(
+        &self,
+        layout: &TextLayout,
+        size: f64,
+        scale: f64,
+        color: Palette,
+    ) -> Option<(Texture, (f64, f64))> {
+        let px = ((size * scale).round() as u32).max(1);
+
+        // Glyphs and their top-left pixel in the layout
+        let mut placed = Vec::with_capacity(layout.glyphs.len());
+        let (mut min_x, mut min_y) = (i32::MAX, i32::MAX);
+        let (mut max_x, mut max_y) = (i32::MIN, i32::MIN);
+        for glyph in layout.glyphs.iter() {
+            let bitmap = match self.glyph(glyph.id, px) {
+                Some(bitmap) => bitmap,
+                None => continue,
+            };
+            let x = (glyph.x * scale).round() as i32 + bitmap.left;
+            let y = (glyph.y * scale).round() as i32 + bitmap.top;
+
+            min_x = min_x.min(x);
+            min_y = min_y.min(y);
+            max_x = max_x.max(x + bitmap.width as i32);
+            max_y = max_y.max(y + bitmap.height as i32);
+            placed.push((bitmap, x, y));
+        }
+
+        if placed.is_empty() {
+            return None;
+        }
+
+        let (width, height) = ((max_x - min_x) as usize, (max_y - min_y) as usize);
+        let mut pixels = vec![0u8; width * height * 4];
+        for p in pixels.chunks_mut(4) {
+            p[0] = color.r;
+            p[1] = color.g;
+            p[2] = color.b;
+        }
+
+        for (bitmap, x, y) in placed.iter() {
+            let (ox, oy) = ((x - min_x) as usize, (y - min_y) as usize);
+            for row in 0..bitmap.height {
+                for column in 0..bitmap.width {
+                    let coverage = bitmap.coverage[row * bitmap.width + column] as u32;
+                    let alpha = (coverage * color.a as u32 / 255) as u8;
+                    // Overlapping glyphs keep the stronger coverage.
+                    let i = ((oy + row) * width + ox + column) * 4 + 3;
+                    pixels[i] = pixels[i].max(alpha);
+                }
+            }
+        }
+
+        match Texture::from_rgba(width as u32, height as u32, pixels) {
+            Ok(texture) => Some((texture, (min_x as f64 / scale, min_y as f64 / scale))),
+            Err(_) => None,
+        }
+    }
+
+    fn glyph(&self, id: u16, px: u32) -> Option<Rc<GlyphBitmap>> {
+        let mut glyphs = self.glyphs.borrow_mut();
+        if let Some(bitmap) = glyphs.get(&(id, px)) {
+            return Some(bitmap.clone());
+        }
+
+        let glyph = GlyphId(id).with_scale_and_position(PxScale::from(px as f32), point(0.0, 0.0));
+        let outlined = self.font.outline_glyph(glyph)?;
+        let bounds = outlined.px_bounds();
+        let (width, height) = (bounds.width() as usize, bounds.height() as usize);
+
+        let mut coverage = vec![0u8; width * height];
+        outlined.draw(|x, y, c| {
+            let (x, y) = (x as usize, y as usize);
+            if x < width && y < height {
+                coverage[y * width + x] = (c.clamp(0.0, 1.0) * 255.0).round() as u8;
+            }
+        });
+
+        let bitmap = Rc::new(GlyphBitmap {
+            width,
+            height,
+            left: bounds.min.x as i32,
+            top: bounds.min.y as i32,
+            coverage,
+        });
+
+        if glyphs.len() >= MAX_CACHED_GLYPHS {
+            glyphs.clear();
+        }
+        glyphs.insert((id, px), bitmap.clone());
+
+        Some(bitmap)
+    }
+}
@@ -1,6 +1,11 @@
 pub mod color;
+pub mod font;
 mod fx_edge;
 pub mod fx_rectangle;
 pub mod fx_triangle;
+pub mod pixel_buffer;
 pub mod render_context;
+pub mod render_target;
+pub mod stroke;
+pub mod texture;
 pub mod vector_font;
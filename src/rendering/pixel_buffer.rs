@@ -0,0 +1,261 @@
+extern crate sdl2;
+
+use std::any::Any;
+
+use self::sdl2::{
+    pixels::Color,
+    rect::{Point, Rect},
+};
+
+use geometry::rectangle::Rectangle;
+use rendering::{render_target::RenderTarget, texture::Texture};
+
+// A software render target: an RGBA image in memory.
+//
+//   let buffer = PixelBuffer::new(800, 600);
+//   let mut context = Context::with_target(Box::new(buffer));
+//   ...
+//   let frame = context.target().as_any().downcast_ref::<PixelBuffer>();
+//
+// Drawing follows the SDL canvas: colors are alpha blended over what is
+// already there and rectangles cover width x height pixels from their
+// top-left corner.
+
+pub struct PixelBuffer {
+    width: u32,
+    height: u32,
+    // RGBA rows from the top left
+    pixels: Vec<u8>,
+
+    draw_color: Color,
+
+    // (min x, min y, max x, max y), max exclusive
+    clip: (i32, i32, i32, i32),
+
+    // Number of times present() was called
+    frames: usize,
+}
+
+impl PixelBuffer {
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![0; width as usize * height as usize * 4],
+            draw_color: Color::RGBA(255, 255, 255, 255),
+            clip: (0, 0, width as i32, height as i32),
+            frames: 0,
+        }
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn pixels(&self) -> &[u8] {
+        &self.pixels
+    }
+
+    pub fn frames(&self) -> usize {
+        self.frames
+    }
+
+    pub fn pixel(&self, x: i32, y: i32) -> Option<Color> {
+        if x < 0 || y < 0 || x >= self.width as i32 || y >= self.height as i32 {
+            return None;
+        }
+        let i = self.index(x, y);
+        let p = &self.pixels[i..i + 4];
+        Some(Color::RGBA(p[0], p[1], p[2], p[3]))
+    }
+
+    /// Sets every pixel, ignoring the clip rectangle.
+    pub fn clear(&mut self, color: Color) {
+        for p in self.pixels.chunks_mut(4) {
+            p.copy_from_slice(&[color.r, color.g, color.b, color.a]);
+        }
+    }
+
+    #[inline]
+    fn index(&self, x: i32, y: i32) -> usize {
+        (y as usize * self.width as usize + x as usize) * 4
+    }
+
+    #[inline]
+    fn blend(&mut self, x: i32, y: i32, r: u8, g: u8, b: u8, a: u8) {
+        let (min_x, min_y, max_x, max_y) = self.clip;
+        if x < min_x || y < min_y || x >= max_x || y >= max_y || a == 0 {
+            return;
+        }
+
+        let i = self.index(x, y);
+        let p = &mut self.pixels[i..i + 4];
+
+        if a == 255 {
+            p.copy_from_slice(&[r, g, b, 255]);
+            return;
+        }
+
+        // Source over
+        let sa = a as u32;
+        let da = p[3] as u32 * (255 - sa) / 255;
+        let out_a = sa + da;
+        let mix = |s: u8, d: u8| ((s as u32 * sa + d as u32 * da) / out_a) as u8;
+
+        p[0] = mix(r, p[0]);
+        p[1] = mix(g, p[1]);
+        p[2] = mix(b, p[2]);
+        p[3] = out_a as u8;
+    }
+
+    #[inline]
+    fn plot(&mut self, x: i32, y: i32) {
+        let c = self.draw_color;
+        self.blend(x, y, c.r, c.g, c.b, c.a);
+    }
+
+    // Spans and columns are cut to the clip rectangle here, so their ends
+    // may be far outside it; they are i64 so working them out can't overflow.
+    fn fill_span(&mut self, x1: i64, x2: i64, y: i64) {
+        let (min_x, min_y, max_x, max_y) = self.clip;
+        if y < min_y as i64 || y >= max_y as i64 {
+            return;
+        }
+        for x in x1.max(min_x as i64)..=x2.min(max_x as i64 - 1) {
+            self.plot(x as i32, y as i32);
+        }
+    }
+
+    fn fill_column(&mut self, x: i64, y1: i64, y2: i64) {
+        let (min_x, min_y, max_x, max_y) = self.clip;
+        if x < min_x as i64 || x >= max_x as i64 {
+            return;
+        }
+        for y in y1.max(min_y as i64)..=y2.min(max_y as i64 - 1) {
+            self.plot(x as i32, y as i32);
+        }
+    }
+}
+
+impl RenderTarget for PixelBuffer {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn set_draw_color(&mut self, color: Color) {
+        self.draw_color = color;
+    }
+
+    fn draw_point(&mut self, point: Point) -> Result<(), String> {
+        self.plot(point.x(), point.y());
+        Ok(())
+    }
+
+    fn draw_line(&mut self, start: Point, end: Point) -> Result<(), String> {
+        // Only the part within a pixel of the clip rectangle is stepped.
+        let (min_x, min_y, max_x, max_y) = self.clip;
+        let bounds = Rectangle::from_min_max(
+            min_x as f64 - 1.0,
+            min_y as f64 - 1.0,
+            max_x as f64,
+            max_y as f64,
+        );
+        let (x1, y1) = (start.x() as f64, start.y() as f64);
+        let (x2, y2) = (end.x() as f64, end.y() as f64);
+        let (t0, t1) = match bounds.clip_segment((x1, y1), (x2, y2)) {
+            Some(range) => range,
+            None => return Ok(()),
+        };
+        let (ex, ey) = (x2 - x1, y2 - y1);
+
+        // Bresenham
+        let (mut x, mut y) = ((x1 + ex * t0).round() as i64, (y1 + ey * t0).round() as i64);
+        let (x2, y2) = ((x1 + ex * t1).round() as i64, (y1 + ey * t1).round() as i64);
+        let dx = (x2 - x).abs();
+        let dy = -(y2 - y).abs();
+        let sx = if x < x2 { 1 } else { -1 };
+        let sy = if y < y2 { 1 } else { -1 };
+        let mut err = dx + dy;
+
+        loop {
+            self.plot(x as i32, y as i32);
+            if x == x2 && y == y2 {
+                break;
+            }
+            let e2 = 2 * err;
+            if e2 >= dy {
+                err += dy;
+                x += sx;
+            }
+            if e2 <= dx {
+                err += dx;
+                y += sy;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn draw_rect(&mut self, rect: Rect) -> Result<(), String> {
+        let (x1, y1) = (rect.x() as i64, rect.y() as i64);
+        let (x2, y2) = (x1 + rect.width() as i64 - 1, y1 + rect.height() as i64 - 1);
+
+        self.fill_span(x1, x2, y1);
+        if y2 > y1 {
+            self.fill_span(x1, x2, y2);
+        }
+        self.fill_column(x1, y1 + 1, y2 - 1);
+        if x2 > x1 {
+            self.fill_column(x2, y1 + 1, y2 - 1);
+        }
+
+        Ok(())
+    }
+
+    fn fill_rect(&mut self, rect: Rect) -> Result<(), String> {
+        let (_, min_y, _, max_y) = self.clip;
+        let (x1, y1) = (rect.x() as i64, rect.y() as i64);
+        let x2 = x1 + rect.width() as i64 - 1;
+        let y2 = y1 + rect.height() as i64;
+
+        for y in y1.max(min_y as i64)..y2.min(max_y as i64) {
+            self.fill_span(x1, x2, y);
+        }
+        Ok(())
+    }
+
+    fn draw_image(&mut self, x: i32, y: i32, image: &Texture) -> Result<(), String> {
+        for iy in 0..image.height() {
+            for ix in 0..image.width() {
+                let (r, g, b, a) = image.texel(ix, iy);
+                self.blend(x + ix as i32, y + iy as i32, r, g, b, a);
+            }
+        }
+        Ok(())
+    }
+
+    fn set_clip_rect(&mut self, rect: Option<Rect>) {
+        let (width, height) = (self.width as i32, self.height as i32);
+        self.clip = match rect {
+            Some(rect) => (
+                rect.left().max(0),
+                rect.top().max(0),
+                rect.right().min(width),
+                rect.bottom().min(height),
+            ),
+            None => (0, 0, width, height),
+        };
+    }
+
+    fn present(&mut self) {
+        self.frames += 1;
+    }
+}
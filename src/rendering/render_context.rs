@@ -3,8 +3,10 @@ extern crate sdl2;
 
 use self::sdl2::render::WindowCanvas;
 
-use std::cell::RefCell;
-// use std::rc::Rc;
+use std::cell::{RefCell, RefMut};
+use std::collections::HashMap;
+use std::f64::consts::PI;
+use std::rc::Rc;
 
 use self::font8x8::{UnicodeFonts, BASIC_FONTS};
 
@@ -14,23 +16,41 @@ use self::sdl2::{
     render::BlendMode,
 };
 
-use geometry::{aabb::AABBox, point::Point as RPoint};
+use geometry::{
+    aabb::AABBox,
+    path::{FlatPath, Path},
+    point::Point as RPoint,
+    rectangle::Rectangle,
+};
 use math::affine_transform::AffineTransform;
-use rendering::{color::Palette, fx_triangle::FXTriangle};
+use rendering::{
+    color::Palette,
+    fx_triangle::FXTriangle,
+    render_target::{RenderTarget, WindowTarget},
+    stroke::{RStrokeStyle, StrokeStyle, Stroker},
+    texture::{Texture, TextureRegion},
+};
 use world::GlobalData;
 
 const STATE_STACK_DEPTH: usize = 100;
 
+// Largest distance, in pixels, allowed between a curve and the straight
+// edges that approximate it.
+const CURVE_TOLERANCE: f64 = 0.25;
+const MAX_CURVE_SEGMENTS: usize = 1024;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
 pub enum RenderStyle {
     Filled,
     Outline,
     Both,
 }
 
-#[derive(Copy, Clone, Debug)]
+#[derive(Clone, Debug)]
 struct State {
     clear_color: Color,
     draw_color: Color,
+    stroke: RStrokeStyle,
     current: AffineTransform,
     filtered: AffineTransform,
 }
@@ -43,12 +63,18 @@ pub struct Context {
     clear_color: Color,
     draw_color: Color,
 
+    // Scales the alpha of every draw color, for example, to fade a whole scene.
+    opacity: f64,
+
+    // How render_line(s) and render_polyline draw
+    stroke: RStrokeStyle,
+
     // Device/Window dimensions
     width: i32,
     height: i32,
 
-    // SDL canvas target.
-    canvas: RefCell<WindowCanvas>,
+    // Device-space target: the window's SDL canvas or a software buffer.
+    canvas: RefCell<Box<dyn RenderTarget>>,
 
     current_aft: AffineTransform,
     post: AffineTransform,
@@ -58,11 +84,31 @@ pub struct Context {
 
     // Triangle rasterizers
     fx_rasterizer: RefCell<FXTriangle>,
+
+    // text() glyphs by (char, scale, fill)
+    glyphs: RefCell<HashMap<(char, usize, usize), Glyph>>,
+
+    // blend_pixel() pixels waiting to be drawn, by alpha
+    pending_pixels: RefCell<Vec<Vec<Point>>>,
+}
+
+// A font glyph as a white mask texture, copied tinted with the draw color,
+// and as rectangles for targets that can't copy textures.
+struct Glyph {
+    rects: Vec<Rect>,
+    // None if nothing is lit, for example, a space
+    image: Option<Texture>,
 }
 
 // SceneManager creates the Context.
 impl Context {
-    pub fn new(canvas: WindowCanvas) -> Self {
+    pub fn new(mut canvas: WindowCanvas) -> Self {
+        canvas.set_blend_mode(BlendMode::Blend);
+        Context::with_target(Box::new(WindowTarget::new(canvas)))
+    }
+
+    /// Renders into `target` instead of a window, for example, a PixelBuffer.
+    pub fn with_target(target: Box<dyn RenderTarget>) -> Self {
         Self {
             state: Vec::with_capacity(STATE_STACK_DEPTH),
             stack_top: 0,
@@ -70,29 +116,32 @@ impl Context {
             height: 0,
             clear_color: Color::RGB(32, 32, 32),
             draw_color: Color::RGB(0, 0, 0),
-            canvas: RefCell::new(canvas),
+            opacity: 1.0,
+            stroke: Rc::new(StrokeStyle::default()),
+            canvas: RefCell::new(target),
             current_aft: AffineTransform::new(),
             post: AffineTransform::new(),
             view_space: AffineTransform::new(),
             fx_rasterizer: RefCell::new(FXTriangle::new()),
+            glyphs: RefCell::new(HashMap::new()),
+            pending_pixels: RefCell::new(vec![Vec::new(); 256]),
         }
     }
 
     pub fn initialize(&mut self, data: &GlobalData) {
-        self.canvas.borrow_mut().set_blend_mode(BlendMode::Blend);
-
         self.width = data.window_width as i32;
         self.height = data.window_height as i32;
 
         let copy = State {
             clear_color: Color::RGB(0, 0, 0),
             draw_color: Color::RGB(0, 0, 0),
+            stroke: self.stroke.clone(),
             current: AffineTransform::new(),
             filtered: AffineTransform::new(),
         };
 
         for _ in 0..STATE_STACK_DEPTH {
-            self.state.push(copy);
+            self.state.push(copy.clone());
         }
 
         self.set_view_space(data);
@@ -121,6 +170,12 @@ impl Context {
         &self.view_space
     }
 
+    /// The render target, for example, to read back a PixelBuffer with
+    /// `target().as_any().downcast_ref::<PixelBuffer>()`.
+    pub fn target(&self) -> RefMut<'_, dyn RenderTarget> {
+        RefMut::map(self.canvas.borrow_mut(), |target| &mut **target)
+    }
+
     // ----------------------------------------------------------
     // Color
     // ----------------------------------------------------------
@@ -129,10 +184,33 @@ impl Context {
     }
 
     pub fn set_draw_color(&mut self, color: &Palette) {
-        self.draw_color = Color::RGBA(color.r, color.g, color.b, color.a);
+        self.flush_pixels();
+        let alpha = (color.a as f64 * self.opacity).round() as u8;
+        self.draw_color = Color::RGBA(color.r, color.g, color.b, alpha);
         self.canvas.borrow_mut().set_draw_color(self.draw_color);
     }
 
+    /// 0.0 (invisible) -> 1.0 (opaque). Applies to colors set after this call.
+    pub fn set_opacity(&mut self, opacity: f64) {
+        self.opacity = opacity.clamp(0.0, 1.0);
+    }
+
+    pub fn opacity(&self) -> f64 {
+        self.opacity
+    }
+
+    // ----------------------------------------------------------
+    // Stroke
+    // ----------------------------------------------------------
+    /// Applies to lines drawn after this call until the state is restored.
+    pub fn set_stroke(&mut self, stroke: RStrokeStyle) {
+        self.stroke = stroke;
+    }
+
+    pub fn stroke(&self) -> &StrokeStyle {
+        &self.stroke
+    }
+
     /// Clears the background canvas
     pub fn clear(&self) {
         // {
@@ -183,6 +261,7 @@ impl Context {
 
             top.clear_color = self.clear_color;
             top.draw_color = self.draw_color;
+            top.stroke = self.stroke.clone();
             top.current = self.current_aft;
         }
         // println!("Context save:");
@@ -200,15 +279,17 @@ impl Context {
 
     /// Restores rendering state
     pub fn restore(&mut self) {
+        self.flush_pixels();
         self.stack_top -= 1;
 
         // println!("Context restore:");
         // print_stack(&self.state, self.stack_top, 10);
 
-        let top = self.state[self.stack_top];
+        let top = &self.state[self.stack_top];
 
         self.clear_color = top.clear_color;
         self.draw_color = top.draw_color;
+        self.stroke = top.stroke.clone();
         self.current_aft = top.current;
 
         let mut can = self.canvas.borrow_mut();
@@ -230,6 +311,16 @@ impl Context {
         }
     }
 
+    /// The device rectangle grown by `margin` pixels on each side.
+    pub fn device_bounds(&self, margin: f64) -> Rectangle {
+        Rectangle::from_min_max(
+            -margin,
+            -margin,
+            self.width as f64 + margin,
+            self.height as f64 + margin,
+        )
+    }
+
     pub fn top_index(&self) -> usize {
         self.stack_top
     }
@@ -247,24 +338,27 @@ impl Context {
     // All render methods are affected by the current matrix context.
     // -------------------------------------------------------------
     pub fn post(&self) {
+        self.flush_pixels();
         self.canvas.borrow_mut().present();
     }
 
     pub fn render_points(&self, vertices: &RefCell<Vec<RPoint>>) {
-        let v = vertices.borrow();
-        let mut can = self.canvas.borrow_mut();
+        let points: Vec<Point> = vertices
+            .borrow()
+            .iter()
+            .map(|p| Point::new(p.x as i32, p.y as i32))
+            .collect();
 
-        for p in v.iter() {
-            match can.draw_point(Point::new(p.x as i32, p.y as i32)) {
-                Err(err) => {
-                    dbg!(err);
-                }
-                _ => (),
-            }
-        }
+        self.draw_points(&points);
     }
 
     pub fn render_line(&self, x1: f64, y1: f64, x2: f64, y2: f64) {
+        if !self.stroke.is_hairline() {
+            let points = [RPoint::from_xy(x1, y1), RPoint::from_xy(x2, y2)];
+            Stroker::stroke(self, &points, false, &self.stroke);
+            return;
+        }
+
         let mut can = self.canvas.borrow_mut();
         match can.draw_line(
             Point::new(x1 as i32, y1 as i32),
@@ -279,30 +373,126 @@ impl Context {
 
     pub fn render_lines(&self, vertices: &RefCell<Vec<RPoint>>) {
         let v = vertices.borrow();
-        let mut can = self.canvas.borrow_mut();
-        let mut capture = true;
-        let mut v1 = Point::new(0, 0);
 
-        for p in v.iter() {
-            if capture {
-                v1 = Point::new(p.x as i32, p.y as i32);
-                capture = false;
-            } else {
-                match can.draw_line(v1, Point::new(p.x as i32, p.y as i32)) {
-                    Err(err) => {
-                        dbg!(err);
-                    }
-                    _ => (),
+        if !self.stroke.is_hairline() {
+            for pair in v.chunks_exact(2) {
+                Stroker::stroke(self, pair, false, &self.stroke);
+            }
+            return;
+        }
+
+        // Each pair is a line. Lines that carry on from the one before are
+        // sent together.
+        let mut run: Vec<Point> = Vec::new();
+        for pair in v.chunks_exact(2) {
+            let (start, end) = (
+                Point::new(pair[0].x as i32, pair[0].y as i32),
+                Point::new(pair[1].x as i32, pair[1].y as i32),
+            );
+            if run.last() != Some(&start) {
+                self.draw_lines(&run);
+                run.clear();
+                run.push(start);
+            }
+            run.push(end);
+        }
+        self.draw_lines(&run);
+    }
+
+    /// Lines through each vertex in turn, with joins at the corners.
+    /// `closed` adds a line from the last vertex back to the first.
+    pub fn render_polyline(&self, vertices: &RefCell<Vec<RPoint>>, closed: bool) {
+        Stroker::stroke(self, &vertices.borrow(), closed, &self.stroke);
+    }
+
+    /// Draws a polygon, with any holes, from its transformed vertices:
+    /// the outline's followed by each hole's. `contours` is where each
+    /// contour ends in `vertices` and `triangles` comes from
+    /// Polygon::triangulate(), which is worth caching as it doesn't change
+    /// with the transform.
+    pub fn render_polygon(
+        &self,
+        vertices: &RefCell<Vec<RPoint>>,
+        contours: &[usize],
+        triangles: &[[usize; 3]],
+        style: RenderStyle,
+    ) {
+        let v = vertices.borrow();
+
+        if style != RenderStyle::Outline {
+            self.fill_mesh(&v, triangles);
+        }
+
+        if style != RenderStyle::Filled {
+            let mut start = 0;
+            for &end in contours.iter() {
+                Stroker::stroke(self, &v[start..end], true, &self.stroke);
+                start = end;
+            }
+        }
+    }
+
+    /// Flattens `path` to the current transform, transforms it and draws
+    /// it. Paths drawn every frame are better kept in a PathNode, which
+    /// only flattens and triangulates when the path or the scale changes.
+    pub fn render_path(&self, path: &Path, style: RenderStyle) {
+        let flat = path.flatten(self.curve_tolerance());
+
+        let triangles = if style == RenderStyle::Outline {
+            Vec::new()
+        } else {
+            match flat.triangulate() {
+                Ok(triangles) => triangles,
+                Err(err) => {
+                    dbg!(err);
+                    Vec::new()
                 }
-                capture = true;
+            }
+        };
+
+        let bucket = RefCell::new(vec![RPoint::new(); flat.points.len()]);
+        self.transform(&flat.points, &bucket);
+        self.render_flat_path(&bucket, &flat, &triangles, style);
+    }
+
+    /// Draws a flattened path from its transformed points. `triangles`
+    /// comes from FlatPath::triangulate().
+    pub fn render_flat_path(
+        &self,
+        vertices: &RefCell<Vec<RPoint>>,
+        path: &FlatPath,
+        triangles: &[[usize; 3]],
+        style: RenderStyle,
+    ) {
+        let v = vertices.borrow();
+
+        if style != RenderStyle::Outline {
+            self.fill_mesh(&v, triangles);
+        }
+
+        if style != RenderStyle::Filled {
+            let mut start = 0;
+            for &(end, closed) in path.subpaths.iter() {
+                Stroker::stroke(self, &v[start..end], closed, &self.stroke);
+                start = end;
             }
         }
     }
 
-    // pub fn render_polygon(&self, vertices: &Vec<RPoint>) {
-    //     let mut raster = self.tri_rasterizer.borrow_mut();
+    /// The node-space distance that stays within CURVE_TOLERANCE pixels
+    /// under the current transform. Curves flattened to it look smooth.
+    pub fn curve_tolerance(&self) -> f64 {
+        CURVE_TOLERANCE / self.current_aft.max_scale().max(1.0e-9)
+    }
 
-    // }
+    fn fill_mesh(&self, v: &[RPoint], triangles: &[[usize; 3]]) {
+        let mut raster = self.fx_rasterizer.borrow_mut();
+        for t in triangles.iter() {
+            let (p0, p1, p2) = (&v[t[0]], &v[t[1]], &v[t[2]]);
+            raster.set(p0.x, p0.y, p1.x, p1.y, p2.x, p2.y);
+            raster.draw(self);
+        }
+    }
 
     pub fn render_triangle(&self, vertices: &RefCell<Vec<RPoint>>) {
         // Update visual with transformed vertices.
@@ -339,6 +529,291 @@ impl Context {
         raster.draw(&self);
     }
 
+    /// Draws the `source` texels of `texture` stretched over the node-space
+    /// rectangle `dest`. The current transform applies in full, so the
+    /// image can be rotated, scaled non-uniformly and sheared. Texels are
+    /// sampled nearest-neighbor and their alpha is scaled by the opacity.
+    ///
+    /// Rotated and scaled images are copied from a texture the target keeps
+    /// on the GPU. Sheared images, and targets without textures, are drawn
+    /// texel by texel instead, which is much slower.
+    pub fn render_texture(&self, texture: &Texture, source: &TextureRegion, dest: &Rectangle) {
+        let source = source.clamped(texture);
+        if source.width == 0 || source.height == 0 || dest.width == 0.0 || dest.height == 0.0 {
+            return;
+        }
+
+        let aft = &self.current_aft;
+        if (aft.a * aft.d - aft.b * aft.c).abs() < f64::EPSILON {
+            // Collapsed to a line or point
+            return;
+        }
+
+        if self.copy_texture(texture, &source, dest) {
+            return;
+        }
+
+        // Device-space bounds of the destination, clipped to the device.
+        let corners = [
+            aft.mul_components(dest.min.x, dest.min.y),
+            aft.mul_components(dest.max.x, dest.min.y),
+            aft.mul_components(dest.max.x, dest.max.y),
+            aft.mul_components(dest.min.x, dest.max.y),
+        ];
+        let (mut min_x, mut min_y) = (f64::MAX, f64::MAX);
+        let (mut max_x, mut max_y) = (f64::MIN, f64::MIN);
+        for (x, y) in corners.iter() {
+            min_x = min_x.min(*x);
+            min_y = min_y.min(*y);
+            max_x = max_x.max(*x);
+            max_y = max_y.max(*y);
+        }
+
+        let left = (min_x.floor() as i32).max(0);
+        let top = (min_y.floor() as i32).max(0);
+        let right = (max_x.ceil() as i32).min(self.width);
+        let bottom = (max_y.ceil() as i32).min(self.height);
+        if left >= right || top >= bottom {
+            return;
+        }
+
+        let (width, height) = ((right - left) as u32, (bottom - top) as u32);
+
+        // Map each device pixel center back into the destination and
+        // sample the texel there.
+        let inverse = aft.inverse();
+        let u_scale = source.width as f64 / dest.width;
+        let v_scale = source.height as f64 / dest.height;
+        let alpha = self.opacity;
+
+        let mut pixels = vec![0u8; width as usize * height as usize * 4];
+        let mut i = 0;
+        for py in top..bottom {
+            for px in left..right {
+                let (lx, ly) = inverse.mul_components(px as f64 + 0.5, py as f64 + 0.5);
+                let u = (lx - dest.min.x) * u_scale;
+                let v = (ly - dest.min.y) * v_scale;
+
+                if u >= 0.0 && v >= 0.0 && u < source.width as f64 && v < source.height as f64 {
+                    let (r, g, b, a) = texture.texel(source.x + u as u32, source.y + v as u32);
+                    pixels[i] = r;
+                    pixels[i + 1] = g;
+                    pixels[i + 2] = b;
+                    pixels[i + 3] = (a as f64 * alpha).round() as u8;
+                }
+
+                i += 4;
+            }
+        }
+
+        let image = match Texture::from_rgba(width, height, pixels) {
+            Ok(image) => image,
+            Err(err) => {
+                dbg!(err);
+                return;
+            }
+        };
+
+        if let Err(err) = self.canvas.borrow_mut().draw_image(left, top, &image) {
+            dbg!(err);
+        }
+    }
+
+    // Copies the texture with the target's copy_image() if the current
+    // transform is a rotation and scale without shear. Returns false if it
+    // is sheared or the target can't copy.
+    fn copy_texture(&self, texture: &Texture, source: &TextureRegion, dest: &Rectangle) -> bool {
+        let aft = &self.current_aft;
+
+        // The transform is a rotation of scale (sx, sy) when its columns are
+        // at right angles. A negative sy mirrors the image.
+        let sx = aft.a.hypot(aft.b);
+        let sy = (aft.a * aft.d - aft.b * aft.c) / sx;
+        if (aft.a * aft.c + aft.b * aft.d).abs() > 1.0e-9 * sx * sy.abs() {
+            return false;
+        }
+
+        let (width, height) = (dest.width * sx, dest.height * sy);
+        if width.abs() < 0.5 || height.abs() < 0.5 {
+            return true;
+        }
+
+        let (cx, cy) = aft.mul_components(
+            (dest.min.x + dest.max.x) / 2.0,
+            (dest.min.y + dest.max.y) / 2.0,
+        );
+        let device = Rect::new(
+            (cx - width.abs() / 2.0).round() as i32,
+            (cy - height.abs() / 2.0).round() as i32,
+            width.abs().round() as u32,
+            height.abs().round() as u32,
+        );
+        let region = Rect::new(
+            source.x as i32,
+            source.y as i32,
+            source.width,
+            source.height,
+        );
+        let angle = aft.b.atan2(aft.a).to_degrees();
+        let alpha = (self.opacity * 255.0).round() as u8;
+
+        match self.canvas.borrow_mut().copy_image(
+            texture,
+            region,
+            device,
+            angle,
+            (width < 0.0, height < 0.0),
+            Color::RGBA(255, 255, 255, alpha),
+        ) {
+            Ok(copied) => copied,
+            Err(err) => {
+                dbg!(err);
+                true
+            }
+        }
+    }
+
+    // -------------------------------------------------------------
+    // Curves
+    // Centers and radii are in node-space, angles are in radians
+    // measured from the +x axis towards +y. Curves are split into enough
+    // edges to look smooth at their size on screen. Outlines use the
+    // stroke style.
+    // -------------------------------------------------------------
+    pub fn render_circle(&self, cx: f64, cy: f64, radius: f64, style: RenderStyle) {
+        self.render_ellipse(cx, cy, radius, radius, style);
+    }
+
+    pub fn render_ellipse(&self, cx: f64, cy: f64, rx: f64, ry: f64, style: RenderStyle) {
+        let points = self.curve_points((cx, cy), (rx, ry), 0.0, 2.0 * PI, false);
+        if points.len() < 3 {
+            return;
+        }
+
+        let center = self.current_aft.mul_components(cx, cy);
+        self.render_curve(&points, Some(center), true, style);
+    }
+
+    /// The part of the circle from `start` through `sweep`, which may be
+    /// negative. Filled arcs are closed by a straight chord. Scale the
+    /// node non-uniformly for elliptical arcs.
+    pub fn render_arc(
+        &self,
+        cx: f64,
+        cy: f64,
+        radius: f64,
+        start: f64,
+        sweep: f64,
+        style: RenderStyle,
+    ) {
+        let points = self.curve_points((cx, cy), (radius, radius), start, sweep, true);
+        if points.len() < 2 {
+            return;
+        }
+
+        match style {
+            RenderStyle::Outline => self.render_curve(&points, None, false, style),
+            RenderStyle::Filled => self.render_curve(&points, None, true, style),
+            RenderStyle::Both => {
+                self.render_curve(&points, None, true, RenderStyle::Filled);
+                self.render_curve(&points, None, false, RenderStyle::Outline);
+            }
+        }
+    }
+
+    /// A wedge of the circle from `start` through `sweep`, outlined along
+    /// its arc and both radii.
+    pub fn render_pie(
+        &self,
+        cx: f64,
+        cy: f64,
+        radius: f64,
+        start: f64,
+        sweep: f64,
+        style: RenderStyle,
+    ) {
+        let mut points = self.curve_points((cx, cy), (radius, radius), start, sweep, true);
+        if points.len() < 2 {
+            return;
+        }
+
+        let (x, y) = self.current_aft.mul_components(cx, cy);
+        points.push(RPoint::from_xy(x, y));
+        self.render_curve(&points, Some((x, y)), true, style);
+    }
+
+    // Device-space points along the ellipse from `start` through `sweep`.
+    // `ends` includes the point at start + sweep, which closed curves
+    // leave out.
+    fn curve_points(
+        &self,
+        (cx, cy): (f64, f64),
+        (rx, ry): (f64, f64),
+        start: f64,
+        sweep: f64,
+        ends: bool,
+    ) -> Vec<RPoint> {
+        let sweep = sweep.clamp(-2.0 * PI, 2.0 * PI);
+        if sweep == 0.0 {
+            return Vec::new();
+        }
+
+        // The largest radius on screen
+        let aft = &self.current_aft;
+        let radius = aft.max_scale() * rx.abs().max(ry.abs());
+
+        // Each edge spans the angle at which its chord stays within the
+        // tolerance of the curve.
+        let step = if radius > CURVE_TOLERANCE {
+            2.0 * (1.0 - CURVE_TOLERANCE / radius).acos()
+        } else {
+            PI / 2.0
+        };
+        let segments = ((sweep.abs() / step).ceil() as usize).clamp(4, MAX_CURVE_SEGMENTS);
+
+        let count = if ends { segments + 1 } else { segments };
+        (0..count)
+            .map(|i| {
+                let angle = start + sweep * i as f64 / segments as f64;
+                let (x, y) = aft.mul_components(cx + rx * angle.cos(), cy + ry * angle.sin());
+                RPoint::from_xy(x, y)
+            })
+            .collect()
+    }
+
+    // Fills the convex outline as a fan around `center`, or its first
+    // point, and/or strokes it.
+    fn render_curve(
+        &self,
+        outline: &[RPoint],
+        center: Option<(f64, f64)>,
+        closed: bool,
+        style: RenderStyle,
+    ) {
+        let fill = |points: &[RPoint]| {
+            let (hub, rim) = match center {
+                Some(center) => (center, points),
+                None => ((points[0].x, points[0].y), &points[1..]),
+            };
+            for pair in rim.windows(2) {
+                self.fill_triangle(hub, (pair[0].x, pair[0].y), (pair[1].x, pair[1].y));
+            }
+            if center.is_some() && closed && rim.len() > 2 {
+                let (first, last) = (&rim[0], &rim[rim.len() - 1]);
+                self.fill_triangle(hub, (last.x, last.y), (first.x, first.y));
+            }
+        };
+
+        match style {
+            RenderStyle::Filled => fill(outline),
+            RenderStyle::Outline => Stroker::stroke(self, outline, closed, &self.stroke),
+            RenderStyle::Both => {
+                fill(outline);
+                Stroker::stroke(self, outline, closed, &self.stroke);
+            }
+        }
+    }
+
     // The vertices are expected to be the corners of an axis aligned bounding box.
     pub fn render_aabb_rectangle(&self, corners: &AABBox, filled: RenderStyle) {
         // Update visual with transformed vertices.
@@ -380,6 +855,154 @@ impl Context {
         }
     }
 
+    /// x,y should already be transformed. Draws with the draw color's
+    /// alpha scaled by `coverage` (0.0 -> 1.0). The pixel is queued with
+    /// others of the same alpha until flush_pixels(), which the draw color
+    /// changing, restore() and post() also do.
+    pub fn blend_pixel(&self, x: i32, y: i32, coverage: f64) {
+        let alpha = (self.draw_color.a as f64 * coverage.clamp(0.0, 1.0)).round() as u8;
+        if alpha == 0 {
+            return;
+        }
+
+        self.pending_pixels.borrow_mut()[alpha as usize].push(Point::new(x, y));
+    }
+
+    /// Draws the pixels queued by blend_pixel(), one batch per alpha.
+    pub fn flush_pixels(&self) {
+        let mut pending = self.pending_pixels.borrow_mut();
+        let color = self.draw_color;
+        let mut can = self.canvas.borrow_mut();
+
+        let mut flushed = false;
+        for (alpha, points) in pending.iter_mut().enumerate() {
+            if points.is_empty() {
+                continue;
+            }
+
+            can.set_draw_color(Color::RGBA(color.r, color.g, color.b, alpha as u8));
+            if let Err(err) = can.draw_points(points) {
+                dbg!(err);
+            }
+            points.clear();
+            flushed = true;
+        }
+
+        if flushed {
+            can.set_draw_color(color);
+        }
+    }
+
+    pub fn draw_line(&self, x1: i32, y1: i32, x2: i32, y2: i32) {
+        let mut can = self.canvas.borrow_mut();
+        if let Err(err) = can.draw_line(Point::new(x1, y1), Point::new(x2, y2)) {
+            dbg!(err);
+        }
+    }
+
+    /// An anti-aliased one pixel wide line with sub-pixel end points
+    /// (Xiaolin Wu's algorithm). Only the part near the device is drawn.
+    pub fn draw_aa_line(&self, x1: f64, y1: f64, x2: f64, y2: f64) {
+        // Cut it a couple of pixels outside the device so the shaded ends
+        // of a cut line aren't seen.
+        let (t0, t1) = match self.device_bounds(2.0).clip_segment((x1, y1), (x2, y2)) {
+            Some(range) => range,
+            None => return,
+        };
+        let (dx, dy) = (x2 - x1, y2 - y1);
+        let (x1, y1, x2, y2) = (x1 + dx * t0, y1 + dy * t0, x1 + dx * t1, y1 + dy * t1);
+
+        let steep = (y2 - y1).abs() > (x2 - x1).abs();
+
+        // Work along x; steep lines swap x and y.
+        let (mut x1, mut y1, mut x2, mut y2) = if steep {
+            (y1, x1, y2, x2)
+        } else {
+            (x1, y1, x2, y2)
+        };
+        if x1 > x2 {
+            std::mem::swap(&mut x1, &mut x2);
+            std::mem::swap(&mut y1, &mut y2);
+        }
+
+        let plot = |x: i32, y: i32, coverage: f64| {
+            if steep {
+                self.blend_pixel(y, x, coverage);
+            } else {
+                self.blend_pixel(x, y, coverage);
+            }
+        };
+
+        // Fractional part, also for negative values
+        let fpart = |v: f64| v - v.floor();
+
+        let dx = x2 - x1;
+        let gradient = if dx == 0.0 { 1.0 } else { (y2 - y1) / dx };
+
+        // Pixel centers are at .5
+        let (x1, y1, x2, y2) = (x1 - 0.5, y1 - 0.5, x2 - 0.5, y2 - 0.5);
+
+        // First end point
+        let x_end = (x1 + 0.5).floor();
+        let y_end = y1 + gradient * (x_end - x1);
+        let x_gap = 1.0 - fpart(x1 + 0.5);
+        let x_start = x_end as i32;
+        plot(x_start, y_end.floor() as i32, (1.0 - fpart(y_end)) * x_gap);
+        plot(x_start, y_end.floor() as i32 + 1, fpart(y_end) * x_gap);
+        let mut y = y_end + gradient;
+
+        // Second end point
+        let x_end = (x2 + 0.5).floor();
+        let y_end = y2 + gradient * (x_end - x2);
+        let x_gap = fpart(x2 + 0.5);
+        let x_stop = x_end as i32;
+        plot(x_stop, y_end.floor() as i32, (1.0 - fpart(y_end)) * x_gap);
+        plot(x_stop, y_end.floor() as i32 + 1, fpart(y_end) * x_gap);
+
+        for x in (x_start + 1)..x_stop {
+            plot(x, y.floor() as i32, 1.0 - fpart(y));
+            plot(x, y.floor() as i32 + 1, fpart(y));
+            y += gradient;
+        }
+    }
+
+    /// Batched set_pixel().
+    pub fn draw_points(&self, points: &[Point]) {
+        if points.is_empty() {
+            return;
+        }
+        if let Err(err) = self.canvas.borrow_mut().draw_points(points) {
+            dbg!(err);
+        }
+    }
+
+    /// Lines joining the points in turn.
+    pub fn draw_lines(&self, points: &[Point]) {
+        if points.len() < 2 {
+            return;
+        }
+        if let Err(err) = self.canvas.borrow_mut().draw_lines(points) {
+            dbg!(err);
+        }
+    }
+
+    /// Batched fill_rectangle().
+    pub fn fill_rects(&self, rects: &[Rect]) {
+        if rects.is_empty() {
+            return;
+        }
+        if let Err(err) = self.canvas.borrow_mut().fill_rects(rects) {
+            dbg!(err);
+        }
+    }
+
+    /// Fills the device-space triangle with the draw color.
+    pub fn fill_triangle(&self, p0: (f64, f64), p1: (f64, f64), p2: (f64, f64)) {
+        let mut raster = self.fx_rasterizer.borrow_mut();
+        raster.set(p0.0, p0.1, p1.0, p1.1, p2.0, p2.1);
+        raster.draw(self);
+    }
+
     pub fn draw_rectangle(&self, xmin: i32, ymin: i32, xmax: i32, ymax: i32) {
         let mut can = self.canvas.borrow_mut();
         match can.draw_rect(Rect::new(
@@ -410,6 +1033,19 @@ impl Context {
         }
     }
 
+    /// Limits drawing to a device-space rectangle until unclip() is called.
+    pub fn clip(&self, xmin: i32, ymin: i32, xmax: i32, ymax: i32) {
+        let width = (xmax - xmin).max(0) as u32;
+        let height = (ymax - ymin).max(0) as u32;
+        self.canvas
+            .borrow_mut()
+            .set_clip_rect(Some(Rect::new(xmin, ymin, width, height)));
+    }
+
+    pub fn unclip(&self) {
+        self.canvas.borrow_mut().set_clip_rect(None);
+    }
+
     pub fn draw_point(&mut self, _x: f64, _y: f64) {
         // transform coordinates and render.
     }
@@ -449,54 +1085,140 @@ impl Context {
         }
     }
 
+    /// Draws `text` in the 8x8 font with its top-left corner at device
+    /// x, y. Each font pixel is a `scale` sized square, less `fill` pixels
+    /// to leave a gap between them. Each glyph is a texture copied in the
+    /// draw color; targets that can't copy textures get the whole string
+    /// as one batch of rectangles.
     pub fn text(&mut self, x: i32, y: i32, text: &str, scale: usize, fill: usize) {
-        // if x as usize >= self.world_properties.window_width
-        //     || y as usize >= self.world_properties.window_height
-        // {
-        //     return;
-        // }
+        self.flush_pixels();
 
-        let mut cx = x;
+        let mut rects = Vec::new();
+        let mut glyphs = self.glyphs.borrow_mut();
+        let mut can = self.canvas.borrow_mut();
+        let size = 8 * scale as u32;
 
+        let mut cx = x;
         for c in text.chars() {
-            let mut gy = y; // move y back to the "top" for each char
-            if let Some(glyph) = BASIC_FONTS.get(c) {
-                for g in &glyph {
-                    let mut gx = cx; // set to current column
-                    for bit in 0..8 {
-                        // scan each pixel in the row
-                        match *g & 1 << bit {
-                            0 => (),
-                            _ => {
-                                if scale == 1 {
-                                    self.set_pixel(gx, gy);
-                                } else {
-                                    let mut fillet = fill;
-                                    if fill > scale {
-                                        fillet = 0;
-                                    }
-                                    for xl in 0..((scale - fillet) as i32) {
-                                        for yl in 0..((scale - fillet) as i32) {
-                                            self.set_pixel(gx + xl, gy + yl);
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                        gx += scale as i32;
+            let glyph = glyphs
+                .entry((c, scale, fill))
+                .or_insert_with(|| Glyph::new(c, scale, fill));
+
+            let copied = match glyph.image {
+                Some(ref image) => match can.copy_image(
+                    image,
+                    Rect::new(0, 0, size, size),
+                    Rect::new(cx, y, size, size),
+                    0.0,
+                    (false, false),
+                    self.draw_color,
+                ) {
+                    Ok(copied) => copied,
+                    Err(err) => {
+                        dbg!(err);
+                        true
                     }
-                    gy += scale as i32; // move to next pixel-row in char
+                },
+                None => false,
+            };
+
+            if !copied {
+                for r in glyph.rects.iter() {
+                    rects.push(Rect::new(cx + r.x(), y + r.y(), r.width(), r.height()));
                 }
             }
             cx += 8 * scale as i32; // move to next column/char/glyph
         }
+
+        if !rects.is_empty() {
+            if let Err(err) = can.fill_rects(&rects) {
+                dbg!(err);
+            }
+        }
     }
 }
 
+impl Glyph {
+    fn new(c: char, scale: usize, fill: usize) -> Self {
+        let rects = glyph_rects(c, scale, fill);
+        if rects.is_empty() {
+            return Self { rects, image: None };
+        }
+
+        let size = 8 * scale;
+        let mut pixels = vec![0u8; size * size * 4];
+        for r in rects.iter() {
+            for y in r.y() as usize..r.y() as usize + r.height() as usize {
+                let start = (y * size + r.x() as usize) * 4;
+                let end = start + r.width() as usize * 4;
+                for texel in pixels[start..end].iter_mut() {
+                    *texel = 255;
+                }
+            }
+        }
+
+        let image = match Texture::from_rgba(size as u32, size as u32, pixels) {
+            Ok(image) => Some(image),
+            Err(err) => {
+                dbg!(err);
+                None
+            }
+        };
+
+        Self { rects, image }
+    }
+}
+
+// The rectangles that draw glyph `c` at the origin, one per lit font pixel
+// or, without gaps, one per run of them along a row.
+fn glyph_rects(c: char, scale: usize, fill: usize) -> Vec<Rect> {
+    let mut rects = Vec::new();
+    let glyph = match BASIC_FONTS.get(c) {
+        Some(glyph) => glyph,
+        None => return rects,
+    };
+
+    let size = if scale == 1 {
+        1
+    } else if fill > scale {
+        scale
+    } else {
+        scale - fill
+    };
+    if size == 0 {
+        return rects;
+    }
+    let (scale, size) = (scale as i32, size as u32);
+
+    for (row, bits) in glyph.iter().enumerate() {
+        let y = row as i32 * scale;
+        let mut bit = 0;
+        while bit < 8 {
+            if bits & 1 << bit == 0 {
+                bit += 1;
+                continue;
+            }
+
+            let mut run = 1;
+            if size as i32 == scale {
+                while bit + run < 8 && bits & 1 << (bit + run) != 0 {
+                    run += 1;
+                }
+            }
+
+            let width = (run - 1) as u32 * scale as u32 + size;
+            rects.push(Rect::new(bit * scale, y, width, size));
+            bit += run;
+        }
+    }
+
+    rects
+}
+
 fn print_stack(state: &Vec<State>, stack_top: usize, to_depth: usize) {
     println!("Stack --------------------------");
     for i in 0..to_depth {
-        let item = state[i];
+        let item = &state[i];
         if i == stack_top {
             println!("({:?}) <<#### ({})", item.current, i);
         } else {
@@ -0,0 +1,260 @@
+extern crate sdl2;
+
+use std::any::Any;
+use std::collections::HashMap;
+
+use self::sdl2::{
+    pixels::{Color, PixelFormatEnum},
+    rect::{Point, Rect},
+    render::{BlendMode, Texture as SdlTexture, TextureCreator, WindowCanvas},
+    video::WindowContext,
+};
+
+use rendering::texture::Texture;
+
+// The device-space surface the Context draws on.
+//
+// The window's SDL canvas, wrapped in a WindowTarget, is the usual target.
+// A PixelBuffer renders into memory instead, for example, to render without
+// a window or to capture a frame. Everything is drawn blended with the
+// current draw color.
+
+// Frames an SDL texture is kept after it was last drawn
+const TEXTURE_IDLE_FRAMES: u64 = 120;
+
+pub trait RenderTarget {
+    fn as_any(&self) -> &dyn Any;
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+
+    fn set_draw_color(&mut self, color: Color);
+
+    fn draw_point(&mut self, point: Point) -> Result<(), String>;
+    fn draw_line(&mut self, start: Point, end: Point) -> Result<(), String>;
+    fn draw_rect(&mut self, rect: Rect) -> Result<(), String>;
+    fn fill_rect(&mut self, rect: Rect) -> Result<(), String>;
+
+    // Batched forms of the above, one call for many. Targets that can send
+    // a batch at once should override them.
+    fn draw_points(&mut self, points: &[Point]) -> Result<(), String> {
+        for point in points.iter() {
+            self.draw_point(*point)?;
+        }
+        Ok(())
+    }
+
+    /// Lines joining the points in turn.
+    fn draw_lines(&mut self, points: &[Point]) -> Result<(), String> {
+        for pair in points.windows(2) {
+            self.draw_line(pair[0], pair[1])?;
+        }
+        Ok(())
+    }
+
+    fn fill_rects(&mut self, rects: &[Rect]) -> Result<(), String> {
+        for rect in rects.iter() {
+            self.fill_rect(*rect)?;
+        }
+        Ok(())
+    }
+
+    /// Blends `image` with its top-left corner at device x, y.
+    fn draw_image(&mut self, x: i32, y: i32, image: &Texture) -> Result<(), String>;
+
+    /// Blends the `source` texels of `image` scaled to `dest`, mirrored
+    /// across its (horizontal, vertical) center lines as `flip` asks, then
+    /// rotated `angle` degrees clockwise about its center. Each texel is
+    /// tinted by `color`: its channels are scaled by the color's / 255, so
+    /// white leaves it as it is. Returns false, having drawn nothing, if
+    /// the target can't, and the caller draws the image itself.
+    fn copy_image(
+        &mut self,
+        _image: &Texture,
+        _source: Rect,
+        _dest: Rect,
+        _angle: f64,
+        _flip: (bool, bool),
+        _color: Color,
+    ) -> Result<bool, String> {
+        Ok(false)
+    }
+
+    /// None removes the clip rectangle.
+    fn set_clip_rect(&mut self, rect: Option<Rect>);
+
+    /// Shows what was drawn since the last present.
+    fn present(&mut self);
+}
+
+/// The window's canvas. Each Texture drawn with copy_image() is uploaded
+/// to an SDL texture once and kept while it goes on being drawn.
+pub struct WindowTarget {
+    canvas: WindowCanvas,
+    creator: TextureCreator<WindowContext>,
+
+    // SDL textures by Texture id, and the frame each was last drawn in
+    textures: HashMap<usize, (SdlTexture, u64)>,
+    // Number of times present() was called
+    frame: u64,
+}
+
+impl WindowTarget {
+    pub fn new(canvas: WindowCanvas) -> Self {
+        let creator = canvas.texture_creator();
+        Self {
+            canvas,
+            creator,
+            textures: HashMap::new(),
+            frame: 0,
+        }
+    }
+
+    pub fn canvas(&self) -> &WindowCanvas {
+        &self.canvas
+    }
+
+    pub fn canvas_mut(&mut self) -> &mut WindowCanvas {
+        &mut self.canvas
+    }
+
+    /// How many textures are on the GPU.
+    pub fn texture_count(&self) -> usize {
+        self.textures.len()
+    }
+
+    fn upload(&self, image: &Texture) -> Result<SdlTexture, String> {
+        let mut texture = self
+            .creator
+            .create_texture_static(PixelFormatEnum::RGBA32, image.width(), image.height())
+            .map_err(|err| err.to_string())?;
+        texture.set_blend_mode(BlendMode::Blend);
+
+        if let Err(err) = texture.update(None, image.pixels(), image.width() as usize * 4) {
+            // The canvas is alive, so destroying its texture is sound.
+            unsafe { texture.destroy() };
+            return Err(err.to_string());
+        }
+
+        Ok(texture)
+    }
+
+    // Frees the textures that haven't been drawn for a while, for example,
+    // because their Texture was dropped.
+    fn evict_idle_textures(&mut self) {
+        let frame = self.frame;
+        let idle: Vec<usize> = self
+            .textures
+            .iter()
+            .filter(|(_, (_, used))| frame - used > TEXTURE_IDLE_FRAMES)
+            .map(|(id, _)| *id)
+            .collect();
+
+        for id in idle.iter() {
+            if let Some((texture, _)) = self.textures.remove(id) {
+                unsafe { texture.destroy() };
+            }
+        }
+    }
+}
+
+impl RenderTarget for WindowTarget {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn set_draw_color(&mut self, color: Color) {
+        self.canvas.set_draw_color(color);
+    }
+
+    fn draw_point(&mut self, point: Point) -> Result<(), String> {
+        self.canvas.draw_point(point)
+    }
+
+    fn draw_line(&mut self, start: Point, end: Point) -> Result<(), String> {
+        self.canvas.draw_line(start, end)
+    }
+
+    fn draw_rect(&mut self, rect: Rect) -> Result<(), String> {
+        self.canvas.draw_rect(rect)
+    }
+
+    fn fill_rect(&mut self, rect: Rect) -> Result<(), String> {
+        self.canvas.fill_rect(rect)
+    }
+
+    fn draw_points(&mut self, points: &[Point]) -> Result<(), String> {
+        self.canvas.draw_points(points)
+    }
+
+    fn draw_lines(&mut self, points: &[Point]) -> Result<(), String> {
+        self.canvas.draw_lines(points)
+    }
+
+    fn fill_rects(&mut self, rects: &[Rect]) -> Result<(), String> {
+        self.canvas.fill_rects(rects)
+    }
+
+    fn draw_image(&mut self, x: i32, y: i32, image: &Texture) -> Result<(), String> {
+        if image.width() == 0 || image.height() == 0 {
+            return Ok(());
+        }
+
+        // The image is made for this one draw, so it isn't kept.
+        let texture = self.upload(image)?;
+        let result = self.canvas.copy(
+            &texture,
+            None,
+            Rect::new(x, y, image.width(), image.height()),
+        );
+        unsafe { texture.destroy() };
+
+        result
+    }
+
+    fn copy_image(
+        &mut self,
+        image: &Texture,
+        source: Rect,
+        dest: Rect,
+        angle: f64,
+        flip: (bool, bool),
+        color: Color,
+    ) -> Result<bool, String> {
+        if image.width() == 0 || image.height() == 0 {
+            return Ok(true);
+        }
+
+        if !self.textures.contains_key(&image.id()) {
+            let texture = self.upload(image)?;
+            self.textures.insert(image.id(), (texture, self.frame));
+        }
+
+        let frame = self.frame;
+        let (texture, used) = match self.textures.get_mut(&image.id()) {
+            Some(entry) => entry,
+            None => return Ok(false),
+        };
+        *used = frame;
+
+        texture.set_color_mod(color.r, color.g, color.b);
+        texture.set_alpha_mod(color.a);
+        self.canvas
+            .copy_ex(texture, source, dest, angle, None, flip.0, flip.1)?;
+
+        Ok(true)
+    }
+
+    fn set_clip_rect(&mut self, rect: Option<Rect>) {
+        self.canvas.set_clip_rect(rect);
+    }
+
+    fn present(&mut self) {
+        self.canvas.present();
+
+        self.frame += 1;
+        self.evict_idle_textures();
+    }
+}
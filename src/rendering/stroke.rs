@@ -0,0 +1,495 @@
+extern crate sdl2;
+
+use std::f64::consts::PI;
+use std::rc::Rc;
+
+use self::sdl2::rect::Point as DevicePoint;
+
+use geometry::{point::Point, rectangle::Rectangle};
+use rendering::render_context::Context;
+
+// How lines are drawn: width, anti-aliasing, caps, joins and dashes.
+//
+// A stroke style is set on the Context, or on a node with set_stroke(), in
+// which case it also applies to the node's children. Lengths are in device
+// pixels because lines are stroked after they are transformed.
+//
+// The default style draws the one-pixel aliased lines the canvas draws.
+// Wider lines are filled as triangles, and with anti-aliasing on their
+// edges are traced with Wu lines.
+
+pub type RStrokeStyle = Rc<StrokeStyle>;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum LineCap {
+    // Ends at the end point
+    Butt,
+    // Extends half the width past the end point
+    Square,
+    // A half circle around the end point
+    Round,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum LineJoin {
+    // Extends the outer edges until they meet, within the miter limit
+    Miter,
+    // Cuts the corner off
+    Bevel,
+    Round,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct StrokeStyle {
+    pub width: f64,
+    pub anti_aliased: bool,
+    pub cap: LineCap,
+    pub join: LineJoin,
+    // Longest miter, as a multiple of the width, before a miter join is
+    // drawn as a bevel.
+    pub miter_limit: f64,
+    // Alternating dash and gap lengths, empty for solid lines. An odd
+    // count is repeated to make it even.
+    pub dash: Vec<f64>,
+    // How far into the pattern lines start
+    pub dash_offset: f64,
+}
+
+impl Default for StrokeStyle {
+    fn default() -> Self {
+        Self {
+            width: 1.0,
+            anti_aliased: false,
+            cap: LineCap::Butt,
+            join: LineJoin::Miter,
+            miter_limit: 4.0,
+            dash: Vec::new(),
+            dash_offset: 0.0,
+        }
+    }
+}
+
+impl StrokeStyle {
+    pub fn new(width: f64) -> Self {
+        Self {
+            width,
+            ..StrokeStyle::default()
+        }
+    }
+
+    /// True if lines can go straight to the canvas.
+    pub fn is_hairline(&self) -> bool {
+        self.width <= 1.0 && !self.anti_aliased && self.dash.is_empty()
+    }
+}
+
+pub struct Stroker;
+
+impl Stroker {
+    /// Strokes the device-space polyline through `points` with the
+    /// context's draw color.
+    pub fn stroke(context: &Context, points: &[Point], closed: bool, style: &StrokeStyle) {
+        let mut points: Vec<(f64, f64)> = points.iter().map(|p| (p.x, p.y)).collect();
+        points.dedup();
+        if closed && points.len() > 2 && points.first() == points.last() {
+            points.pop();
+        }
+        if points.is_empty() {
+            return;
+        }
+
+        // Segments are cut where they leave the device, far enough out
+        // that the caps and edges at a cut aren't seen.
+        let bounds = context.device_bounds(style.width.max(1.0) + 2.0);
+
+        if style.dash.iter().sum::<f64>() > 0.0 {
+            if closed {
+                points.push(points[0]);
+            }
+            for mut dash in Stroker::dashes(&points, style, &bounds) {
+                dash.dedup();
+                Stroker::stroke_polyline(context, &dash, false, style, &bounds);
+            }
+        } else {
+            let closed = closed && points.len() > 2;
+            Stroker::stroke_polyline(context, &points, closed, style, &bounds);
+        }
+
+        // The anti-aliased edges, in one batch per alpha
+        context.flush_pixels();
+    }
+
+    fn stroke_polyline(
+        context: &Context,
+        points: &[(f64, f64)],
+        closed: bool,
+        style: &StrokeStyle,
+        bounds: &Rectangle,
+    ) {
+        if style.width <= 1.0 {
+            Stroker::hairlines(context, points, closed, style.anti_aliased);
+            return;
+        }
+
+        let hw = style.width / 2.0;
+
+        if points.len() == 1 {
+            // A dot
+            let (x, y) = points[0];
+            match style.cap {
+                LineCap::Butt => (),
+                LineCap::Square => {
+                    let square = [
+                        (x - hw, y - hw),
+                        (x + hw, y - hw),
+                        (x + hw, y + hw),
+                        (x - hw, y + hw),
+                    ];
+                    Stroker::fill_convex(context, &square, style.anti_aliased);
+                }
+                LineCap::Round => Stroker::disc(context, points[0], hw, style.anti_aliased),
+            }
+            return;
+        }
+
+        let count = points.len();
+        let segments = if closed { count } else { count - 1 };
+
+        for i in 0..segments {
+            let mut a = points[i];
+            let mut b = points[(i + 1) % count];
+            let (dx, dy) = direction(a, b);
+
+            if !closed && style.cap == LineCap::Square {
+                if i == 0 {
+                    a = (a.0 - dx * hw, a.1 - dy * hw);
+                }
+                if i == segments - 1 {
+                    b = (b.0 + dx * hw, b.1 + dy * hw);
+                }
+            }
+
+            // The segment may only be partly on the device.
+            let (t0, t1) = match bounds.clip_segment(a, b) {
+                Some(range) => range,
+                None => continue,
+            };
+            let (ex, ey) = (b.0 - a.0, b.1 - a.1);
+            let (a, b) = (
+                (a.0 + ex * t0, a.1 + ey * t0),
+                (a.0 + ex * t1, a.1 + ey * t1),
+            );
+
+            let (nx, ny) = (-dy * hw, dx * hw);
+            let quad = [
+                (a.0 + nx, a.1 + ny),
+                (b.0 + nx, b.1 + ny),
+                (b.0 - nx, b.1 - ny),
+                (a.0 - nx, a.1 - ny),
+            ];
+            context.fill_triangle(quad[0], quad[1], quad[2]);
+            context.fill_triangle(quad[0], quad[2], quad[3]);
+
+            if style.anti_aliased {
+                context.draw_aa_line(quad[0].0, quad[0].1, quad[1].0, quad[1].1);
+                context.draw_aa_line(quad[3].0, quad[3].1, quad[2].0, quad[2].1);
+                if !closed && style.cap != LineCap::Round {
+                    if i == 0 {
+                        context.draw_aa_line(quad[3].0, quad[3].1, quad[0].0, quad[0].1);
+                    }
+                    if i == segments - 1 {
+                        context.draw_aa_line(quad[1].0, quad[1].1, quad[2].0, quad[2].1);
+                    }
+                }
+            }
+        }
+
+        // Joins
+        let joins = if closed { 0..count } else { 1..count - 1 };
+        for i in joins {
+            let previous = points[(i + count - 1) % count];
+            let next = points[(i + 1) % count];
+            Stroker::join(context, previous, points[i], next, hw, style);
+        }
+
+        if !closed && style.cap == LineCap::Round {
+            Stroker::disc(context, points[0], hw, style.anti_aliased);
+            Stroker::disc(context, points[count - 1], hw, style.anti_aliased);
+        }
+    }
+
+    fn hairlines(context: &Context, points: &[(f64, f64)], closed: bool, anti_aliased: bool) {
+        let count = points.len();
+        if count == 1 {
+            let (x, y) = points[0];
+            context.set_pixel(x as i32, y as i32);
+            return;
+        }
+
+        if !anti_aliased {
+            let mut line: Vec<DevicePoint> = points
+                .iter()
+                .map(|&(x, y)| DevicePoint::new(x as i32, y as i32))
+                .collect();
+            if closed {
+                line.push(line[0]);
+            }
+            context.draw_lines(&line);
+            return;
+        }
+
+        let segments = if closed { count } else { count - 1 };
+        for i in 0..segments {
+            let (a, b) = (points[i], points[(i + 1) % count]);
+            context.draw_aa_line(a.0, a.1, b.0, b.1);
+        }
+    }
+
+    // Fills the outer corner where the segment arriving at `vertex` meets
+    // the one leaving it.
+    fn join(
+        context: &Context,
+        previous: (f64, f64),
+        vertex: (f64, f64),
+        next: (f64, f64),
+        hw: f64,
+        style: &StrokeStyle,
+    ) {
+        let (d0x, d0y) = direction(previous, vertex);
+        let (d1x, d1y) = direction(vertex, next);
+
+        let cross = d0x * d1y - d0y * d1x;
+        if cross.abs() < 1.0e-9 {
+            // Straight on, or doubling back
+            if d0x * d1x + d0y * d1y < 0.0 && style.join == LineJoin::Round {
+                Stroker::disc(context, vertex, hw, style.anti_aliased);
+            }
+            return;
+        }
+
+        // The outer side is opposite the turn.
+        let side = if cross > 0.0 { -1.0 } else { 1.0 };
+        let p0 = (vertex.0 - d0y * hw * side, vertex.1 + d0x * hw * side);
+        let p1 = (vertex.0 - d1y * hw * side, vertex.1 + d1x * hw * side);
+
+        match style.join {
+            LineJoin::Round => Stroker::disc(context, vertex, hw, style.anti_aliased),
+            LineJoin::Bevel => Stroker::bevel(context, vertex, p0, p1, style.anti_aliased),
+            LineJoin::Miter => {
+                // Half the angle between the segments' normals
+                let cos_half = ((1.0 + d0x * d1x + d0y * d1y) / 2.0).sqrt();
+                if cos_half < 1.0e-9 || 1.0 / cos_half > style.miter_limit {
+                    Stroker::bevel(context, vertex, p0, p1, style.anti_aliased);
+                    return;
+                }
+
+                let (mx, my) = (p0.0 + p1.0 - 2.0 * vertex.0, p0.1 + p1.1 - 2.0 * vertex.1);
+                let length = (mx * mx + my * my).sqrt();
+                let reach = hw / cos_half / length;
+                let tip = (vertex.0 + mx * reach, vertex.1 + my * reach);
+
+                context.fill_triangle(vertex, p0, tip);
+                context.fill_triangle(vertex, tip, p1);
+                if style.anti_aliased {
+                    context.draw_aa_line(p0.0, p0.1, tip.0, tip.1);
+                    context.draw_aa_line(tip.0, tip.1, p1.0, p1.1);
+                }
+            }
+        }
+    }
+
+    fn bevel(
+        context: &Context,
+        vertex: (f64, f64),
+        p0: (f64, f64),
+        p1: (f64, f64),
+        anti_aliased: bool,
+    ) {
+        context.fill_triangle(vertex, p0, p1);
+        if anti_aliased {
+            context.draw_aa_line(p0.0, p0.1, p1.0, p1.1);
+        }
+    }
+
+    fn disc(context: &Context, center: (f64, f64), radius: f64, anti_aliased: bool) {
+        // Keep each edge about 2 pixels long.
+        let sides = ((2.0 * PI * radius / 2.0).ceil() as usize).clamp(8, 64);
+
+        let outline: Vec<(f64, f64)> = (0..sides)
+            .map(|i| {
+                let angle = 2.0 * PI * i as f64 / sides as f64;
+                (
+                    center.0 + radius * angle.cos(),
+                    center.1 + radius * angle.sin(),
+                )
+            })
+            .collect();
+
+        Stroker::fill_convex(context, &outline, anti_aliased);
+    }
+
+    fn fill_convex(context: &Context, outline: &[(f64, f64)], anti_aliased: bool) {
+        for i in 1..outline.len() - 1 {
+            context.fill_triangle(outline[0], outline[i], outline[i + 1]);
+        }
+
+        if anti_aliased {
+            for i in 0..outline.len() {
+                let (a, b) = (outline[i], outline[(i + 1) % outline.len()]);
+                context.draw_aa_line(a.0, a.1, b.0, b.1);
+            }
+        }
+    }
+
+    // Splits the polyline into the pieces the dash pattern keeps. Only the
+    // parts of segments inside `bounds` are split; the pattern is moved
+    // along the rest without walking it.
+    fn dashes(
+        points: &[(f64, f64)],
+        style: &StrokeStyle,
+        bounds: &Rectangle,
+    ) -> Vec<Vec<(f64, f64)>> {
+        let mut dasher = Dasher::new(style);
+
+        if !dasher.in_gap() {
+            dasher.current.push(points[0]);
+        }
+
+        for window in points.windows(2) {
+            let (a, b) = (window[0], window[1]);
+            let length = ((b.0 - a.0).powi(2) + (b.1 - a.1).powi(2)).sqrt();
+
+            let (t0, t1) = match bounds.clip_segment(a, b) {
+                Some(range) => range,
+                None => {
+                    dasher.skip(length, b);
+                    continue;
+                }
+            };
+
+            let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+            let start = (a.0 + dx * t0, a.1 + dy * t0);
+            let end = (a.0 + dx * t1, a.1 + dy * t1);
+
+            if t0 > 0.0 {
+                dasher.skip(length * t0, start);
+            }
+            dasher.walk(start, end, length * (t1 - t0));
+            if t1 < 1.0 {
+                dasher.skip(length * (1.0 - t1), b);
+            }
+        }
+
+        if dasher.current.len() > 1 {
+            dasher.dashes.push(dasher.current);
+        }
+
+        dasher.dashes
+    }
+}
+
+// Where a polyline is in a dash pattern, and the dashes made so far
+struct Dasher {
+    // Alternating dash and gap lengths, an even count
+    pattern: Vec<f64>,
+    period: f64,
+    index: usize,
+    // Length left in pattern[index]
+    remaining: f64,
+
+    dashes: Vec<Vec<(f64, f64)>>,
+    current: Vec<(f64, f64)>,
+}
+
+impl Dasher {
+    fn new(style: &StrokeStyle) -> Self {
+        let mut pattern = style.dash.clone();
+        if pattern.len() % 2 == 1 {
+            pattern.extend_from_slice(&style.dash);
+        }
+        let period: f64 = pattern.iter().sum();
+        let remaining = pattern[0];
+
+        let mut dasher = Self {
+            pattern,
+            period,
+            index: 0,
+            remaining,
+            dashes: Vec::new(),
+            current: Vec::new(),
+        };
+
+        // Find where the offset lands in the pattern.
+        dasher.advance(style.dash_offset.rem_euclid(period));
+        dasher
+    }
+
+    // Odd entries are gaps
+    fn in_gap(&self) -> bool {
+        self.index % 2 == 1
+    }
+
+    // Moves `distance` along the pattern without making dashes.
+    fn advance(&mut self, distance: f64) {
+        let mut distance = distance.rem_euclid(self.period);
+        while distance > 0.0 {
+            if distance < self.remaining {
+                self.remaining -= distance;
+                return;
+            }
+            distance -= self.remaining;
+            self.index = (self.index + 1) % self.pattern.len();
+            self.remaining = self.pattern[self.index];
+        }
+    }
+
+    // Moves `distance` along a part of the line that isn't drawn, to `to`.
+    // The dash in progress is ended and, if the pattern is in a dash at
+    // `to`, a new one starts there.
+    fn skip(&mut self, distance: f64, to: (f64, f64)) {
+        if self.current.len() > 1 {
+            self.dashes.push(std::mem::take(&mut self.current));
+        }
+        self.current.clear();
+
+        self.advance(distance);
+        if !self.in_gap() {
+            self.current.push(to);
+        }
+    }
+
+    // Splits the segment from `a` to `b`, `length` long, into dashes.
+    fn walk(&mut self, a: (f64, f64), b: (f64, f64), length: f64) {
+        let (dx, dy) = direction(a, b);
+        let (mut a, mut length) = (a, length);
+
+        while length > self.remaining {
+            a = (a.0 + dx * self.remaining, a.1 + dy * self.remaining);
+            length -= self.remaining;
+
+            // A dash ends, or one starts
+            self.current.push(a);
+            if !self.in_gap() {
+                self.dashes.push(std::mem::take(&mut self.current));
+            }
+
+            self.index = (self.index + 1) % self.pattern.len();
+            self.remaining = self.pattern[self.index];
+        }
+
+        self.remaining -= length;
+        if !self.in_gap() {
+            self.current.push(b);
+        }
+    }
+}
+
+// Unit vector from a to b
+fn direction(a: (f64, f64), b: (f64, f64)) -> (f64, f64) {
+    let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+    let length = (dx * dx + dy * dy).sqrt();
+    if length == 0.0 {
+        return (1.0, 0.0);
+    }
+    (dx / length, dy / length)
+}
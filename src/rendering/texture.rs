@@ -0,0 +1,110 @@
+use std::rc::Rc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+// An image held in memory as 8-bit RGBA texels, row by row from the top
+// left, with straight (not premultiplied) alpha. Textures are immutable once
+// made so they can be shared between nodes; see assets::texture_manager for
+// loading them from files.
+//
+// Each texture has an id of its own, which render targets use to keep a
+// copy of it on the GPU.
+
+pub type RTexture = Rc<Texture>;
+
+static NEXT_ID: AtomicUsize = AtomicUsize::new(1);
+
+pub struct Texture {
+    id: usize,
+    width: u32,
+    height: u32,
+    pixels: Vec<u8>,
+}
+
+impl Texture {
+    /// `pixels` holds width * height RGBA texels.
+    pub fn from_rgba(width: u32, height: u32, pixels: Vec<u8>) -> Result<Self, String> {
+        let expected = width as usize * height as usize * 4;
+        if pixels.len() != expected {
+            return Err(format!(
+                "{}x{} texture needs {} bytes, got {}",
+                width,
+                height,
+                expected,
+                pixels.len()
+            ));
+        }
+
+        Ok(Self {
+            id: NEXT_ID.fetch_add(1, Ordering::Relaxed),
+            width,
+            height,
+            pixels,
+        })
+    }
+
+    /// Unique among the textures made while the program runs.
+    pub fn id(&self) -> usize {
+        self.id
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn pixels(&self) -> &[u8] {
+        &self.pixels
+    }
+
+    /// The (r, g, b, a) texel at x, y.
+    #[inline]
+    pub fn texel(&self, x: u32, y: u32) -> (u8, u8, u8, u8) {
+        let i = (y as usize * self.width as usize + x as usize) * 4;
+        (
+            self.pixels[i],
+            self.pixels[i + 1],
+            self.pixels[i + 2],
+            self.pixels[i + 3],
+        )
+    }
+
+    /// The region covering the whole texture.
+    pub fn region(&self) -> TextureRegion {
+        TextureRegion::new(0, 0, self.width, self.height)
+    }
+}
+
+/// A rectangle of texels within a texture.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct TextureRegion {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl TextureRegion {
+    pub fn new(x: u32, y: u32, width: u32, height: u32) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    /// The part of the region that lies inside `texture`.
+    pub fn clamped(&self, texture: &Texture) -> Self {
+        let x = self.x.min(texture.width());
+        let y = self.y.min(texture.height());
+        Self {
+            x,
+            y,
+            width: self.width.min(texture.width() - x),
+            height: self.height.min(texture.height() - y),
+        }
+    }
+}
@@ -1,4 +1,7 @@
-use geometry::point::Point;
+use std::collections::HashMap;
+
+use geometry::{point::Point, rectangle::Rectangle};
+use rendering::font::TextAlignment;
 
 pub struct VectorLine(f64, f64, f64, f64);
 
@@ -16,6 +19,13 @@ impl VectorGlyph {
         self.lines.push(Point::from_xy(x2, y2));
     }
 
+    // Adds a line between each pair of consecutive points.
+    fn add_polyline(&mut self, points: &[(f64, f64)]) {
+        for pair in points.windows(2) {
+            self.add_line(pair[0].0, pair[0].1, pair[1].0, pair[1].1);
+        }
+    }
+
     pub fn get_lines(&self) -> &Vec<Point> {
         &self.lines
     }
@@ -23,8 +33,24 @@ impl VectorGlyph {
 
 // VectorFont is a collection of glyphs where each glyph is a collection
 // of lines.
+//
+// Glyphs are one unit wide, centered on x = 0, with the baseline at y = 0,
+// capitals and ascenders reaching up to y = -1 and descenders down to
+// y = DESCENT. Every printable ASCII character has a glyph; anything else
+// is drawn as a crossed box.
+//
+// The font is monospaced: characters are centered on multiples of the
+// horizontal offset and lines are a vertical offset apart. Text is placed
+// relative to the first line's baseline, with the origin at the center of
+// the first character, the middle of each line or the center of the last
+// character for left, center and right alignment.
+
+// Depth of descenders below the baseline
+const DESCENT: f64 = 0.25;
+
 pub struct VectorFont {
-    glyphs: Vec<VectorGlyph>,
+    glyphs: HashMap<char, VectorGlyph>,
+    fallback: VectorGlyph,
 
     horizontal_offset: f64,
     vertical_offset: f64,
@@ -33,10 +59,22 @@ pub struct VectorFont {
 
 impl VectorFont {
     pub fn new() -> Self {
+        let mut fallback = VectorGlyph::new();
+        fallback.add_polyline(&[
+            (-0.4, 0.0),
+            (-0.4, -1.0),
+            (0.4, -1.0),
+            (0.4, 0.0),
+            (-0.4, 0.0),
+        ]);
+        fallback.add_line(-0.4, 0.0, 0.4, -1.0);
+        fallback.add_line(-0.4, -1.0, 0.4, 0.0);
+
         let mut vf = Self {
-            glyphs: Vec::new(),
+            glyphs: HashMap::new(),
+            fallback,
             horizontal_offset: 1.2,
-            vertical_offset: 1.2,
+            vertical_offset: 1.5,
             scale: 3.0,
         };
 
@@ -57,55 +95,126 @@ impl VectorFont {
         self.scale
     }
 
+    /// The glyph for `c`, or the fallback glyph if the font has none.
     pub fn get_glyph(&self, c: char) -> &VectorGlyph {
-        match c {
-            'A' => &self.glyphs[0],
-            'B' => &self.glyphs[1],
-            'C' => &self.glyphs[2],
-            'D' => &self.glyphs[3],
-            'E' => &self.glyphs[4],
-            'F' => &self.glyphs[5],
-            'G' => &self.glyphs[6],
-            'H' => &self.glyphs[7],
-            'I' => &self.glyphs[8],
-            'J' => &self.glyphs[9],
-            'K' => &self.glyphs[10],
-            'L' => &self.glyphs[11],
-            'M' => &self.glyphs[12],
-            'N' => &self.glyphs[13],
-            'O' => &self.glyphs[14],
-            'P' => &self.glyphs[15],
-            'Q' => &self.glyphs[16],
-            'R' => &self.glyphs[17],
-            'S' => &self.glyphs[18],
-            'T' => &self.glyphs[19],
-            'U' => &self.glyphs[20],
-            'V' => &self.glyphs[21],
-            'W' => &self.glyphs[22],
-            'X' => &self.glyphs[23],
-            'Y' => &self.glyphs[24],
-            'Z' => &self.glyphs[25],
-            '0' => &self.glyphs[26],
-            '1' => &self.glyphs[27],
-            '2' => &self.glyphs[28],
-            '3' => &self.glyphs[29],
-            '4' => &self.glyphs[30],
-            '5' => &self.glyphs[31],
-            '6' => &self.glyphs[32],
-            '7' => &self.glyphs[33],
-            '8' => &self.glyphs[34],
-            '9' => &self.glyphs[35],
-            '=' => &self.glyphs[36],
-            ',' => &self.glyphs[37],
-            '.' => &self.glyphs[38],
-            '/' => &self.glyphs[39],
-            '!' => &self.glyphs[40],
-            ':' => &self.glyphs[41],
-            '_' => &self.glyphs[42],
-            '-' => &self.glyphs[43],
-            ' ' => &self.glyphs[44],
-            _ => &self.glyphs[0],
+        self.glyphs.get(&c).unwrap_or(&self.fallback)
+    }
+
+    pub fn has_glyph(&self, c: char) -> bool {
+        self.glyphs.contains_key(&c)
+    }
+
+    // --------------------------------------------------------
+    // Measurement
+    // --------------------------------------------------------
+    /// The width of a single line, from the left edge of its first
+    /// character to the right edge of its last.
+    pub fn line_width(&self, line: &str) -> f64 {
+        match line.chars().count() {
+            0 => 0.0,
+            n => (n - 1) as f64 * self.horizontal_offset + 1.0,
+        }
+    }
+
+    /// The width of each line of `text`.
+    pub fn measure(&self, text: &str) -> Vec<f64> {
+        text.split('\n').map(|line| self.line_width(line)).collect()
+    }
+
+    /// The rectangle `text` covers when placed with `alignment`.
+    pub fn bounds(&self, text: &str, alignment: TextAlignment) -> Rectangle {
+        let widths = self.measure(text);
+
+        let mut min_x = f64::INFINITY;
+        let mut max_x = f64::NEG_INFINITY;
+        for &width in widths.iter().filter(|&&width| width > 0.0) {
+            let left = self.line_left(width, alignment);
+            min_x = min_x.min(left);
+            max_x = max_x.max(left + width);
+        }
+
+        if min_x > max_x {
+            return Rectangle::from_min_max(0.0, 0.0, 0.0, 0.0);
+        }
+
+        let bottom = (widths.len() - 1) as f64 * self.vertical_offset + DESCENT;
+        Rectangle::from_min_max(min_x, -1.0, max_x, bottom)
+    }
+
+    // Where the left edge of a line of `width` goes.
+    fn line_left(&self, width: f64, alignment: TextAlignment) -> f64 {
+        -0.5 - (width - 1.0) * alignment.anchor()
+    }
+
+    // --------------------------------------------------------
+    // Layout
+    // --------------------------------------------------------
+    /// Breaks the lines of `text` at spaces so none is wider than `width`.
+    /// Words that don't fit on a line of their own are split.
+    pub fn wrap(&self, text: &str, width: f64) -> String {
+        // Characters that fit within the width, at least one
+        let max = ((width - 1.0) / self.horizontal_offset).floor().max(0.0) as usize + 1;
+
+        let mut lines: Vec<Vec<char>> = Vec::new();
+        for paragraph in text.split('\n') {
+            let mut line: Option<Vec<char>> = None;
+
+            for word in paragraph.split(' ') {
+                let mut word: Vec<char> = word.chars().collect();
+
+                if let Some(current) = line.as_mut() {
+                    if current.len() + 1 + word.len() <= max {
+                        current.push(' ');
+                        current.extend(word);
+                        continue;
+                    }
+                }
+
+                if let Some(current) = line.take() {
+                    lines.push(current);
+                }
+                while word.len() > max {
+                    let rest = word.split_off(max);
+                    lines.push(word);
+                    word = rest;
+                }
+                line = Some(word);
+            }
+
+            lines.push(line.unwrap_or_default());
+        }
+
+        lines
+            .iter()
+            .map(|line| line.iter().collect::<String>())
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+
+    /// The lines that draw `text`, as pairs of points. '\n' starts a new
+    /// line.
+    pub fn layout(&self, text: &str, alignment: TextAlignment) -> Vec<Point> {
+        let mut vertices = Vec::new();
+
+        for (row, line) in text.split('\n').enumerate() {
+            let width = self.line_width(line);
+            // Center of the line's first character
+            let x = self.line_left(width, alignment) + 0.5;
+            let y = row as f64 * self.vertical_offset;
+
+            for (column, c) in line.chars().enumerate() {
+                let xpos = x + column as f64 * self.horizontal_offset;
+                for l in self.get_glyph(c).get_lines().iter() {
+                    vertices.push(Point::from_xy(l.x + xpos, l.y + y));
+                }
+            }
         }
+
+        vertices
+    }
+
+    fn add(&mut self, c: char, glyph: VectorGlyph) {
+        self.glyphs.insert(c, glyph);
     }
 
     fn construct(vf: &mut VectorFont) {
@@ -114,7 +223,7 @@ impl VectorFont {
         glyph.add_line(-0.5, 0.0, 0.0, -1.0);
         glyph.add_line(0.5, 0.0, 0.0, -1.0);
         glyph.add_line(-0.3, -0.4, 0.3, -0.4);
-        vf.glyphs.push(glyph);
+        vf.add('A', glyph);
 
         // B
         let mut glyph = VectorGlyph::new();
@@ -129,7 +238,7 @@ impl VectorFont {
         glyph.add_line(0.5, -0.45, 0.5, -0.35);
         glyph.add_line(0.5, -0.35, 0.25, -0.0);
         glyph.add_line(-0.5, -0.5, 0.25, -0.5);
-        vf.glyphs.push(glyph);
+        vf.add('B', glyph);
 
         // C
         let mut glyph = VectorGlyph::new();
@@ -140,7 +249,7 @@ impl VectorFont {
         glyph.add_line(-0.5, -0.75, -0.45, -1.0);
         glyph.add_line(-0.45, -1.0, 0.25, -1.0);
         glyph.add_line(0.25, -1.0, 0.5, -0.75);
-        vf.glyphs.push(glyph);
+        vf.add('C', glyph);
 
         // D
         let mut glyph = VectorGlyph::new();
@@ -150,7 +259,7 @@ impl VectorFont {
         glyph.add_line(-0.5, -1.0, 0.25, -1.0);
         glyph.add_line(0.25, -1.0, 0.5, -0.75);
         glyph.add_line(0.5, -0.75, 0.5, -0.25);
-        vf.glyphs.push(glyph);
+        vf.add('D', glyph);
 
         // E
         let mut glyph = VectorGlyph::new();
@@ -158,14 +267,14 @@ impl VectorFont {
         glyph.add_line(-0.5, 0.0, -0.5, -1.0);
         glyph.add_line(-0.5, -1.0, 0.5, -1.0);
         glyph.add_line(-0.5, -0.5, 0.40, -0.5);
-        vf.glyphs.push(glyph);
+        vf.add('E', glyph);
 
         // F
         let mut glyph = VectorGlyph::new();
         glyph.add_line(-0.5, 0.0, -0.5, -1.0);
         glyph.add_line(-0.5, -1.0, 0.5, -1.0);
         glyph.add_line(-0.5, -0.5, 0.4, -0.5);
-        vf.glyphs.push(glyph);
+        vf.add('F', glyph);
 
         // G
         let mut glyph = VectorGlyph::new();
@@ -177,21 +286,21 @@ impl VectorFont {
         glyph.add_line(-0.5, 0.0, -0.5, -1.0);
         glyph.add_line(-0.5, -1.0, 0.45, -1.0);
         glyph.add_line(0.45, -1.0, 0.5, -0.75);
-        vf.glyphs.push(glyph);
+        vf.add('G', glyph);
 
         // H
         let mut glyph = VectorGlyph::new();
         glyph.add_line(-0.5, 0.0, -0.5, -1.0);
         glyph.add_line(0.5, 0.0, 0.5, -1.0);
         glyph.add_line(-0.5, -0.5, 0.5, -0.5);
-        vf.glyphs.push(glyph);
+        vf.add('H', glyph);
 
         // I
         let mut glyph = VectorGlyph::new();
         glyph.add_line(-0.5, 0.0, 0.5, 0.0);
         glyph.add_line(-0.5, -1.0, 0.5, -1.0);
         glyph.add_line(0.0, 0.0, 0.0, -1.0);
-        vf.glyphs.push(glyph);
+        vf.add('I', glyph);
 
         // J
         let mut glyph = VectorGlyph::new();
@@ -201,20 +310,20 @@ impl VectorFont {
         glyph.add_line(0.5, -0.25, 0.4, 0.0);
         glyph.add_line(0.4, 0.0, -0.4, 0.0);
         glyph.add_line(-0.4, 0.0, -0.5, -0.25);
-        vf.glyphs.push(glyph);
+        vf.add('J', glyph);
 
         // K
         let mut glyph = VectorGlyph::new();
         glyph.add_line(-0.5, 0.0, -0.5, -1.0);
         glyph.add_line(-0.5, -0.5, 0.4, -1.0);
         glyph.add_line(-0.5, -0.5, 0.5, 0.0);
-        vf.glyphs.push(glyph);
+        vf.add('K', glyph);
 
         // L
         let mut glyph = VectorGlyph::new();
         glyph.add_line(-0.5, 0.0, -0.5, -1.0);
         glyph.add_line(-0.5, 0.0, 0.4, 0.0);
-        vf.glyphs.push(glyph);
+        vf.add('L', glyph);
 
         // M
         let mut glyph = VectorGlyph::new();
@@ -222,14 +331,14 @@ impl VectorFont {
         glyph.add_line(-0.5, -1.0, 0.0, -0.5);
         glyph.add_line(0.0, -0.5, 0.5, -1.0);
         glyph.add_line(0.5, 0.0, 0.5, -1.0);
-        vf.glyphs.push(glyph);
+        vf.add('M', glyph);
 
         // N
         let mut glyph = VectorGlyph::new();
         glyph.add_line(-0.5, 0.0, -0.5, -1.0);
         glyph.add_line(-0.5, -1.0, 0.5, 0.0);
         glyph.add_line(0.5, 0.0, 0.5, -1.0);
-        vf.glyphs.push(glyph);
+        vf.add('N', glyph);
 
         // O
         let mut glyph = VectorGlyph::new();
@@ -241,7 +350,7 @@ impl VectorFont {
         glyph.add_line(0.4, -1.0, 0.5, -0.75);
         glyph.add_line(0.5, -0.75, 0.5, -0.25);
         glyph.add_line(0.5, -0.25, 0.4, 0.0);
-        vf.glyphs.push(glyph);
+        vf.add('O', glyph);
 
         // P
         let mut glyph = VectorGlyph::new();
@@ -251,7 +360,7 @@ impl VectorFont {
         glyph.add_line(0.5, -0.85, 0.5, -0.65);
         glyph.add_line(0.5, -0.65, 0.4, -0.5);
         glyph.add_line(0.4, -0.5, -0.5, -0.5);
-        vf.glyphs.push(glyph);
+        vf.add('P', glyph);
 
         // Q
         let mut glyph = VectorGlyph::new();
@@ -264,7 +373,7 @@ impl VectorFont {
         glyph.add_line(0.5, -0.75, 0.5, -0.25);
         glyph.add_line(0.5, -0.25, 0.4, 0.0);
         glyph.add_line(0.0, -0.5, 0.7, 0.2);
-        vf.glyphs.push(glyph);
+        vf.add('Q', glyph);
 
         // R
         let mut glyph = VectorGlyph::new();
@@ -275,7 +384,7 @@ impl VectorFont {
         glyph.add_line(0.5, -0.65, 0.4, -0.5);
         glyph.add_line(0.4, -0.5, -0.5, -0.5);
         glyph.add_line(0.2, -0.5, 0.5, -0.0);
-        vf.glyphs.push(glyph);
+        vf.add('R', glyph);
 
         // S
         let mut glyph = VectorGlyph::new();
@@ -288,13 +397,13 @@ impl VectorFont {
         glyph.add_line(0.5, -0.5, 0.5, -0.25);
         glyph.add_line(0.5, -0.25, 0.4, 0.0);
         glyph.add_line(-0.5, -0.5, 0.5, -0.5);
-        vf.glyphs.push(glyph);
+        vf.add('S', glyph);
 
         // T
         let mut glyph = VectorGlyph::new();
         glyph.add_line(-0.5, -1.0, 0.5, -1.0);
         glyph.add_line(0.0, 0.0, 0.0, -1.0);
-        vf.glyphs.push(glyph);
+        vf.add('T', glyph);
 
         // U
         let mut glyph = VectorGlyph::new();
@@ -303,13 +412,13 @@ impl VectorFont {
         glyph.add_line(-0.5, -0.25, -0.5, -1.0);
         glyph.add_line(0.5, -1.0, 0.5, -0.25);
         glyph.add_line(0.5, -0.25, 0.4, 0.0);
-        vf.glyphs.push(glyph);
+        vf.add('U', glyph);
 
         // V
         let mut glyph = VectorGlyph::new();
         glyph.add_line(-0.5, -1.0, 0.0, 0.0);
         glyph.add_line(0.0, 0.0, 0.5, -1.0);
-        vf.glyphs.push(glyph);
+        vf.add('V', glyph);
 
         // W
         let mut glyph = VectorGlyph::new();
@@ -317,27 +426,27 @@ impl VectorFont {
         glyph.add_line(-0.5, 0.0, 0.0, -0.5);
         glyph.add_line(0.0, -0.5, 0.5, 0.0);
         glyph.add_line(0.5, -1.0, 0.5, 0.0);
-        vf.glyphs.push(glyph);
+        vf.add('W', glyph);
 
         // X
         let mut glyph = VectorGlyph::new();
         glyph.add_line(-0.5, -1.0, 0.5, 0.0);
         glyph.add_line(-0.5, 0.0, 0.5, -1.0);
-        vf.glyphs.push(glyph);
+        vf.add('X', glyph);
 
         // Y
         let mut glyph = VectorGlyph::new();
         glyph.add_line(-0.5, -1.0, 0.0, -0.5);
         glyph.add_line(0.0, -0.5, 0.5, -1.0);
         glyph.add_line(0.0, -0.5, 0.0, 0.0);
-        vf.glyphs.push(glyph);
+        vf.add('Y', glyph);
 
         // Z
         let mut glyph = VectorGlyph::new();
         glyph.add_line(-0.5, -1.0, 0.5, -1.0);
         glyph.add_line(0.5, -1.0, -0.5, 0.0);
         glyph.add_line(-0.5, 0.0, 0.5, 0.0);
-        vf.glyphs.push(glyph);
+        vf.add('Z', glyph);
 
         // 0
         let mut glyph = VectorGlyph::new();
@@ -350,14 +459,14 @@ impl VectorFont {
         glyph.add_line(0.5, -0.75, 0.5, -0.25);
         glyph.add_line(0.5, -0.25, 0.4, 0.0);
         glyph.add_line(-0.45, -0.1, 0.45, -0.9);
-        vf.glyphs.push(glyph);
+        vf.add('0', glyph);
 
         // 1
         let mut glyph = VectorGlyph::new();
         glyph.add_line(-0.2, -0.8, 0.0, -1.0);
         glyph.add_line(0.0, -1.0, 0.0, 0.0);
         glyph.add_line(-0.5, 0.0, 0.5, 0.0);
-        vf.glyphs.push(glyph);
+        vf.add('1', glyph);
 
         // 2
         let mut glyph = VectorGlyph::new();
@@ -365,7 +474,7 @@ impl VectorFont {
         glyph.add_line(0.4, -1.0, 0.5, -0.75);
         glyph.add_line(0.5, -0.75, -0.5, 0.0);
         glyph.add_line(-0.5, 0.0, 0.5, 0.0);
-        vf.glyphs.push(glyph);
+        vf.add('2', glyph);
 
         // 3
         let mut glyph = VectorGlyph::new();
@@ -373,14 +482,14 @@ impl VectorFont {
         glyph.add_line(0.5, -1.0, 0.5, 0.0);
         glyph.add_line(0.5, 0.0, -0.5, 0.0);
         glyph.add_line(-0.4, -0.5, 0.5, -0.5);
-        vf.glyphs.push(glyph);
+        vf.add('3', glyph);
 
         // 4
         let mut glyph = VectorGlyph::new();
         glyph.add_line(0.0, 0.0, 0.0, -1.0);
         glyph.add_line(0.0, -1.0, -0.5, -0.5);
         glyph.add_line(-0.5, -0.5, 0.5, -0.5);
-        vf.glyphs.push(glyph);
+        vf.add('4', glyph);
 
         // 5
         let mut glyph = VectorGlyph::new();
@@ -391,7 +500,7 @@ impl VectorFont {
         glyph.add_line(0.5, -0.4, 0.5, -0.25);
         glyph.add_line(0.5, -0.25, 0.4, 0.0);
         glyph.add_line(0.4, 0.0, -0.5, 0.0);
-        vf.glyphs.push(glyph);
+        vf.add('5', glyph);
 
         // 6
         let mut glyph = VectorGlyph::new();
@@ -400,13 +509,13 @@ impl VectorFont {
         glyph.add_line(0.5, -0.5, 0.5, 0.0);
         glyph.add_line(0.5, 0.0, -0.5, 0.0);
         glyph.add_line(-0.5, 0.0, -0.5, -0.5);
-        vf.glyphs.push(glyph);
+        vf.add('6', glyph);
 
         // 7
         let mut glyph = VectorGlyph::new();
         glyph.add_line(-0.5, -1.0, 0.5, -1.0);
         glyph.add_line(0.5, -1.0, 0.0, 0.0);
-        vf.glyphs.push(glyph);
+        vf.add('7', glyph);
 
         // 8
         let mut glyph = VectorGlyph::new();
@@ -419,7 +528,7 @@ impl VectorFont {
         glyph.add_line(0.5, -0.75, 0.5, -0.25);
         glyph.add_line(0.5, -0.25, 0.4, 0.0);
         glyph.add_line(-0.5, -0.5, 0.5, -0.5);
-        vf.glyphs.push(glyph);
+        vf.add('8', glyph);
 
         // 9
         let mut glyph = VectorGlyph::new();
@@ -428,19 +537,19 @@ impl VectorFont {
         glyph.add_line(0.5, -0.5, 0.5, -1.0);
         glyph.add_line(0.5, -1.0, -0.5, -1.0);
         glyph.add_line(-0.5, -1.0, -0.5, -0.5);
-        vf.glyphs.push(glyph);
+        vf.add('9', glyph);
 
         // =
         let mut glyph = VectorGlyph::new();
         glyph.add_line(-0.5, -0.3, 0.5, -0.3);
         glyph.add_line(-0.5, -0.7, 0.5, -0.7);
-        vf.glyphs.push(glyph);
+        vf.add('=', glyph);
 
         // ,
         let mut glyph = VectorGlyph::new();
         glyph.add_line(0.0, -0.3, 0.0, -0.2);
         glyph.add_line(0.0, -0.2, -0.3, -0.0);
-        vf.glyphs.push(glyph);
+        vf.add(',', glyph);
 
         // .
         let mut glyph = VectorGlyph::new();
@@ -448,12 +557,12 @@ impl VectorFont {
         glyph.add_line(-0.1, -0.1, 0.1, -0.1);
         glyph.add_line(0.1, -0.1, 0.1, 0.0);
         glyph.add_line(0.1, 0.0, -0.1, 0.0);
-        vf.glyphs.push(glyph);
+        vf.add('.', glyph);
 
         // "/"
         let mut glyph = VectorGlyph::new();
         glyph.add_line(-0.25, 0.0, 0.25, -1.0);
-        vf.glyphs.push(glyph);
+        vf.add('/', glyph);
 
         // !
         let mut glyph = VectorGlyph::new();
@@ -462,7 +571,7 @@ impl VectorFont {
         glyph.add_line(0.1, -0.1, 0.1, 0.0);
         glyph.add_line(0.1, 0.0, -0.1, 0.0);
         glyph.add_line(0.0, -0.2, 0.0, -1.0);
-        vf.glyphs.push(glyph);
+        vf.add('!', glyph);
 
         // :
         let mut glyph = VectorGlyph::new();
@@ -474,20 +583,502 @@ impl VectorFont {
         glyph.add_line(-0.1, -1.0, 0.1, -1.0);
         glyph.add_line(0.1, -1.0, 0.1, -0.9);
         glyph.add_line(0.1, -0.9, -0.1, -0.9);
-        vf.glyphs.push(glyph);
+        vf.add(':', glyph);
 
         // _
         let mut glyph = VectorGlyph::new();
         glyph.add_line(-0.45, -0.1, 0.45, -0.1);
-        vf.glyphs.push(glyph);
+        vf.add('_', glyph);
 
         // -
         let mut glyph = VectorGlyph::new();
         glyph.add_line(-0.40, -0.5, 0.40, -0.5);
-        vf.glyphs.push(glyph);
+        vf.add('-', glyph);
 
         // " " <-- space
         let glyph = VectorGlyph::new();
-        vf.glyphs.push(glyph);
+        vf.add(' ', glyph);
+
+        // a
+        let mut glyph = VectorGlyph::new();
+        glyph.add_polyline(&[(-0.4, -0.6), (0.3, -0.6), (0.4, -0.5), (0.4, 0.0)]);
+        glyph.add_polyline(&[
+            (0.4, -0.35),
+            (-0.3, -0.35),
+            (-0.4, -0.25),
+            (-0.4, -0.1),
+            (-0.3, 0.0),
+            (0.4, 0.0),
+        ]);
+        vf.add('a', glyph);
+
+        // b
+        let mut glyph = VectorGlyph::new();
+        glyph.add_line(-0.4, -1.0, -0.4, 0.0);
+        glyph.add_polyline(&[
+            (-0.4, -0.6),
+            (0.3, -0.6),
+            (0.4, -0.5),
+            (0.4, -0.1),
+            (0.3, 0.0),
+            (-0.4, 0.0),
+        ]);
+        vf.add('b', glyph);
+
+        // c
+        let mut glyph = VectorGlyph::new();
+        glyph.add_polyline(&[
+            (0.4, -0.6),
+            (-0.3, -0.6),
+            (-0.4, -0.5),
+            (-0.4, -0.1),
+            (-0.3, 0.0),
+            (0.4, 0.0),
+        ]);
+        vf.add('c', glyph);
+
+        // d
+        let mut glyph = VectorGlyph::new();
+        glyph.add_line(0.4, -1.0, 0.4, 0.0);
+        glyph.add_polyline(&[
+            (0.4, -0.6),
+            (-0.3, -0.6),
+            (-0.4, -0.5),
+            (-0.4, -0.1),
+            (-0.3, 0.0),
+            (0.4, 0.0),
+        ]);
+        vf.add('d', glyph);
+
+        // e
+        let mut glyph = VectorGlyph::new();
+        glyph.add_polyline(&[
+            (-0.4, -0.3),
+            (0.4, -0.3),
+            (0.4, -0.5),
+            (0.3, -0.6),
+            (-0.3, -0.6),
+            (-0.4, -0.5),
+            (-0.4, -0.1),
+            (-0.3, 0.0),
+            (0.4, 0.0),
+        ]);
+        vf.add('e', glyph);
+
+        // f
+        let mut glyph = VectorGlyph::new();
+        glyph.add_polyline(&[(0.4, -1.0), (0.0, -1.0), (-0.1, -0.9), (-0.1, 0.0)]);
+        glyph.add_line(-0.4, -0.6, 0.3, -0.6);
+        vf.add('f', glyph);
+
+        // g
+        let mut glyph = VectorGlyph::new();
+        glyph.add_polyline(&[
+            (0.4, -0.6),
+            (-0.3, -0.6),
+            (-0.4, -0.5),
+            (-0.4, -0.1),
+            (-0.3, 0.0),
+            (0.4, 0.0),
+        ]);
+        glyph.add_polyline(&[(0.4, -0.6), (0.4, 0.15), (0.3, 0.25), (-0.4, 0.25)]);
+        vf.add('g', glyph);
+
+        // h
+        let mut glyph = VectorGlyph::new();
+        glyph.add_line(-0.4, -1.0, -0.4, 0.0);
+        glyph.add_polyline(&[(-0.4, -0.6), (0.3, -0.6), (0.4, -0.5), (0.4, 0.0)]);
+        vf.add('h', glyph);
+
+        // i
+        let mut glyph = VectorGlyph::new();
+        glyph.add_line(0.0, -0.6, 0.0, 0.0);
+        glyph.add_line(0.0, -0.9, 0.0, -0.8);
+        vf.add('i', glyph);
+
+        // j
+        let mut glyph = VectorGlyph::new();
+        glyph.add_polyline(&[(0.1, -0.6), (0.1, 0.15), (0.0, 0.25), (-0.4, 0.25)]);
+        glyph.add_line(0.1, -0.9, 0.1, -0.8);
+        vf.add('j', glyph);
+
+        // k
+        let mut glyph = VectorGlyph::new();
+        glyph.add_line(-0.4, -1.0, -0.4, 0.0);
+        glyph.add_line(-0.4, -0.25, 0.4, -0.6);
+        glyph.add_line(-0.15, -0.35, 0.4, 0.0);
+        vf.add('k', glyph);
+
+        // l
+        let mut glyph = VectorGlyph::new();
+        glyph.add_polyline(&[
+            (-0.3, -1.0),
+            (-0.1, -1.0),
+            (-0.1, -0.1),
+            (0.0, 0.0),
+            (0.2, 0.0),
+        ]);
+        vf.add('l', glyph);
+
+        // m
+        let mut glyph = VectorGlyph::new();
+        glyph.add_polyline(&[
+            (-0.4, 0.0),
+            (-0.4, -0.6),
+            (-0.1, -0.6),
+            (0.0, -0.5),
+            (0.0, 0.0),
+        ]);
+        glyph.add_polyline(&[
+            (0.0, -0.5),
+            (0.1, -0.6),
+            (0.3, -0.6),
+            (0.4, -0.5),
+            (0.4, 0.0),
+        ]);
+        vf.add('m', glyph);
+
+        // n
+        let mut glyph = VectorGlyph::new();
+        glyph.add_polyline(&[
+            (-0.4, 0.0),
+            (-0.4, -0.6),
+            (0.3, -0.6),
+            (0.4, -0.5),
+            (0.4, 0.0),
+        ]);
+        vf.add('n', glyph);
+
+        // o
+        let mut glyph = VectorGlyph::new();
+        glyph.add_polyline(&[
+            (-0.3, 0.0),
+            (-0.4, -0.1),
+            (-0.4, -0.5),
+            (-0.3, -0.6),
+            (0.3, -0.6),
+            (0.4, -0.5),
+            (0.4, -0.1),
+            (0.3, 0.0),
+            (-0.3, 0.0),
+        ]);
+        vf.add('o', glyph);
+
+        // p
+        let mut glyph = VectorGlyph::new();
+        glyph.add_line(-0.4, -0.6, -0.4, 0.25);
+        glyph.add_polyline(&[
+            (-0.4, -0.6),
+            (0.3, -0.6),
+            (0.4, -0.5),
+            (0.4, -0.1),
+            (0.3, 0.0),
+            (-0.4, 0.0),
+        ]);
+        vf.add('p', glyph);
+
+        // q
+        let mut glyph = VectorGlyph::new();
+        glyph.add_line(0.4, -0.6, 0.4, 0.25);
+        glyph.add_polyline(&[
+            (0.4, -0.6),
+            (-0.3, -0.6),
+            (-0.4, -0.5),
+            (-0.4, -0.1),
+            (-0.3, 0.0),
+            (0.4, 0.0),
+        ]);
+        vf.add('q', glyph);
+
+        // r
+        let mut glyph = VectorGlyph::new();
+        glyph.add_line(-0.4, 0.0, -0.4, -0.6);
+        glyph.add_polyline(&[(-0.4, -0.4), (-0.2, -0.6), (0.4, -0.6)]);
+        vf.add('r', glyph);
+
+        // s
+        let mut glyph = VectorGlyph::new();
+        glyph.add_polyline(&[
+            (0.4, -0.6),
+            (-0.3, -0.6),
+            (-0.4, -0.5),
+            (-0.4, -0.4),
+            (-0.3, -0.3),
+            (0.3, -0.3),
+            (0.4, -0.2),
+            (0.4, -0.1),
+            (0.3, 0.0),
+            (-0.4, 0.0),
+        ]);
+        vf.add('s', glyph);
+
+        // t
+        let mut glyph = VectorGlyph::new();
+        glyph.add_polyline(&[(-0.1, -0.9), (-0.1, -0.1), (0.0, 0.0), (0.3, 0.0)]);
+        glyph.add_line(-0.4, -0.6, 0.3, -0.6);
+        vf.add('t', glyph);
+
+        // u
+        let mut glyph = VectorGlyph::new();
+        glyph.add_polyline(&[(-0.4, -0.6), (-0.4, -0.1), (-0.3, 0.0), (0.4, 0.0)]);
+        glyph.add_line(0.4, -0.6, 0.4, 0.0);
+        vf.add('u', glyph);
+
+        // v
+        let mut glyph = VectorGlyph::new();
+        glyph.add_polyline(&[(-0.4, -0.6), (0.0, 0.0), (0.4, -0.6)]);
+        vf.add('v', glyph);
+
+        // w
+        let mut glyph = VectorGlyph::new();
+        glyph.add_polyline(&[
+            (-0.4, -0.6),
+            (-0.2, 0.0),
+            (0.0, -0.4),
+            (0.2, 0.0),
+            (0.4, -0.6),
+        ]);
+        vf.add('w', glyph);
+
+        // x
+        let mut glyph = VectorGlyph::new();
+        glyph.add_line(-0.4, -0.6, 0.4, 0.0);
+        glyph.add_line(-0.4, 0.0, 0.4, -0.6);
+        vf.add('x', glyph);
+
+        // y
+        let mut glyph = VectorGlyph::new();
+        glyph.add_line(-0.4, -0.6, 0.0, 0.0);
+        glyph.add_line(0.4, -0.6, -0.16, 0.24);
+        vf.add('y', glyph);
+
+        // z
+        let mut glyph = VectorGlyph::new();
+        glyph.add_polyline(&[(-0.4, -0.6), (0.4, -0.6), (-0.4, 0.0), (0.4, 0.0)]);
+        vf.add('z', glyph);
+
+        // "
+        let mut glyph = VectorGlyph::new();
+        glyph.add_line(-0.15, -1.0, -0.15, -0.75);
+        glyph.add_line(0.15, -1.0, 0.15, -0.75);
+        vf.add('"', glyph);
+
+        // #
+        let mut glyph = VectorGlyph::new();
+        glyph.add_line(-0.2, -0.9, -0.2, -0.1);
+        glyph.add_line(0.2, -0.9, 0.2, -0.1);
+        glyph.add_line(-0.45, -0.65, 0.45, -0.65);
+        glyph.add_line(-0.45, -0.35, 0.45, -0.35);
+        vf.add('#', glyph);
+
+        // $
+        let mut glyph = VectorGlyph::new();
+        glyph.add_polyline(&[
+            (0.4, -0.85),
+            (-0.3, -0.85),
+            (-0.4, -0.75),
+            (-0.4, -0.6),
+            (-0.3, -0.5),
+            (0.3, -0.5),
+            (0.4, -0.4),
+            (0.4, -0.25),
+            (0.3, -0.15),
+            (-0.4, -0.15),
+        ]);
+        glyph.add_line(0.0, -1.0, 0.0, 0.0);
+        vf.add('$', glyph);
+
+        // %
+        let mut glyph = VectorGlyph::new();
+        glyph.add_line(-0.4, 0.0, 0.4, -1.0);
+        glyph.add_polyline(&[
+            (-0.4, -1.0),
+            (-0.1, -1.0),
+            (-0.1, -0.7),
+            (-0.4, -0.7),
+            (-0.4, -1.0),
+        ]);
+        glyph.add_polyline(&[
+            (0.1, -0.3),
+            (0.4, -0.3),
+            (0.4, 0.0),
+            (0.1, 0.0),
+            (0.1, -0.3),
+        ]);
+        vf.add('%', glyph);
+
+        // &
+        let mut glyph = VectorGlyph::new();
+        glyph.add_polyline(&[
+            (0.5, 0.0),
+            (-0.2, -0.7),
+            (-0.2, -0.9),
+            (-0.1, -1.0),
+            (0.1, -1.0),
+            (0.2, -0.9),
+            (0.2, -0.7),
+            (-0.4, -0.35),
+            (-0.4, -0.1),
+            (-0.3, 0.0),
+            (0.1, 0.0),
+            (0.45, -0.35),
+        ]);
+        vf.add('&', glyph);
+
+        // '
+        let mut glyph = VectorGlyph::new();
+        glyph.add_line(0.0, -1.0, 0.0, -0.75);
+        vf.add('\'', glyph);
+
+        // (
+        let mut glyph = VectorGlyph::new();
+        glyph.add_polyline(&[(0.2, -1.0), (-0.1, -0.75), (-0.1, -0.05), (0.2, 0.2)]);
+        vf.add('(', glyph);
+
+        // )
+        let mut glyph = VectorGlyph::new();
+        glyph.add_polyline(&[(-0.2, -1.0), (0.1, -0.75), (0.1, -0.05), (-0.2, 0.2)]);
+        vf.add(')', glyph);
+
+        // *
+        let mut glyph = VectorGlyph::new();
+        glyph.add_line(0.0, -0.8, 0.0, -0.2);
+        glyph.add_line(-0.3, -0.65, 0.3, -0.35);
+        glyph.add_line(-0.3, -0.35, 0.3, -0.65);
+        vf.add('*', glyph);
+
+        // +
+        let mut glyph = VectorGlyph::new();
+        glyph.add_line(0.0, -0.8, 0.0, -0.2);
+        glyph.add_line(-0.4, -0.5, 0.4, -0.5);
+        vf.add('+', glyph);
+
+        // ;
+        let mut glyph = VectorGlyph::new();
+        glyph.add_polyline(&[
+            (-0.1, -0.6),
+            (0.1, -0.6),
+            (0.1, -0.7),
+            (-0.1, -0.7),
+            (-0.1, -0.6),
+        ]);
+        glyph.add_polyline(&[(0.0, -0.3), (0.0, -0.2), (-0.3, 0.0)]);
+        vf.add(';', glyph);
+
+        // <
+        let mut glyph = VectorGlyph::new();
+        glyph.add_polyline(&[(0.4, -0.9), (-0.4, -0.5), (0.4, -0.1)]);
+        vf.add('<', glyph);
+
+        // >
+        let mut glyph = VectorGlyph::new();
+        glyph.add_polyline(&[(-0.4, -0.9), (0.4, -0.5), (-0.4, -0.1)]);
+        vf.add('>', glyph);
+
+        // ?
+        let mut glyph = VectorGlyph::new();
+        glyph.add_polyline(&[
+            (-0.4, -0.75),
+            (-0.4, -0.9),
+            (-0.3, -1.0),
+            (0.3, -1.0),
+            (0.4, -0.9),
+            (0.4, -0.6),
+            (0.3, -0.5),
+            (0.0, -0.5),
+            (0.0, -0.25),
+        ]);
+        glyph.add_polyline(&[
+            (-0.1, 0.0),
+            (-0.1, -0.1),
+            (0.1, -0.1),
+            (0.1, 0.0),
+            (-0.1, 0.0),
+        ]);
+        vf.add('?', glyph);
+
+        // @
+        let mut glyph = VectorGlyph::new();
+        glyph.add_polyline(&[
+            (0.2, -0.3),
+            (-0.2, -0.3),
+            (-0.2, -0.7),
+            (0.2, -0.7),
+            (0.2, -0.3),
+            (0.4, -0.3),
+            (0.4, -0.9),
+            (0.3, -1.0),
+            (-0.4, -1.0),
+            (-0.5, -0.9),
+            (-0.5, -0.1),
+            (-0.4, 0.0),
+            (0.4, 0.0),
+        ]);
+        vf.add('@', glyph);
+
+        // [
+        let mut glyph = VectorGlyph::new();
+        glyph.add_polyline(&[(0.2, -1.0), (-0.1, -1.0), (-0.1, 0.2), (0.2, 0.2)]);
+        vf.add('[', glyph);
+
+        // "\"
+        let mut glyph = VectorGlyph::new();
+        glyph.add_line(-0.25, -1.0, 0.25, 0.0);
+        vf.add('\\', glyph);
+
+        // ]
+        let mut glyph = VectorGlyph::new();
+        glyph.add_polyline(&[(-0.2, -1.0), (0.1, -1.0), (0.1, 0.2), (-0.2, 0.2)]);
+        vf.add(']', glyph);
+
+        // ^
+        let mut glyph = VectorGlyph::new();
+        glyph.add_polyline(&[(-0.3, -0.7), (0.0, -1.0), (0.3, -0.7)]);
+        vf.add('^', glyph);
+
+        // `
+        let mut glyph = VectorGlyph::new();
+        glyph.add_line(-0.1, -1.0, 0.1, -0.8);
+        vf.add('`', glyph);
+
+        // {
+        let mut glyph = VectorGlyph::new();
+        glyph.add_polyline(&[
+            (0.2, -1.0),
+            (0.0, -1.0),
+            (-0.1, -0.9),
+            (-0.1, -0.5),
+            (-0.2, -0.4),
+            (-0.1, -0.3),
+            (-0.1, 0.1),
+            (0.0, 0.2),
+            (0.2, 0.2),
+        ]);
+        vf.add('{', glyph);
+
+        // |
+        let mut glyph = VectorGlyph::new();
+        glyph.add_line(0.0, -1.0, 0.0, 0.2);
+        vf.add('|', glyph);
+
+        // }
+        let mut glyph = VectorGlyph::new();
+        glyph.add_polyline(&[
+            (-0.2, -1.0),
+            (0.0, -1.0),
+            (0.1, -0.9),
+            (0.1, -0.5),
+            (0.2, -0.4),
+            (0.1, -0.3),
+            (0.1, 0.1),
+            (0.0, 0.2),
+            (-0.2, 0.2),
+        ]);
+        vf.add('}', glyph);
+
+        // ~
+        let mut glyph = VectorGlyph::new();
+        glyph.add_polyline(&[(-0.4, -0.45), (-0.2, -0.6), (0.2, -0.4), (0.4, -0.55)]);
+        vf.add('~', glyph);
     }
 }
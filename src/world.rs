@@ -1,26 +1,47 @@
 extern crate sdl2;
+extern crate serde_json;
 
 // use std::error::Error;
 // use std::fmt;
 use std::{
     cell::RefCell,
-    collections::HashMap,
+    collections::{HashMap, HashSet},
+    fs,
+    path::{Path, PathBuf},
     rc::Rc,
     time::{Duration, Instant},
 };
 
 use self::sdl2::{
+    event::{Event, WindowEvent},
+    keyboard::Keycode,
+    mouse::MouseWheelDirection,
     render::WindowCanvas,
     Sdl,
-    {event::Event, keyboard::Keycode},
 };
 
-use events::io_events::IOEventData;
+use assets::{
+    file_watcher::FileWatcher,
+    font_manager::{FontManager, RFontManager},
+    loader::Loader,
+    scene_loader::{NodeBuilder, NodeDescription, NodeFactory, SceneLoader},
+    texture_manager::{RTextureManager, TextureManager},
+};
+use debug::{console::Console, inspector::Inspector};
+use events::{
+    action_map::{ActionMap, RActionMap},
+    event_bus::{EventBus, REventBus},
+    io_events::{IOEvent, IOEventData, KeyAction, MouseAction, MouseButton, MouseData},
+    keyboard::{KeyboardState, RKeyboardState},
+    mouse::{Drag, MouseState, RMouseState},
+};
 use nodes::{node_functions::NodeFunctions, node_manager::NodeManager, node_trait::RNode};
 use rendering::render_context::Context;
 
 // Game developer uses this callback to build their game.
 type BuildCallback = fn(&mut World) -> bool;
+// Called with the asset's path when a watched asset file changes.
+pub type AssetCallback = fn(&str, &mut World);
 pub type RCCanvas = Rc<RefCell<WindowCanvas>>;
 
 pub struct GlobalData {
@@ -37,14 +58,37 @@ pub struct GlobalData {
     // Mouse-space is synonymous with window/device space.
     pub mouse: (i32, i32), // (x,y)
     pub mouse_changed: bool,
+    // Buttons held down and drag tracking
+    mouse_state: RMouseState,
 
     // View-space coordinates
     pub view: (f64, f64),
     // node-space coordinates
     // node: (f64, f64),
 
-    // Event targets
-    io_event_targets: Vec<RNode>,
+    // Event targets and the event types each opted in to
+    io_event_targets: Vec<(RNode, Vec<IOEvent>)>,
+
+    // Node-to-node messaging
+    event_bus: REventBus,
+
+    // Keys currently held down
+    keyboard: RKeyboardState,
+
+    // Named actions bound to the keyboard and mouse
+    actions: RActionMap,
+
+    // Images cached by path
+    textures: RTextureManager,
+    // Fonts cached by path
+    fonts: RFontManager,
+
+    // Ids of nodes attached since the last frame. NodeManager stages them
+    // if their parent is already on stage.
+    attached: RefCell<Vec<usize>>,
+    // Ids of nodes whose components changed since the last frame.
+    // NodeManager registers or unregisters them as timing targets.
+    timing_changed: RefCell<Vec<usize>>,
 
     // Collect all nodes in the system for fast access
     node_pool: HashMap<usize, RNode>,
@@ -65,6 +109,10 @@ impl Drop for GlobalData {
 
 impl GlobalData {
     fn new() -> Self {
+        let keyboard = KeyboardState::new();
+        let mouse_state = MouseState::new();
+        let actions = ActionMap::new(keyboard.clone(), mouse_state.clone());
+
         Self {
             window_width: 0,
             window_height: 0,
@@ -80,9 +128,21 @@ impl GlobalData {
             view: (0.0, 0.0),
             // node: (0.0, 0.0),
             mouse_changed: false,
+            mouse_state,
 
             io_event_targets: Vec::new(),
 
+            event_bus: EventBus::new(),
+
+            keyboard,
+            actions,
+
+            textures: TextureManager::new(),
+            fonts: FontManager::new(),
+
+            attached: RefCell::new(Vec::new()),
+            timing_changed: RefCell::new(Vec::new()),
+
             node_pool: HashMap::new(),
         }
     }
@@ -121,6 +181,10 @@ impl GlobalData {
         self.node_pool.len()
     }
 
+    pub fn nodes(&self) -> impl Iterator<Item = &RNode> {
+        self.node_pool.values()
+    }
+
     pub fn print_pool(&self) {
         println!("__~__~__~__~__~__~ NODE POOL __~__~__~__~__~__~__~__~");
         for (k, v) in self.node_pool.iter() {
@@ -129,13 +193,88 @@ impl GlobalData {
         println!("__~__~__~__~__~__~__~__~__~__~__~__~__~__~__~__~");
     }
 
-    pub fn register_io_event_targets(&mut self, node: RNode) {
-        self.io_event_targets.push(node);
+    /// The node receives `events` types as they propagate along its path.
+    /// Registering again adds to the node's types.
+    pub fn register_io_event_target(&mut self, node: RNode, events: &[IOEvent]) {
+        let id = node.borrow().id();
+
+        match self
+            .io_event_targets
+            .iter_mut()
+            .find(|(n, _)| n.borrow().id() == id)
+        {
+            Some((_, types)) => {
+                for event in events.iter() {
+                    if !types.contains(event) {
+                        types.push(*event);
+                    }
+                }
+            }
+            None => self.io_event_targets.push((node, events.to_vec())),
+        }
+    }
+
+    pub fn unregister_io_event_target(&mut self, node_id: usize) {
+        self.io_event_targets
+            .retain(|(n, _)| n.borrow().id() != node_id);
+    }
+
+    pub fn is_io_event_target(&self, node_id: usize, event: IOEvent) -> bool {
+        self.io_event_targets
+            .iter()
+            .any(|(n, types)| types.contains(&event) && n.borrow().id() == node_id)
     }
 
     pub fn io_event_targets_count(&self) -> usize {
         self.io_event_targets.len()
     }
+
+    pub fn queue_attached(&self, node_id: usize) {
+        self.attached.borrow_mut().push(node_id);
+    }
+
+    pub fn take_attached(&self) -> Vec<usize> {
+        std::mem::take(&mut *self.attached.borrow_mut())
+    }
+
+    pub fn queue_timing_change(&self, node_id: usize) {
+        self.timing_changed.borrow_mut().push(node_id);
+    }
+
+    pub fn take_timing_changes(&self) -> Vec<usize> {
+        std::mem::take(&mut *self.timing_changed.borrow_mut())
+    }
+
+    /// Nodes clone this handle if they need to post events outside of
+    /// callbacks that receive GlobalData, for example, during update().
+    pub fn event_bus(&self) -> &REventBus {
+        &self.event_bus
+    }
+
+    /// Nodes clone this handle to poll keys during update().
+    pub fn keyboard(&self) -> &RKeyboardState {
+        &self.keyboard
+    }
+
+    /// Nodes clone this handle to poll mouse buttons during update().
+    pub fn mouse_state(&self) -> &RMouseState {
+        &self.mouse_state
+    }
+
+    /// Nodes clone this handle to query actions during update().
+    pub fn actions(&self) -> &RActionMap {
+        &self.actions
+    }
+
+    /// Nodes clone this handle to load images after construction.
+    pub fn textures(&self) -> &RTextureManager {
+        &self.textures
+    }
+
+    /// Nodes clone this handle to load fonts after construction.
+    pub fn fonts(&self) -> &RFontManager {
+        &self.fonts
+    }
 }
 
 const SECOND: u32 = 1000000000; // billion ns in a second
@@ -154,6 +293,14 @@ const FRAME_PERIOD: f64 = 1_000_000_000.0 / FRAMES_PER_SECOND as f64; // in nano
 
 const UPDATE_PERIOD: f64 = 1_000_000_000.0 / UPDATES_PER_SECOND as f64; // in nanoseconds
 
+// A scene description file bound to the subtree it was loaded into.
+struct WatchedScene {
+    path: PathBuf,
+    root: usize,
+    // Ids of the nodes the file described on its last load
+    described: HashSet<usize>,
+}
+
 /// Ranger is the main object hosting your game. You construct [Scene]s and give them to Ranger
 /// for execution. When the last Scene exits the game comes to an end.
 // #[derive(Clone)]
@@ -162,6 +309,20 @@ pub struct World {
 
     node_manager: NodeManager,
 
+    inspector: Inspector,
+
+    // Optional remote debug console, see enable_debug_console.
+    console: Option<Console>,
+
+    // Hot reloading
+    node_factory: NodeFactory,
+    file_watcher: FileWatcher,
+    watched_scenes: Vec<WatchedScene>,
+    watched_assets: Vec<(PathBuf, AssetCallback)>,
+
+    // Work spread over frames while a loading scene runs
+    loader: Option<Loader>,
+
     context: Sdl,
     config: String,
 
@@ -241,6 +402,13 @@ impl World {
             data: data,
             context: sdl_context,
             node_manager: man,
+            inspector: Inspector::new(),
+            console: None,
+            node_factory: NodeFactory::default(),
+            file_watcher: FileWatcher::new(),
+            watched_scenes: Vec::new(),
+            watched_assets: Vec::new(),
+            loader: None,
             config: config.to_string(),
             id: 0,
         };
@@ -253,14 +421,59 @@ impl World {
         self.id
     }
 
-    /// Configure using config json
+    /// Configure using config json. A missing config file isn't an error,
+    /// the defaults are used.
     pub fn configure(&mut self) -> Result<String, String> {
         println!("Using config: {}", self.config);
 
-        // Err(String::from("kaboom"))
+        let config = match self.read_config()? {
+            Some(config) => config,
+            None => return Ok(String::from("Configured")),
+        };
+
+        if !config["actions"].is_null() {
+            self.data
+                .actions
+                .borrow_mut()
+                .load_json(&config["actions"])
+                .map_err(|err| format!("{}: {}", self.config, err))?;
+        }
+
         Ok(String::from("Configured"))
     }
 
+    /// Writes the current action bindings to the config file. Other
+    /// settings in the file are kept.
+    pub fn save_config(&self) -> Result<(), String> {
+        let mut config = match self.read_config()? {
+            Some(serde_json::Value::Object(config)) => config,
+            _ => serde_json::Map::new(),
+        };
+
+        config.insert("actions".to_string(), self.data.actions.borrow().to_json());
+
+        let text = match serde_json::to_string_pretty(&config) {
+            Ok(text) => text,
+            Err(err) => return Err(err.to_string()),
+        };
+
+        fs::write(&self.config, text).map_err(|err| format!("{}: {}", self.config, err))
+    }
+
+    fn read_config(&self) -> Result<Option<serde_json::Value>, String> {
+        if !Path::new(&self.config).exists() {
+            return Ok(None);
+        }
+
+        let text =
+            fs::read_to_string(&self.config).map_err(|err| format!("{}: {}", self.config, err))?;
+
+        match serde_json::from_str(&text) {
+            Ok(config) => Ok(Some(config)),
+            Err(err) => Err(format!("{}: {}", self.config, err)),
+        }
+    }
+
     pub fn launch(&mut self, build: BuildCallback) -> Result<String, String> {
         // Perform pre-build of underlying Systems (SceneManager, Scheduler, TweenManager...)
         println!("Constructing and/or initializing Systems...");
@@ -301,6 +514,184 @@ impl World {
         self.node_manager.push_node(scene);
     }
 
+    /// See NodeManager::push_overlay
+    pub fn push_overlay(&self, node: RNode, pause_below: bool) {
+        self.node_manager.push_overlay(node, pause_below);
+    }
+
+    pub fn pop_overlay(&mut self) -> Option<RNode> {
+        self.node_manager.pop_overlay(&mut self.data)
+    }
+
+    /// Gives `node` keyboard focus, None clears the focus.
+    pub fn set_focus(&mut self, node: Option<&RNode>) {
+        self.node_manager.set_focus(node, &self.data);
+    }
+
+    /// Exits and releases `node` and its subtree.
+    pub fn destroy_node(&mut self, node: &RNode) {
+        self.node_manager.destroy_node(node, &mut self.data);
+    }
+
+    // ---------------------------------------------------------------
+    // Loading
+    // ---------------------------------------------------------------
+    /// Runs `loader` a slice at a time, once per frame, until it completes.
+    /// Pair it with a SceneLoading scene to show progress.
+    pub fn start_loading(&mut self, loader: Loader) {
+        if self.loader.is_some() {
+            println!("start_loading: replacing an unfinished loader");
+        }
+        self.loader = Some(loader);
+    }
+
+    fn run_loader(&mut self) {
+        // The loader is taken out while it runs because its steps need the World.
+        if let Some(mut loader) = self.loader.take() {
+            loader.run(self);
+            if !loader.is_complete() {
+                // A step may have started another loader.
+                if self.loader.is_none() {
+                    self.loader = Some(loader);
+                }
+            }
+        }
+    }
+
+    // ---------------------------------------------------------------
+    // Scene descriptions and hot reloading
+    // ---------------------------------------------------------------
+    pub fn node_factory(&self) -> &NodeFactory {
+        &self.node_factory
+    }
+
+    /// Makes `type_name` available to the "type" field of scene descriptions.
+    pub fn register_node_type(&mut self, type_name: &str, builder: NodeBuilder) {
+        self.node_factory.register(type_name, builder);
+    }
+
+    /// Applies the scene description at `path` to the subtree at `root`,
+    /// then watches the file and re-applies it whenever it changes.
+    /// See assets::scene_loader for the format.
+    pub fn load_scene(&mut self, path: &str, root: &RNode) -> Result<(), String> {
+        let desc = NodeDescription::from_file(path)?;
+        let described = SceneLoader::reconcile(root, &desc, &HashSet::new(), self)?;
+
+        let path = PathBuf::from(path);
+        self.file_watcher.watch(&path);
+        self.watched_scenes.retain(|scene| scene.path != path);
+        self.watched_scenes.push(WatchedScene {
+            path,
+            root: root.borrow().id(),
+            described,
+        });
+
+        Ok(())
+    }
+
+    /// Calls `on_change` whenever the file at `path` changes.
+    pub fn watch_asset(&mut self, path: &str, on_change: AssetCallback) {
+        let path = PathBuf::from(path);
+        self.file_watcher.watch(&path);
+        self.watched_assets.push((path, on_change));
+    }
+
+    fn reload_changed_files(&mut self) {
+        for path in self.file_watcher.poll() {
+            println!("Reloading: {}", path.display());
+
+            if let Some(index) = self.watched_scenes.iter().position(|s| s.path == path) {
+                self.reload_scene(index);
+            }
+
+            let callbacks: Vec<AssetCallback> = self
+                .watched_assets
+                .iter()
+                .filter(|(asset, _)| *asset == path)
+                .map(|(_, on_change)| *on_change)
+                .collect();
+
+            for on_change in callbacks {
+                on_change(&path.to_string_lossy(), self);
+            }
+        }
+    }
+
+    fn reload_scene(&mut self, index: usize) {
+        let root_id = self.watched_scenes[index].root;
+        let root = match self.data.find_node(&root_id) {
+            Some(root) => root.clone(),
+            None => {
+                // The subtree was destroyed, nothing left to rebuild.
+                let scene = self.watched_scenes.remove(index);
+                self.unwatch_if_unused(&scene.path);
+                return;
+            }
+        };
+
+        let path = self.watched_scenes[index].path.clone();
+        let previous = std::mem::take(&mut self.watched_scenes[index].described);
+
+        let result = NodeDescription::from_file(&path.to_string_lossy())
+            .and_then(|desc| SceneLoader::reconcile(&root, &desc, &previous, self));
+
+        self.watched_scenes[index].described = match result {
+            Ok(described) => described,
+            Err(err) => {
+                // Keep the current tree so the file can be fixed and saved again.
+                println!("Reload of '{}' failed: {}", path.display(), err);
+                previous
+            }
+        };
+    }
+
+    fn unwatch_if_unused(&mut self, path: &Path) {
+        let used = self.watched_scenes.iter().any(|scene| scene.path == path)
+            || self.watched_assets.iter().any(|(asset, _)| asset == path);
+        if !used {
+            self.file_watcher.unwatch(path);
+        }
+    }
+
+    // ---------------------------------------------------------------
+    // Debugging
+    // ---------------------------------------------------------------
+    /// Starts a text command server on 127.0.0.1:`port`. It is only
+    /// reachable from the local machine. See debug::console for commands.
+    pub fn enable_debug_console(&mut self, port: u16) -> Result<(), String> {
+        match Console::new(port) {
+            Ok(console) => {
+                self.console = Some(console);
+                Ok(())
+            }
+            Err(err) => Err(err.to_string()),
+        }
+    }
+
+    fn mouse_data(
+        &self,
+        action: MouseAction,
+        button: Option<MouseButton>,
+        clicks: u8,
+    ) -> MouseData {
+        // Mouse events don't carry modifiers, the latest key event does.
+        let mut mouse = MouseData::new(action, self.data.keyboard.borrow().modifiers());
+        mouse.button = button;
+        mouse.clicks = clicks;
+        mouse
+    }
+
+    fn mouse_event(&mut self, x: i32, y: i32, mouse: MouseData) {
+        self.node_manager
+            .io_event(IOEventData::new_mouse_action(x, y, mouse), &mut self.data);
+    }
+
+    fn drag_event(&mut self, x: i32, y: i32, (action, button, origin): Drag) {
+        let mut mouse = self.mouse_data(action, Some(button), 0);
+        mouse.drag_origin = origin;
+        self.mouse_event(x, y, mouse);
+    }
+
     pub fn core_loop(&mut self) -> Result<String, String> {
         let update_period = Duration::new(0, UPDATE_PERIOD.round() as u32);
         let ns_per_update = update_period.subsec_nanos();
@@ -328,8 +719,6 @@ impl World {
 
         let mut previous_t = Instant::now();
 
-        let mut keycode = Keycode::Clear;
-
         let mut event_pump = match self.context.event_pump() {
             Ok(pump) => pump,
             Err(err) => return Err(err),
@@ -349,28 +738,66 @@ impl World {
                         .. // don't-care about other fields
                     } => break 'fast,
                     Event::KeyDown {
-                        keycode: Some(Keycode::Space),
-                        repeat: false,
+                        keycode,
+                        scancode,
+                        keymod,
+                        repeat,
                         ..
                     } => {
-                        // Do something
+                        self.data.keyboard.borrow_mut().key_down(keycode, scancode, keymod);
+                        let action = if repeat { KeyAction::Repeat } else { KeyAction::Down };
+                        self.node_manager.io_event(
+                            IOEventData::new_key_event(action, keycode, scancode, keymod),
+                            &mut self.data,
+                        );
+
+                        // Inspector hotkeys, after the key has been routed so
+                        // the keyboard state stays in step with KeyUp.
+                        match keycode {
+                            Some(Keycode::F12) if !repeat => self.inspector.toggle(),
+                            Some(Keycode::PageDown) if self.inspector.is_visible() => {
+                                self.inspector.select_next()
+                            }
+                            Some(Keycode::PageUp) if self.inspector.is_visible() => {
+                                self.inspector.select_previous()
+                            }
+                            _ => (),
+                        }
                     }
-                    Event::KeyDown {
-                        keycode: Some(Keycode::Right),
+                    Event::KeyUp {
+                        keycode,
+                        scancode,
+                        keymod,
                         ..
                     } => {
-                        keycode = Keycode::Right;
+                        self.data.keyboard.borrow_mut().key_up(keycode, scancode, keymod);
+                        self.node_manager.io_event(
+                            IOEventData::new_key_event(KeyAction::Up, keycode, scancode, keymod),
+                            &mut self.data,
+                        );
                     }
-                    Event::KeyDown {
-                        keycode: Some(Keycode::Left),
+                    Event::TextInput { text, .. } => {
+                        self.node_manager
+                            .io_event(IOEventData::new_text_input(text), &mut self.data);
+                    }
+                    Event::TextEditing {
+                        text,
+                        start,
+                        length,
                         ..
                     } => {
-                        keycode = Keycode::Left;
+                        self.node_manager.io_event(
+                            IOEventData::new_text_editing(text, start, length),
+                            &mut self.data,
+                        );
                     }
-                    Event::KeyUp {
+                    Event::Window {
+                        win_event: WindowEvent::FocusLost,
                         ..
                     } => {
-                        keycode = Keycode::Clear;
+                        // Key up events for held keys go to the focused window.
+                        self.data.keyboard.borrow_mut().clear();
+                        self.data.mouse_state.borrow_mut().clear();
                     }
                     Event::MouseMotion {
                         x,
@@ -378,16 +805,62 @@ impl World {
                         ..
                     } => {
                         // println!("mouse {},{}", x, y);
-                        self.node_manager.io_event(IOEventData::new_mouse_event(x,y), &mut self.data);
+                        let mouse = self.mouse_data(MouseAction::Move, None, 0);
+                        self.mouse_event(x, y, mouse);
+
+                        let drag = self.data.mouse_state.borrow_mut().motion(x, y);
+                        if let Some(drag) = drag {
+                            self.drag_event(x, y, drag);
+                        }
+                    }
+                    Event::MouseButtonDown {
+                        mouse_btn,
+                        clicks,
+                        x,
+                        y,
+                        ..
+                    } => {
+                        self.data.mouse_state.borrow_mut().button_down(mouse_btn, x, y);
+                        let mouse = self.mouse_data(MouseAction::ButtonDown, Some(mouse_btn), clicks);
+                        self.mouse_event(x, y, mouse);
+                        if clicks == 2 {
+                            let mouse = self.mouse_data(MouseAction::DoubleClick, Some(mouse_btn), clicks);
+                            self.mouse_event(x, y, mouse);
+                        }
+                    }
+                    Event::MouseButtonUp {
+                        mouse_btn,
+                        clicks,
+                        x,
+                        y,
+                        ..
+                    } => {
+                        let drag = self.data.mouse_state.borrow_mut().button_up(mouse_btn);
+                        let mouse = self.mouse_data(MouseAction::ButtonUp, Some(mouse_btn), clicks);
+                        self.mouse_event(x, y, mouse);
+                        if let Some(drag) = drag {
+                            self.drag_event(x, y, drag);
+                        }
+                    }
+                    Event::MouseWheel {
+                        x,
+                        y,
+                        direction,
+                        ..
+                    } => {
+                        let wheel = match direction {
+                            MouseWheelDirection::Flipped => (-x, -y),
+                            _ => (x, y),
+                        };
+                        let mut mouse = self.mouse_data(MouseAction::Wheel, None, 0);
+                        mouse.wheel = wheel;
+                        let (mx, my) = self.data.mouse;
+                        self.mouse_event(mx, my, mouse);
                     }
                     _ => {}
                 }
             }
 
-            if keycode != Keycode::Clear {
-                println!("key: {}", keycode);
-            }
-
             // println!("================================================");
 
             // ##############################################################
@@ -402,6 +875,8 @@ impl World {
             'up: loop {
                 if lag >= ns_per_update {
                     self.node_manager.update(frame_dt);
+                    // Pressed/released actions are seen by one update.
+                    self.data.actions.borrow_mut().end_update();
                     lag -= ns_per_update;
                     ups_cnt += 1;
                 } else {
@@ -410,6 +885,19 @@ impl World {
                 }
             }
 
+            // All updates are complete which makes this a safe point to
+            // deliver any events queued during the updates.
+            self.node_manager.dispatch_events(&self.data);
+
+            // Remote commands are applied at the same safe point.
+            if let Some(console) = self.console.as_mut() {
+                console.poll(&self.node_manager, &mut self.data);
+            }
+
+            self.reload_changed_files();
+
+            self.run_loader();
+
             // ::std::thread::sleep(Duration::from_millis(15)); // force/test pipeline overload
 
             let un = Instant::now().duration_since(u);
@@ -446,6 +934,10 @@ impl World {
 
                 NodeFunctions::render_coordinates(self.node_manager.context_mut(), &self.data);
 
+                let running = self.node_manager.running_node();
+                self.inspector
+                    .draw(&running, self.node_manager.context_mut(), &self.data);
+
                 pn = Instant::now().duration_since(p);
                 // println!("render time: {}", pn.subsec_micros());
                 proc_accum += pn.subsec_nanos();